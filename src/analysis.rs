@@ -0,0 +1,548 @@
+//! Detecting subschemas that no instance can satisfy.
+use crate::{
+    paths::{JSONPointer, PathChunk},
+    primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
+    schemas::{subschemas, Draft, Position},
+};
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// A problem in the schema that does not prevent its compilation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompilationWarning {
+    /// Location of the subschema in the schema.
+    pub schema_path: JSONPointer,
+    /// Human-readable description of the problem.
+    pub message: String,
+}
+
+impl fmt::Display for CompilationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.schema_path.is_empty() {
+            f.write_str(&self.message)
+        } else {
+            write!(f, "{}: {}", self.schema_path, self.message)
+        }
+    }
+}
+
+/// Detect subschemas that no instance can satisfy.
+///
+/// The analysis is conservative: it relies on `type`, numeric limits, lengths, sizes,
+/// `required`, `enum` / `const` and their combinations via `allOf`, `anyOf` and `oneOf`.
+/// `false` and subschemas with `$ref` are not reported.
+pub(crate) fn analyze(draft: Draft, schema: &Value) -> Vec<CompilationWarning> {
+    let mut analysis = Analysis {
+        draft,
+        path: vec![],
+        warnings: vec![],
+    };
+    analysis.analyze(schema);
+    analysis.warnings
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Bound {
+    value: f64,
+    is_exclusive: bool,
+}
+
+/// The stricter of two bounds, `is_lower` tells which direction is stricter.
+fn stricter(left: Option<Bound>, right: Option<Bound>, is_lower: bool) -> Option<Bound> {
+    match (left, right) {
+        (Some(left), Some(right)) if left.value == right.value => Some(Bound {
+            value: left.value,
+            is_exclusive: left.is_exclusive || right.is_exclusive,
+        }),
+        (Some(left), Some(right)) => {
+            if (left.value > right.value) == is_lower {
+                Some(left)
+            } else {
+                Some(right)
+            }
+        }
+        (bound, None) | (None, bound) => bound,
+    }
+}
+
+/// Inclusive limits on the size of strings, arrays or objects.
+#[derive(Debug, Clone, Copy)]
+struct Size {
+    min: u64,
+    max: Option<u64>,
+}
+
+impl Size {
+    const ANY: Size = Size { min: 0, max: None };
+
+    fn intersect(&mut self, other: Size) {
+        self.min = self.min.max(other.min);
+        self.max = match (self.max, other.max) {
+            (Some(left), Some(right)) => Some(left.min(right)),
+            (max, None) | (None, max) => max,
+        };
+    }
+
+    fn is_empty(self) -> bool {
+        matches!(self.max, Some(max) if max < self.min)
+    }
+
+    fn contains(self, size: usize) -> bool {
+        size as u64 >= self.min && !matches!(self.max, Some(max) if size as u64 > max)
+    }
+}
+
+const ALL_TYPES: [PrimitiveType; 7] = [
+    PrimitiveType::Array,
+    PrimitiveType::Boolean,
+    PrimitiveType::Integer,
+    PrimitiveType::Null,
+    PrimitiveType::Number,
+    PrimitiveType::Object,
+    PrimitiveType::String,
+];
+
+/// What is known about instances that satisfy a subschema.
+#[derive(Debug, Clone)]
+struct Summary {
+    /// Types allowed by `type`. `Number` stands for numbers that are not integers.
+    types: PrimitiveTypesBitMap,
+    /// Types that instances may have once all other keywords are taken into account.
+    feasible: PrimitiveTypesBitMap,
+    minimum: Option<Bound>,
+    maximum: Option<Bound>,
+    length: Size,
+    items: Size,
+    properties: Size,
+    required: Vec<String>,
+    /// Possible instances if they are listed via `enum` or `const`.
+    values: Option<Vec<Value>>,
+    /// Whether the subschema is known to be unsatisfiable because of its subschemas.
+    is_unsatisfiable: bool,
+}
+
+impl Summary {
+    fn any() -> Summary {
+        let mut types = PrimitiveTypesBitMap::new();
+        for primitive_type in &ALL_TYPES {
+            types |= *primitive_type;
+        }
+        Summary {
+            types,
+            feasible: types,
+            minimum: None,
+            maximum: None,
+            length: Size::ANY,
+            items: Size::ANY,
+            properties: Size::ANY,
+            required: vec![],
+            values: None,
+            is_unsatisfiable: false,
+        }
+    }
+
+    fn none() -> Summary {
+        Summary {
+            types: PrimitiveTypesBitMap::new(),
+            feasible: PrimitiveTypesBitMap::new(),
+            is_unsatisfiable: true,
+            ..Summary::any()
+        }
+    }
+
+    /// Instances should satisfy both summaries.
+    fn intersect(&mut self, other: &Summary) {
+        self.types = self.types.intersection(other.types);
+        self.minimum = stricter(self.minimum, other.minimum, true);
+        self.maximum = stricter(self.maximum, other.maximum, false);
+        self.length.intersect(other.length);
+        self.items.intersect(other.items);
+        self.properties.intersect(other.properties);
+        for name in &other.required {
+            if !self.required.contains(name) {
+                self.required.push(name.clone())
+            }
+        }
+        self.values = match (self.values.take(), &other.values) {
+            (Some(values), Some(others)) => Some(
+                values
+                    .into_iter()
+                    .filter(|value| others.iter().any(|other| is_same(value, other)))
+                    .collect(),
+            ),
+            (Some(values), None) => Some(values),
+            (None, others) => others.clone(),
+        };
+        self.is_unsatisfiable |= other.is_unsatisfiable;
+    }
+
+    /// Whether `value` satisfies the limits.
+    fn allows(&self, value: &Value) -> bool {
+        match value {
+            Value::Null => self.types.contains_type(PrimitiveType::Null),
+            Value::Bool(_) => self.types.contains_type(PrimitiveType::Boolean),
+            Value::Number(number) => {
+                let number = number.as_f64().unwrap_or(0.0);
+                let primitive_type = if number.fract() == 0.0 {
+                    PrimitiveType::Integer
+                } else {
+                    PrimitiveType::Number
+                };
+                let above = |bound: Bound| {
+                    number > bound.value || (!bound.is_exclusive && number == bound.value)
+                };
+                let below = |bound: Bound| {
+                    number < bound.value || (!bound.is_exclusive && number == bound.value)
+                };
+                self.types.contains_type(primitive_type)
+                    && self.minimum.into_iter().all(above)
+                    && self.maximum.into_iter().all(below)
+            }
+            Value::String(string) => {
+                self.types.contains_type(PrimitiveType::String)
+                    && self.length.contains(string.chars().count())
+            }
+            Value::Array(items) => {
+                self.types.contains_type(PrimitiveType::Array) && self.items.contains(items.len())
+            }
+            Value::Object(object) => {
+                self.types.contains_type(PrimitiveType::Object)
+                    && self.properties.contains(object.len())
+                    && self.required.iter().all(|name| object.contains_key(name))
+            }
+        }
+    }
+}
+
+/// Equality that does not distinguish `1` and `1.0`.
+fn is_same(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(left), Value::Number(right)) => left.as_f64() == right.as_f64(),
+        _ => left == right,
+    }
+}
+
+fn types_of(value: &Value) -> PrimitiveTypesBitMap {
+    let mut types = PrimitiveTypesBitMap::new();
+    let mut add = |name: &str| match name {
+        "array" => types |= PrimitiveType::Array,
+        "boolean" => types |= PrimitiveType::Boolean,
+        "integer" => types |= PrimitiveType::Integer,
+        "null" => types |= PrimitiveType::Null,
+        "number" => {
+            types |= PrimitiveType::Integer;
+            types |= PrimitiveType::Number
+        }
+        "object" => types |= PrimitiveType::Object,
+        "string" => types |= PrimitiveType::String,
+        _ => {}
+    };
+    match value {
+        Value::String(name) => add(name),
+        Value::Array(names) => names.iter().filter_map(Value::as_str).for_each(add),
+        _ => {}
+    }
+    types
+}
+
+fn format_bound(bound: Option<Bound>, is_lower: bool) -> String {
+    match bound {
+        Some(bound) => {
+            let operator = match (is_lower, bound.is_exclusive) {
+                (true, true) => ">",
+                (true, false) => ">=",
+                (false, true) => "<",
+                (false, false) => "<=",
+            };
+            format!("{} {}", operator, bound.value)
+        }
+        None => String::new(),
+    }
+}
+
+struct Analysis {
+    draft: Draft,
+    path: Vec<PathChunk>,
+    warnings: Vec<CompilationWarning>,
+}
+
+impl Analysis {
+    fn nested(&mut self, chunks: &[PathChunk], schema: &Value) -> Summary {
+        let depth = self.path.len();
+        self.path.extend_from_slice(chunks);
+        let summary = self.analyze(schema);
+        self.path.truncate(depth);
+        summary
+    }
+
+    /// Summary of the keywords that constrain the instance itself.
+    fn own(&self, object: &Map<String, Value>) -> Summary {
+        let mut summary = Summary::any();
+        let number = |keyword: &str| object.get(keyword).and_then(Value::as_f64);
+        let size = |min: &str, max: &str| Size {
+            min: object.get(min).and_then(Value::as_u64).unwrap_or(0),
+            max: object.get(max).and_then(Value::as_u64),
+        };
+        if let Some(types) = object.get("type") {
+            summary.types = types_of(types);
+        }
+        let limit = |keyword, exclusive: &str| {
+            number(keyword).map(|value| Bound {
+                value,
+                is_exclusive: object.get(exclusive) == Some(&Value::Bool(true)),
+            })
+        };
+        let exclusive = |keyword| {
+            number(keyword).map(|value| Bound {
+                value,
+                is_exclusive: true,
+            })
+        };
+        if self.draft == Draft::Draft4 {
+            summary.minimum = limit("minimum", "exclusiveMinimum");
+            summary.maximum = limit("maximum", "exclusiveMaximum");
+        } else {
+            summary.minimum = stricter(limit("minimum", ""), exclusive("exclusiveMinimum"), true);
+            summary.maximum = stricter(limit("maximum", ""), exclusive("exclusiveMaximum"), false);
+        }
+        summary.length = size("minLength", "maxLength");
+        summary.items = size("minItems", "maxItems");
+        summary.properties = size("minProperties", "maxProperties");
+        if let Some(Value::Array(required)) = object.get("required") {
+            summary.required = required
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect();
+        }
+        if let Some(Value::Array(values)) = object.get("enum") {
+            summary.values = Some(values.clone());
+        }
+        if self.draft != Draft::Draft4 {
+            if let Some(value) = object.get("const") {
+                summary.intersect(&Summary {
+                    values: Some(vec![value.clone()]),
+                    ..Summary::any()
+                });
+            }
+        }
+        summary
+    }
+
+    fn analyze(&mut self, schema: &Value) -> Summary {
+        let object = match schema {
+            Value::Object(object) => object,
+            Value::Bool(false) => return Summary::none(),
+            _ => return Summary::any(),
+        };
+        // Keywords next to `$ref` are ignored and references may be recursive
+        if object.contains_key("$ref") {
+            return Summary::any();
+        }
+        let mut summary = self.own(object);
+        let mut unsatisfiable_properties = vec![];
+        let mut is_contains_unsatisfiable = false;
+        // Types that instances may have in any branch of `anyOf` and `oneOf`
+        let mut any_of = None;
+        let mut one_of = None;
+        for (keyword, position, subschema) in subschemas(object) {
+            if keyword == "contains" && self.draft == Draft::Draft4 {
+                continue;
+            }
+            let child = self.nested(&position.path(keyword), subschema);
+            match (keyword, position) {
+                ("allOf", _) => summary.intersect(&child),
+                ("anyOf", _) => {
+                    any_of = Some(
+                        child
+                            .feasible
+                            .union(any_of.unwrap_or(PrimitiveTypesBitMap::new())),
+                    );
+                }
+                ("oneOf", _) => {
+                    one_of = Some(
+                        child
+                            .feasible
+                            .union(one_of.unwrap_or(PrimitiveTypesBitMap::new())),
+                    );
+                }
+                ("properties", Position::Name(name)) if child.feasible.is_empty() => {
+                    unsatisfiable_properties.push(name.to_string())
+                }
+                ("contains", _) => {
+                    summary.items.intersect(Size { min: 1, max: None });
+                    is_contains_unsatisfiable = child.feasible.is_empty();
+                }
+                _ => {}
+            }
+        }
+        for types in any_of.into_iter().chain(one_of) {
+            if types.is_empty() {
+                // Every branch is reported separately
+                summary.is_unsatisfiable = true;
+            }
+            summary.types = summary.types.intersection(types);
+        }
+        if summary.is_unsatisfiable {
+            summary.feasible = PrimitiveTypesBitMap::new();
+            return summary;
+        }
+        // Limits are checked on `types` and the result is stored in `feasible`, hence parent
+        // schemas check the combined limits again and report them
+        let declared = summary.types;
+        let mut reasons = vec![];
+        let mut remove = |summary: &mut Summary, primitive_type, reason: String| {
+            if summary.types.contains_type(primitive_type) {
+                summary.types = summary.types.remove_type(primitive_type);
+                if !reasons.contains(&reason) {
+                    reasons.push(reason)
+                }
+            }
+        };
+        if let (Some(minimum), Some(maximum)) = (summary.minimum, summary.maximum) {
+            let bounds = format!(
+                "{} and {}",
+                format_bound(summary.minimum, true),
+                format_bound(summary.maximum, false)
+            );
+            if minimum.value > maximum.value
+                || (minimum.value == maximum.value
+                    && (minimum.is_exclusive || maximum.is_exclusive))
+            {
+                let reason = format!("no number is {}", bounds);
+                remove(&mut summary, PrimitiveType::Number, reason.clone());
+                remove(&mut summary, PrimitiveType::Integer, reason);
+            } else {
+                let lowest = if minimum.is_exclusive {
+                    minimum.value.floor() + 1.0
+                } else {
+                    minimum.value.ceil()
+                };
+                let highest = if maximum.is_exclusive {
+                    maximum.value.ceil() - 1.0
+                } else {
+                    maximum.value.floor()
+                };
+                if lowest > highest {
+                    remove(
+                        &mut summary,
+                        PrimitiveType::Integer,
+                        format!("no integer is {}", bounds),
+                    );
+                }
+            }
+        }
+        if summary.length.is_empty() {
+            let reason = format!(
+                "no string is at least {} and at most {} characters long",
+                summary.length.min,
+                summary.length.max.unwrap_or_default()
+            );
+            remove(&mut summary, PrimitiveType::String, reason);
+        }
+        if summary.items.is_empty() {
+            let reason = format!(
+                "no array has at least {} and at most {} items",
+                summary.items.min,
+                summary.items.max.unwrap_or_default()
+            );
+            remove(&mut summary, PrimitiveType::Array, reason);
+        }
+        if is_contains_unsatisfiable {
+            let reason = "no array item satisfies `contains`".to_string();
+            remove(&mut summary, PrimitiveType::Array, reason);
+        }
+        let mut properties = summary.properties;
+        properties.intersect(Size {
+            min: summary.required.len() as u64,
+            max: None,
+        });
+        if properties.is_empty() {
+            let reason = format!(
+                "no object has at least {} and at most {} properties",
+                properties.min,
+                properties.max.unwrap_or_default()
+            );
+            remove(&mut summary, PrimitiveType::Object, reason);
+        }
+        for name in &unsatisfiable_properties {
+            if summary.required.contains(name) {
+                let reason = format!("required property `{}` can't be satisfied", name);
+                remove(&mut summary, PrimitiveType::Object, reason);
+            }
+        }
+        let remaining_types = summary.types;
+        if let Some(values) = summary.values.take() {
+            let allowed: Vec<_> = values
+                .iter()
+                .filter(|value| summary.allows(value))
+                .cloned()
+                .collect();
+            if allowed.is_empty() {
+                summary.types = PrimitiveTypesBitMap::new();
+                if values.is_empty() {
+                    reasons.push("`enum` and `const` have no values in common".to_string())
+                } else if !remaining_types.is_empty() {
+                    reasons.push("no `enum` or `const` value satisfies other keywords".to_string())
+                }
+            }
+            summary.values = Some(allowed);
+        }
+        if summary.types.is_empty() {
+            let message = if reasons.is_empty() {
+                "no type satisfies all `type` constraints".to_string()
+            } else {
+                reasons.join("; ")
+            };
+            self.warnings.push(CompilationWarning {
+                schema_path: JSONPointer::from(self.path.clone()),
+                message: format!("The schema can't be satisfied: {}", message),
+            });
+            summary.is_unsatisfiable = true;
+        }
+        summary.feasible = summary.types;
+        summary.types = declared;
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::JSONSchema;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(&json!({"type": "string", "minLength": 5, "maxLength": 2}), &[("", "no string is at least 5 and at most 2 characters long")]; "lengths")]
+    #[test_case(&json!({"minLength": 5, "maxLength": 2}), &[]; "other types")]
+    #[test_case(&json!({"allOf": [{"type": "integer"}, {"type": "string"}]}), &[("", "no type satisfies all `type` constraints")]; "disjoint types")]
+    #[test_case(&json!({"type": "number", "allOf": [{"type": "integer"}, {"minimum": 1.2, "maximum": 1.8}]}), &[("", "no integer is >= 1.2 and <= 1.8")]; "integer bounds")]
+    #[test_case(&json!({"properties": {"a": {"type": "number", "exclusiveMinimum": 3, "maximum": 3}}}), &[("/properties/a", "no number is > 3 and <= 3")]; "exclusive bounds")]
+    #[test_case(&json!({"$schema": "http://json-schema.org/draft-04/schema#", "type": "number", "minimum": 3, "maximum": 3, "exclusiveMinimum": true}), &[("", "no number is > 3 and <= 3")]; "draft 4 bounds")]
+    #[test_case(&json!({"type": "number", "minimum": 3, "maximum": 3}), &[]; "single number")]
+    #[test_case(&json!({"enum": ["a", 1], "type": "boolean"}), &[("", "no `enum` or `const` value satisfies other keywords")]; "enum values")]
+    #[test_case(&json!({"allOf": [{"enum": ["a", "b"]}, {"const": "c"}]}), &[("", "`enum` and `const` have no values in common")]; "enum intersection")]
+    #[test_case(&json!({"enum": ["abc", 1], "maxLength": 2, "maximum": 0}), &[("", "no `enum` or `const` value satisfies other keywords")]; "enum values and limits")]
+    #[test_case(&json!({"type": "object", "required": ["a", "b"], "maxProperties": 1}), &[("", "no object has at least 2 and at most 1 properties")]; "required properties")]
+    #[test_case(&json!({"type": "object", "required": ["a"], "properties": {"a": {"allOf": [{"type": "null"}, {"type": "array"}]}}}), &[("/properties/a", "no type satisfies all `type` constraints"), ("", "required property `a` can't be satisfied")]; "unsatisfiable required property")]
+    #[test_case(&json!({"type": "string", "anyOf": [{"type": "integer"}, {"type": "null"}]}), &[("", "no type satisfies all `type` constraints")]; "any of")]
+    #[test_case(&json!({"allOf": [{"type": "string", "minLength": 3, "maxLength": 1}, {}]}), &[("/allOf/0", "no string is at least 3 and at most 1 characters long")]; "reported once")]
+    #[test_case(&json!({"properties": {"a": false}, "not": {}, "items": {"$ref": "#/definitions/a", "type": "string", "minLength": 3, "maxLength": 1}}), &[]; "intentionally unsatisfiable")]
+    fn warnings(schema: &Value, expected: &[(&str, &str)]) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        let warnings: Vec<_> = compiled
+            .warnings()
+            .iter()
+            .map(|warning| (warning.schema_path.to_string(), warning.message.clone()))
+            .collect();
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|(path, message)| {
+                (
+                    (*path).to_string(),
+                    format!("The schema can't be satisfied: {}", message),
+                )
+            })
+            .collect();
+        assert_eq!(warnings, expected);
+    }
+}
@@ -0,0 +1,275 @@
+//! Bundling a schema together with all documents it references.
+use crate::{
+    compilation::{JSONSchema, DEFAULT_ROOT_URL},
+    error::ValidationError,
+    resolver::find_schemas,
+    schemas::{id_of, subschemas_mut, Draft},
+    subschemas::Result,
+};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use url::Url;
+
+/// The keyword that holds reusable schemas in `draft`.
+/// Draft 2019-09 renamed it to `$defs`, but all supported drafts use `definitions`.
+#[inline]
+const fn definitions_keyword(draft: Draft) -> &'static str {
+    match draft {
+        Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => "definitions",
+    }
+}
+
+struct Bundler<'c, 'a> {
+    compiled: &'c JSONSchema<'a>,
+    draft: Draft,
+    /// Canonical URLs of loaded documents by the URLs they were loaded from
+    canonical: HashMap<String, Url>,
+    /// Loaded documents that are not processed yet
+    pending: Vec<(Url, Value)>,
+    /// Processed documents
+    documents: Vec<(Url, Value)>,
+}
+
+impl Bundler<'_, '_> {
+    /// Whether `resource` is the root document or is embedded in it.
+    fn is_internal(&self, resource: &Url) -> bool {
+        let mut scope = self.compiled.context.scope.clone().into_owned();
+        scope.set_fragment(None);
+        resource.as_str() == DEFAULT_ROOT_URL
+            || *resource == scope
+            || self.compiled.resolver.is_embedded(resource)
+    }
+
+    /// Load the document under `resource` and return its canonical URL.
+    fn load(&mut self, resource: &Url) -> Result<Url> {
+        if let Some(canonical) = self.canonical.get(resource.as_str()) {
+            return Ok(canonical.clone());
+        }
+        let mut document = self
+            .compiled
+            .resolver
+            .resolve_url(resource, self.compiled.schema)
+            .map_err(ValidationError::into_owned)?
+            .into_owned();
+        let canonical = match id_of(self.draft, &document) {
+            Some(id) => resource.join(id)?,
+            None => resource.clone(),
+        };
+        if let Value::Object(object) = &mut document {
+            // Relative identifiers would be resolved against the root document after bundling
+            let keyword = if self.draft == Draft::Draft4 {
+                "id"
+            } else {
+                "$id"
+            };
+            object.insert(keyword.to_string(), Value::String(canonical.to_string()));
+        }
+        // Subschemas with their own `$id` inside the loaded document
+        let mut embedded = vec![];
+        find_schemas(self.draft, &document, &canonical, &mut |id, _| {
+            embedded.push(id);
+            None
+        })?;
+        for id in embedded {
+            let id = Url::parse(&id)?;
+            if id.fragment().is_none() {
+                self.canonical.insert(id.to_string(), id);
+            }
+        }
+        self.canonical
+            .insert(resource.to_string(), canonical.clone());
+        self.canonical
+            .insert(canonical.to_string(), canonical.clone());
+        self.pending.push((canonical.clone(), document));
+        Ok(canonical)
+    }
+
+    /// Point external references in `schema` to canonical URLs of loaded documents.
+    /// References within `document` itself are kept as they are.
+    fn rewrite(
+        &mut self,
+        schema: &mut Value,
+        base_url: &Url,
+        document: Option<&Url>,
+    ) -> Result<()> {
+        let base_url = match id_of(self.draft, schema) {
+            Some(id) => base_url.join(id)?,
+            None => base_url.clone(),
+        };
+        if let Value::Object(object) = schema {
+            if let Some(Value::String(reference)) = object.get_mut("$ref") {
+                let url = base_url.join(reference)?;
+                let mut resource = url.clone();
+                resource.set_fragment(None);
+                let is_local = match document {
+                    Some(document) => self.canonical.get(resource.as_str()) == Some(document),
+                    None => self.is_internal(&resource),
+                };
+                if !is_local {
+                    let mut canonical = self.load(&resource)?;
+                    canonical.set_fragment(url.fragment());
+                    *reference = canonical.to_string();
+                }
+            }
+            for (_, _, subschema) in subschemas_mut(object) {
+                self.rewrite(subschema, &base_url, document)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> JSONSchema<'a> {
+    /// Bundle the schema and all documents it references (directly or transitively) into a
+    /// single self-contained document.
+    ///
+    /// Every external document is embedded under the definitions keyword of the schema draft
+    /// (`definitions` in all supported drafts) with its canonical URL as the key and as its `$id`
+    /// (`id` in Draft 4), and external `$ref` are rewritten to these URLs. Documents that
+    /// reference each other are embedded once.
+    ///
+    /// This crate resolves the bundle without loading anything. Other validators do the same
+    /// only if they look for `$id` inside `definitions`; the rewritten `$ref` are not JSON
+    /// Pointers into the bundle.
+    ///
+    /// ```rust,no_run
+    /// use jsonschema::JSONSchema;
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({"$ref": "https://example.com/item.json#/definitions/id"});
+    /// let compiled = JSONSchema::compile(&schema).unwrap();
+    /// let bundle = compiled.bundle().unwrap();
+    /// assert_eq!(
+    ///     bundle["definitions"]["https://example.com/item.json"]["$id"],
+    ///     json!("https://example.com/item.json")
+    /// );
+    /// ```
+    pub fn bundle(&self) -> std::result::Result<Value, ValidationError<'static>> {
+        let mut bundler = Bundler {
+            compiled: self,
+            draft: self.context.config.draft(),
+            canonical: HashMap::new(),
+            pending: vec![],
+            documents: vec![],
+        };
+        let mut root = self.schema.clone();
+        bundler.rewrite(&mut root, &self.context.scope, None)?;
+        while let Some((canonical, mut document)) = bundler.pending.pop() {
+            bundler.rewrite(&mut document, &canonical, Some(&canonical))?;
+            bundler.documents.push((canonical, document));
+        }
+        if bundler.documents.is_empty() {
+            return Ok(root);
+        }
+        // Boolean schemas have no references, hence there is nothing to embed
+        if let Value::Object(object) = &mut root {
+            match object
+                .entry(definitions_keyword(bundler.draft))
+                .or_insert_with(|| Value::Object(Map::new()))
+            {
+                Value::Object(definitions) => {
+                    for (canonical, document) in bundler.documents {
+                        definitions.insert(canonical.to_string(), document);
+                    }
+                }
+                _ => return Err(ValidationError::schema()),
+            }
+        }
+        Ok(root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DocumentCache, JSONSchema};
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+
+    fn bundle(schema: &Value, documents: &[(&str, Value)]) -> Value {
+        let cache = DocumentCache::default();
+        for (url, document) in documents {
+            cache.insert((*url).to_string(), Arc::new(document.clone()));
+        }
+        let compiled = JSONSchema::options()
+            .with_document_cache(cache)
+            .compile(schema)
+            .unwrap();
+        compiled.bundle().unwrap()
+    }
+
+    #[test]
+    fn local_references_are_kept() {
+        let schema =
+            json!({"properties": {"a": {"$ref": "#/definitions/a"}}, "definitions": {"a": {}}});
+        assert_eq!(bundle(&schema, &[]), schema);
+    }
+
+    #[test]
+    fn external_documents_are_embedded() {
+        let schema = json!({
+            "$id": "http://example.com/root.json",
+            "properties": {"item": {"$ref": "item.json#/definitions/item"}}
+        });
+        let item = json!({
+            "definitions": {
+                "item": {"properties": {"id": {"$ref": "#/definitions/id"}, "tag": {"$ref": "tag.json"}}},
+                "id": {"type": "integer"}
+            }
+        });
+        let tag = json!({"$id": "http://example.com/tags/tag.json", "type": "string"});
+        let bundled = bundle(
+            &schema,
+            &[
+                ("http://example.com/item.json", item),
+                ("http://example.com/tag.json", tag),
+            ],
+        );
+        assert_eq!(
+            bundled,
+            json!({
+                "$id": "http://example.com/root.json",
+                "properties": {"item": {"$ref": "http://example.com/item.json#/definitions/item"}},
+                "definitions": {
+                    "http://example.com/item.json": {
+                        "$id": "http://example.com/item.json",
+                        "definitions": {
+                            "item": {"properties": {
+                                "id": {"$ref": "#/definitions/id"},
+                                "tag": {"$ref": "http://example.com/tags/tag.json"}
+                            }},
+                            "id": {"type": "integer"}
+                        }
+                    },
+                    "http://example.com/tags/tag.json": {"$id": "http://example.com/tags/tag.json", "type": "string"}
+                }
+            })
+        );
+        // The bundle is resolved without the cache
+        let compiled = JSONSchema::compile(&bundled).unwrap();
+        assert!(compiled.is_valid(&json!({"item": {"id": 1, "tag": "a"}})));
+        assert!(!compiled.is_valid(&json!({"item": {"id": "1"}})));
+        assert!(!compiled.is_valid(&json!({"item": {"tag": 1}})));
+    }
+
+    #[test]
+    fn cycles() {
+        let schema = json!({"$ref": "http://example.com/a.json"});
+        let a = json!({"properties": {"b": {"$ref": "b.json"}}});
+        let b = json!({"properties": {"a": {"$ref": "a.json"}, "root": {"$ref": "http://example.com/a.json#"}}});
+        let bundled = bundle(
+            &schema,
+            &[
+                ("http://example.com/a.json", a),
+                ("http://example.com/b.json", b),
+            ],
+        );
+        let definitions = bundled["definitions"].as_object().unwrap();
+        assert_eq!(definitions.len(), 2);
+        assert_eq!(
+            bundled["definitions"]["http://example.com/b.json"]["properties"]["a"],
+            json!({"$ref": "http://example.com/a.json"})
+        );
+        let compiled = JSONSchema::compile(&bundled).unwrap();
+        assert!(compiled.is_valid(&json!({"b": {"a": {"b": {}}}})));
+    }
+}
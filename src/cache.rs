@@ -0,0 +1,191 @@
+//! Cache of remote documents loaded during reference resolution.
+use parking_lot::RwLock;
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+struct Entry {
+    document: Arc<Value>,
+    inserted_at: Instant,
+}
+
+/// A thread-safe cache of remote documents loaded via `$ref`.
+///
+/// Clones of a `DocumentCache` share the same storage and settings, hence the same cache could be
+/// passed to many `CompilationOptions` and every remote document is loaded only once for all
+/// schemas compiled with them. Changing the settings of any clone affects all of them.
+///
+/// ```rust
+/// # use jsonschema::{DocumentCache, JSONSchema};
+/// # use std::time::Duration;
+/// # let schema = serde_json::json!({});
+/// let mut cache = DocumentCache::default();
+/// cache.with_ttl(Duration::from_secs(3600)).with_capacity(128);
+/// let compiled = JSONSchema::options()
+///     .with_document_cache(cache.clone())
+///     .compile(&schema);
+/// ```
+#[derive(Clone, Default)]
+pub struct DocumentCache {
+    state: Arc<RwLock<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    documents: HashMap<String, Entry>,
+    ttl: Option<Duration>,
+    capacity: Option<usize>,
+}
+
+impl Entry {
+    #[inline]
+    fn is_expired(&self, ttl: Option<Duration>, now: Instant) -> bool {
+        match ttl {
+            Some(ttl) => now.duration_since(self.inserted_at) >= ttl,
+            None => false,
+        }
+    }
+}
+
+impl DocumentCache {
+    /// Expire documents after they spent `ttl` in the cache.
+    /// Expired documents are loaded again on the next resolution.
+    #[inline]
+    pub fn with_ttl(&mut self, ttl: Duration) -> &mut Self {
+        self.state.write().ttl = Some(ttl);
+        self
+    }
+
+    /// Store at most `capacity` documents. When the cache is full, the oldest document is evicted.
+    #[inline]
+    pub fn with_capacity(&mut self, capacity: usize) -> &mut Self {
+        self.state.write().capacity = Some(capacity);
+        self
+    }
+
+    /// The number of stored documents (including the expired ones that were not evicted yet).
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.state.read().documents.len()
+    }
+
+    /// Whether there are no stored documents.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.state.read().documents.is_empty()
+    }
+
+    /// Remove all stored documents.
+    #[inline]
+    pub fn clear(&self) {
+        self.state.write().documents.clear()
+    }
+
+    pub(crate) fn get(&self, url: &str) -> Option<Arc<Value>> {
+        let state = self.state.read();
+        match state.documents.get(url) {
+            Some(entry) if !entry.is_expired(state.ttl, Instant::now()) => {
+                Some(Arc::clone(&entry.document))
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn insert(&self, url: String, document: Arc<Value>) {
+        let now = Instant::now();
+        let mut state = self.state.write();
+        if let Some(capacity) = state.capacity {
+            if capacity == 0 {
+                return;
+            }
+            if state.documents.len() >= capacity && !state.documents.contains_key(&url) {
+                let ttl = state.ttl;
+                state
+                    .documents
+                    .retain(|_, entry| !entry.is_expired(ttl, now));
+                if state.documents.len() >= capacity {
+                    let oldest = state
+                        .documents
+                        .iter()
+                        .min_by_key(|(_, entry)| entry.inserted_at)
+                        .map(|(url, _)| url.clone());
+                    if let Some(oldest) = oldest {
+                        state.documents.remove(&oldest);
+                    }
+                }
+            }
+        }
+        state.documents.insert(
+            url,
+            Entry {
+                document,
+                inserted_at: now,
+            },
+        );
+    }
+}
+
+impl fmt::Debug for DocumentCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.read();
+        f.debug_struct("DocumentCache")
+            .field("documents", &state.documents.keys())
+            .field("ttl", &state.ttl)
+            .field("capacity", &state.capacity)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DocumentCache;
+    use serde_json::json;
+    use std::{sync::Arc, time::Duration};
+
+    #[test]
+    fn shared_between_clones() {
+        let cache = DocumentCache::default();
+        cache
+            .clone()
+            .insert("http://example.com".to_string(), Arc::new(json!({})));
+        assert_eq!(cache.len(), 1);
+        assert_eq!(*cache.get("http://example.com").unwrap(), json!({}));
+    }
+
+    #[test]
+    fn settings_shared_between_clones() {
+        let cache = DocumentCache::default();
+        cache.clone().with_capacity(1);
+        cache.insert("http://example.com/a".to_string(), Arc::new(json!(1)));
+        cache.insert("http://example.com/b".to_string(), Arc::new(json!(2)));
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get("http://example.com/b").is_some());
+    }
+
+    #[test]
+    fn oldest_document_is_evicted() {
+        let mut cache = DocumentCache::default();
+        cache.with_capacity(2);
+        cache.insert("http://example.com/a".to_string(), Arc::new(json!(1)));
+        cache.insert("http://example.com/b".to_string(), Arc::new(json!(2)));
+        cache.insert("http://example.com/c".to_string(), Arc::new(json!(3)));
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("http://example.com/a").is_none());
+        assert!(cache.get("http://example.com/b").is_some());
+        assert!(cache.get("http://example.com/c").is_some());
+    }
+
+    #[test]
+    fn expired_document_is_not_returned() {
+        let mut cache = DocumentCache::default();
+        cache.with_ttl(Duration::from_secs(0));
+        cache.insert("http://example.com".to_string(), Arc::new(json!({})));
+        assert!(cache.get("http://example.com").is_none());
+    }
+}
@@ -0,0 +1,232 @@
+//! Interpreting strings as values of the types expected by the schema.
+use crate::{
+    compilation::JSONSchema,
+    error::{ErrorIterator, ValidationError},
+    primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
+    subschemas::{self, Result, Source},
+};
+use serde_json::Value;
+use std::{convert::TryFrom, iter::once};
+
+const NUMERIC_KEYWORDS: [&str; 5] = [
+    "minimum",
+    "maximum",
+    "exclusiveMinimum",
+    "exclusiveMaximum",
+    "multipleOf",
+];
+
+fn type_of(value: &Value) -> PrimitiveType {
+    match value {
+        Value::Array(_) => PrimitiveType::Array,
+        Value::Bool(_) => PrimitiveType::Boolean,
+        Value::Null => PrimitiveType::Null,
+        Value::Number(number) if number.is_f64() => PrimitiveType::Number,
+        Value::Number(_) => PrimitiveType::Integer,
+        Value::Object(_) => PrimitiveType::Object,
+        Value::String(_) => PrimitiveType::String,
+    }
+}
+
+/// Types expected by the given schemas. They are taken from `type` or, if it is absent,
+/// inferred from numeric keywords, `const` and `enum`.
+fn expected_types(sources: &[Source<'_>]) -> Option<PrimitiveTypesBitMap> {
+    let mut types = None;
+    for source in sources {
+        let mut add =
+            |primitive_type| *types.get_or_insert_with(PrimitiveTypesBitMap::new) |= primitive_type;
+        match source.schema.get("type") {
+            Some(Value::String(name)) => {
+                if let Ok(primitive_type) = PrimitiveType::try_from(name.as_str()) {
+                    add(primitive_type)
+                }
+            }
+            Some(Value::Array(names)) => {
+                for name in names.iter().filter_map(Value::as_str) {
+                    if let Ok(primitive_type) = PrimitiveType::try_from(name) {
+                        add(primitive_type)
+                    }
+                }
+            }
+            _ => {
+                if NUMERIC_KEYWORDS
+                    .iter()
+                    .any(|keyword| source.schema.get(keyword).is_some())
+                {
+                    add(PrimitiveType::Number)
+                }
+                if let Some(value) = source.schema.get("const") {
+                    add(type_of(value))
+                }
+                if let Some(Value::Array(options)) = source.schema.get("enum") {
+                    for option in options {
+                        add(type_of(option))
+                    }
+                }
+            }
+        }
+    }
+    types
+}
+
+/// Interpret `text` as a value of one of `types`, trying them in a fixed order:
+/// boolean, null, integer, number, array. Arrays are comma-separated.
+fn coerce_string(text: &str, types: PrimitiveTypesBitMap) -> Option<Value> {
+    if types.contains_type(PrimitiveType::Boolean) {
+        match text {
+            "true" => return Some(Value::Bool(true)),
+            "false" => return Some(Value::Bool(false)),
+            _ => {}
+        }
+    }
+    if types.contains_type(PrimitiveType::Null) && (text.is_empty() || text == "null") {
+        return Some(Value::Null);
+    }
+    if types.contains_type(PrimitiveType::Integer) || types.contains_type(PrimitiveType::Number) {
+        if let Ok(value) = text.parse::<u64>() {
+            return Some(Value::from(value));
+        }
+        if let Ok(value) = text.parse::<i64>() {
+            return Some(Value::from(value));
+        }
+    }
+    if types.contains_type(PrimitiveType::Number) {
+        if let Ok(value) = text.parse::<f64>() {
+            if value.is_finite() {
+                return Some(Value::from(value));
+            }
+        }
+    }
+    if types.contains_type(PrimitiveType::Array) {
+        if text.is_empty() {
+            return Some(Value::Array(vec![]));
+        }
+        return Some(Value::Array(
+            text.split(',')
+                .map(|item| Value::String(item.to_string()))
+                .collect(),
+        ));
+    }
+    None
+}
+
+fn coerce(compiled: &JSONSchema, sources: &[Source<'_>], instance: &mut Value) -> Result<()> {
+    if let Value::String(text) = instance {
+        if let Some(types) = expected_types(sources) {
+            if !types.contains_type(PrimitiveType::String) {
+                if let Some(value) = coerce_string(text, types) {
+                    *instance = value;
+                }
+            }
+        }
+    }
+    match instance {
+        Value::Object(object) => {
+            for (name, value) in object.iter_mut() {
+                let children = subschemas::property(compiled, sources, name)?;
+                coerce(compiled, &children, value)?;
+            }
+        }
+        Value::Array(items) => {
+            for (index, value) in items.iter_mut().enumerate() {
+                let children = subschemas::item(compiled, sources, index)?;
+                coerce(compiled, &children, value)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+impl<'a> JSONSchema<'a> {
+    /// Validate an instance where values may arrive as strings (e.g. query parameters, CSV
+    /// rows or environment variables) and return the coerced instance together with the result.
+    ///
+    /// A string is interpreted as a boolean, `null` (`"null"` or an empty string), integer,
+    /// number or comma-separated array, in this order, if the schemas that apply to its
+    /// location do not accept strings. Expected types come from `type` or, if it is absent, from
+    /// numeric keywords, `const` and `enum`. Strings that can't be interpreted are kept.
+    ///
+    /// ```rust
+    /// use jsonschema::JSONSchema;
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({
+    ///     "properties": {
+    ///         "limit": {"type": "integer", "maximum": 100},
+    ///         "tags": {"type": "array", "items": {"type": "integer"}}
+    ///     }
+    /// });
+    /// let compiled = JSONSchema::compile(&schema).unwrap();
+    /// let (coerced, result) = compiled.validate_coerced(json!({"limit": "10", "tags": "1,2"}));
+    /// assert!(result.is_ok());
+    /// assert_eq!(coerced, json!({"limit": 10, "tags": [1, 2]}));
+    /// ```
+    pub fn validate_coerced(
+        &self,
+        mut instance: Value,
+    ) -> (Value, std::result::Result<(), ErrorIterator<'static>>) {
+        if let Err(error) =
+            subschemas::root(self).and_then(|sources| coerce(self, &sources, &mut instance))
+        {
+            return (instance, Err(Box::new(once(error))));
+        }
+        let errors: Vec<_> = match self.validate(&instance) {
+            Ok(()) => vec![],
+            Err(errors) => errors.map(ValidationError::into_owned).collect(),
+        };
+        if errors.is_empty() {
+            (instance, Ok(()))
+        } else {
+            (instance, Err(Box::new(errors.into_iter())))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::JSONSchema;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(&json!({"type": "integer"}), json!("42"), &json!(42); "integer")]
+    #[test_case(&json!({"type": "integer"}), json!("-42"), &json!(-42); "negative integer")]
+    #[test_case(&json!({"type": "number"}), json!("4.5"), &json!(4.5); "number")]
+    #[test_case(&json!({"type": ["boolean", "null"]}), json!("false"), &json!(false); "boolean")]
+    #[test_case(&json!({"type": ["boolean", "null"]}), json!(""), &json!(null); "null")]
+    #[test_case(&json!({"type": ["integer", "string"]}), json!("42"), &json!("42"); "strings are accepted")]
+    #[test_case(&json!({"minimum": 1}), json!("2"), &json!(2); "numeric keywords")]
+    #[test_case(&json!({"enum": [true, 1]}), json!("true"), &json!(true); "enum options")]
+    #[test_case(&json!({"type": "array", "items": {"type": "number"}}), json!("1,2.5"), &json!([1, 2.5]); "array")]
+    #[test_case(&json!({"type": "integer"}), json!("foo"), &json!("foo"); "not coercible")]
+    #[test_case(
+        &json!({"$ref": "#/definitions/row", "definitions": {"row": {"items": [{"type": "integer"}, {"type": "string"}]}}}),
+        json!(["1", "2"]),
+        &json!([1, "2"]);
+        "references"
+    )]
+    #[test_case(
+        &json!({"patternProperties": {"^n": {"type": "number"}}, "additionalProperties": {"type": "boolean"}}),
+        json!({"n1": "1", "flag": "true", "other": "yes"}),
+        &json!({"n1": 1, "flag": true, "other": "yes"});
+        "pattern and additional properties"
+    )]
+    fn coercion(schema: &Value, instance: Value, expected: &Value) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        let (coerced, _) = compiled.validate_coerced(instance);
+        assert_eq!(&coerced, expected);
+    }
+
+    #[test]
+    fn errors_refer_to_coerced_values() {
+        let schema = json!({"properties": {"limit": {"type": "integer", "maximum": 100}}});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let (_, result) = compiled.validate_coerced(json!({"limit": "101"}));
+        let errors: Vec<_> = result.expect_err("Too large").collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "101 is greater than the maximum of 100"
+        );
+    }
+}
@@ -0,0 +1,895 @@
+//! Comparing two versions of a schema and classifying the differences.
+use crate::{
+    compilation::JSONSchema,
+    error::ValidationError,
+    keywords::{multiple_of::is_multiple_of, pattern::convert_regex},
+    paths::{JSONPointer, PathChunk},
+    schemas::{id_of, Draft},
+    subschemas::Result,
+};
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::{borrow::Cow, fmt};
+use url::Url;
+
+/// How many `$ref` in a row are followed before giving up on resolving a schema.
+const MAX_REFERENCES: usize = 32;
+
+/// How a change affects the set of valid instances.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// The new schema accepts fewer instances, e.g. a `required` entry was added.
+    /// Producers that follow the old schema may emit documents rejected by the new one.
+    Narrowing,
+    /// The new schema accepts more instances, e.g. an `enum` value was added.
+    /// Consumers that follow the old schema may receive documents they don't expect.
+    Widening,
+    /// Some instances are accepted only by the old schema and others only by the new one,
+    /// e.g. `pattern` was changed.
+    Incompatible,
+}
+
+impl ChangeKind {
+    fn inverted(self) -> Self {
+        match self {
+            ChangeKind::Narrowing => ChangeKind::Widening,
+            ChangeKind::Widening => ChangeKind::Narrowing,
+            ChangeKind::Incompatible => ChangeKind::Incompatible,
+        }
+    }
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeKind::Narrowing => f.write_str("narrowing"),
+            ChangeKind::Widening => f.write_str("widening"),
+            ChangeKind::Incompatible => f.write_str("incompatible"),
+        }
+    }
+}
+
+/// A difference between two versions of a schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaChange {
+    /// Location of the changed keyword in the new schema, e.g. `/properties/name/maxLength`.
+    /// References are followed transparently, so they don't appear in the path.
+    pub path: JSONPointer,
+    /// The keyword value in the old schema. `None` if the keyword is absent.
+    pub old: Option<Value>,
+    /// The keyword value in the new schema. `None` if the keyword is absent.
+    pub new: Option<Value>,
+    /// The effect of the change.
+    pub kind: ChangeKind,
+}
+
+impl SchemaChange {
+    /// Whether documents emitted according to the old schema may be rejected by the new one.
+    #[must_use]
+    #[inline]
+    pub fn is_breaking_for_producers(&self) -> bool {
+        self.kind != ChangeKind::Widening
+    }
+    /// Whether documents valid against the new schema may be rejected by the old one.
+    #[must_use]
+    #[inline]
+    pub fn is_breaking_for_consumers(&self) -> bool {
+        self.kind != ChangeKind::Narrowing
+    }
+}
+
+impl fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |value: &Option<Value>| match value {
+            Some(value) => value.to_string(),
+            None => "<absent>".to_string(),
+        };
+        write!(
+            f,
+            "{}: {} -> {} ({})",
+            self.path,
+            show(&self.old),
+            show(&self.new),
+            self.kind
+        )
+    }
+}
+
+/// How changes inside a subschema affect its parent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    /// As they are, e.g. inside `properties`.
+    Preserve,
+    /// Inverted, inside `not`.
+    Invert,
+    /// Unknown, e.g. inside `if`.
+    Unknown,
+}
+
+impl Mode {
+    fn apply(self, kind: ChangeKind) -> ChangeKind {
+        match self {
+            Mode::Preserve => kind,
+            Mode::Invert => kind.inverted(),
+            Mode::Unknown => ChangeKind::Incompatible,
+        }
+    }
+
+    fn nested(self, mode: Mode) -> Mode {
+        match (self, mode) {
+            (Mode::Unknown, _) | (_, Mode::Unknown) => Mode::Unknown,
+            (Mode::Preserve, mode) | (mode, Mode::Preserve) => mode,
+            (Mode::Invert, Mode::Invert) => Mode::Preserve,
+        }
+    }
+}
+
+/// A subschema with its base URL.
+#[derive(Clone)]
+struct Node<'c> {
+    schema: Cow<'c, Value>,
+    scope: Url,
+}
+
+impl<'c> Node<'c> {
+    fn child(&self, schema: Option<&'c Value>) -> Node<'c> {
+        Node {
+            schema: match schema {
+                Some(schema) => Cow::Borrowed(schema),
+                None => Cow::Owned(Value::Bool(true)),
+            },
+            scope: self.scope.clone(),
+        }
+    }
+}
+
+/// Follow `$ref` and return the referenced schema, together with the last followed reference.
+fn resolve<'c: 's, 's>(
+    compiled: &'c JSONSchema<'c>,
+    node: &Node<'s>,
+) -> Result<(Node<'s>, Option<Url>)> {
+    let draft = compiled.context.config.draft();
+    let mut node = node.clone();
+    let mut reference = None;
+    for _ in 0..MAX_REFERENCES {
+        if let Some(id) = id_of(draft, &node.schema) {
+            node.scope = Url::options().base_url(Some(&node.scope)).parse(id)?;
+        }
+        match node.schema.get("$ref") {
+            Some(Value::String(value)) => {
+                let url = Url::options().base_url(Some(&node.scope)).parse(value)?;
+                let (scope, schema) = compiled
+                    .resolver
+                    .resolve_fragment(draft, &url, compiled.schema)
+                    .map_err(ValidationError::into_owned)?;
+                node = Node { schema, scope };
+                reference = Some(url);
+            }
+            _ => break,
+        }
+    }
+    Ok((node, reference))
+}
+
+struct Comparison<'c> {
+    old: &'c JSONSchema<'c>,
+    new: &'c JSONSchema<'c>,
+    path: Vec<PathChunk>,
+    /// Pairs of references that were already compared. Makes recursive schemas finite.
+    seen: Vec<(Option<Url>, Option<Url>)>,
+    changes: Vec<SchemaChange>,
+}
+
+/// Keywords of a schema as an object. `true` is the same as an empty object.
+fn as_object(schema: &Value) -> Option<Cow<'_, Map<String, Value>>> {
+    match schema {
+        Value::Object(object) => Some(Cow::Borrowed(object)),
+        Value::Bool(false) => None,
+        _ => Some(Cow::Owned(Map::new())),
+    }
+}
+
+fn as_f64(value: Option<&Value>) -> Option<f64> {
+    value.and_then(Value::as_f64)
+}
+
+/// Whether `types` allows `name`, given that `integer` is a subset of `number`.
+fn allows(types: &[&str], name: &str) -> bool {
+    types.contains(&name) || (name == "integer" && types.contains(&"number"))
+}
+
+fn type_names(value: &Value) -> Vec<&str> {
+    match value {
+        Value::String(name) => vec![name.as_str()],
+        Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+        _ => vec![],
+    }
+}
+
+/// Compare two sets where a missing keyword means "everything".
+fn compare_sets(old_is_subset: bool, new_is_subset: bool) -> Option<ChangeKind> {
+    match (old_is_subset, new_is_subset) {
+        (true, true) => None,
+        (true, false) => Some(ChangeKind::Widening),
+        (false, true) => Some(ChangeKind::Narrowing),
+        (false, false) => Some(ChangeKind::Incompatible),
+    }
+}
+
+impl<'c> Comparison<'c> {
+    fn record(
+        &mut self,
+        chunks: &[&str],
+        old: Option<&Value>,
+        new: Option<&Value>,
+        kind: ChangeKind,
+        mode: Mode,
+    ) {
+        let mut path = self.path.clone();
+        path.extend(
+            chunks
+                .iter()
+                .map(|chunk| PathChunk::Property((*chunk).to_string())),
+        );
+        self.changes.push(SchemaChange {
+            path: JSONPointer::from(path),
+            old: old.cloned(),
+            new: new.cloned(),
+            kind: mode.apply(kind),
+        })
+    }
+
+    /// Compare subschemas at `chunks` relative to the current path.
+    fn nested(
+        &mut self,
+        chunks: &[&str],
+        old: &Node<'_>,
+        new: &Node<'_>,
+        mode: Mode,
+    ) -> Result<()> {
+        let depth = self.path.len();
+        self.path.extend(
+            chunks
+                .iter()
+                .map(|chunk| PathChunk::Property((*chunk).to_string())),
+        );
+        let result = self.compare(old, new, mode);
+        self.path.truncate(depth);
+        result
+    }
+
+    fn compare(&mut self, old: &Node<'_>, new: &Node<'_>, mode: Mode) -> Result<()> {
+        let (old, old_reference) = resolve(self.old, old)?;
+        let (new, new_reference) = resolve(self.new, new)?;
+        if old_reference.is_some() || new_reference.is_some() {
+            let pair = (old_reference, new_reference);
+            if self.seen.contains(&pair) {
+                return Ok(());
+            }
+            self.seen.push(pair);
+        }
+        let (old_keywords, new_keywords) = match (as_object(&old.schema), as_object(&new.schema)) {
+            (Some(old_keywords), Some(new_keywords)) => (old_keywords, new_keywords),
+            (None, None) => return Ok(()),
+            (Some(_), None) => {
+                self.record(
+                    &[],
+                    Some(&old.schema),
+                    Some(&new.schema),
+                    ChangeKind::Narrowing,
+                    mode,
+                );
+                return Ok(());
+            }
+            (None, Some(_)) => {
+                self.record(
+                    &[],
+                    Some(&old.schema),
+                    Some(&new.schema),
+                    ChangeKind::Widening,
+                    mode,
+                );
+                return Ok(());
+            }
+        };
+        let sides = Sides {
+            old_draft: self.old.context.config.draft(),
+            new_draft: self.new.context.config.draft(),
+            old: &old_keywords,
+            new: &new_keywords,
+        };
+        self.compare_values(&sides, mode);
+        self.compare_numbers(&sides, mode);
+        self.compare_strings(&sides, mode);
+        self.compare_required(&sides, mode);
+        self.compare_properties(&sides, &old, &new, mode)?;
+        self.compare_items(&sides, &old, &new, mode)?;
+        self.compare_applicators(&sides, &old, &new, mode)?;
+        Ok(())
+    }
+
+    /// `type`, `enum` and `const`.
+    fn compare_values(&mut self, sides: &Sides<'_>, mode: Mode) {
+        let (old, new) = sides.get("type");
+        if old != new {
+            let old_types = old.map(type_names);
+            let new_types = new.map(type_names);
+            let is_subset = |types: &Option<Vec<&str>>, of: &Option<Vec<&str>>| match (types, of) {
+                (_, None) => true,
+                (None, Some(_)) => false,
+                (Some(types), Some(of)) => types.iter().all(|name| allows(of, name)),
+            };
+            if let Some(kind) = compare_sets(
+                is_subset(&old_types, &new_types),
+                is_subset(&new_types, &old_types),
+            ) {
+                self.record(&["type"], old, new, kind, mode)
+            }
+        }
+        let (old, new) = sides.get("enum");
+        if old != new {
+            let is_subset = |values: Option<&Value>, of: Option<&Value>| match (values, of) {
+                (_, None) => true,
+                (Some(Value::Array(values)), Some(Value::Array(of))) => {
+                    values.iter().all(|value| of.contains(value))
+                }
+                _ => false,
+            };
+            if let Some(kind) = compare_sets(is_subset(old, new), is_subset(new, old)) {
+                self.record(&["enum"], old, new, kind, mode)
+            }
+        }
+        let (old, new) = sides.get("const");
+        if old != new {
+            let kind = match (old, new) {
+                (None, Some(_)) => ChangeKind::Narrowing,
+                (Some(_), None) => ChangeKind::Widening,
+                _ => ChangeKind::Incompatible,
+            };
+            self.record(&["const"], old, new, kind, mode)
+        }
+    }
+
+    /// Numeric limits and sizes of strings, arrays and objects.
+    fn compare_numbers(&mut self, sides: &Sides<'_>, mode: Mode) {
+        const LOWER: [&str; 5] = [
+            "minimum",
+            "exclusiveMinimum",
+            "minLength",
+            "minItems",
+            "minProperties",
+        ];
+        const UPPER: [&str; 5] = [
+            "maximum",
+            "exclusiveMaximum",
+            "maxLength",
+            "maxItems",
+            "maxProperties",
+        ];
+        for (keywords, is_lower) in &[(LOWER, true), (UPPER, false)] {
+            for keyword in keywords {
+                let (old, new) = sides.get(keyword);
+                let kind = match (as_f64(old), as_f64(new)) {
+                    (Some(old), Some(new)) if (old < new) == *is_lower && old != new => {
+                        ChangeKind::Narrowing
+                    }
+                    (Some(old), Some(new)) if old != new => ChangeKind::Widening,
+                    (None, Some(_)) => ChangeKind::Narrowing,
+                    (Some(_), None) => ChangeKind::Widening,
+                    _ => continue,
+                };
+                self.record(&[keyword], old, new, kind, mode)
+            }
+        }
+        // Draft 4 `exclusiveMinimum` & `exclusiveMaximum` flags and `uniqueItems`
+        for keyword in &["exclusiveMinimum", "exclusiveMaximum", "uniqueItems"] {
+            let (old, new) = sides.raw(keyword);
+            let is_flag = |value: Option<&Value>| matches!(value, None | Some(Value::Bool(_)));
+            let flag = |value: Option<&Value>| value == Some(&Value::Bool(true));
+            if !is_flag(old) || !is_flag(new) || flag(old) == flag(new) {
+                continue;
+            }
+            let kind = if flag(new) {
+                ChangeKind::Narrowing
+            } else {
+                ChangeKind::Widening
+            };
+            self.record(&[keyword], old, new, kind, mode)
+        }
+        let (old, new) = sides.get("multipleOf");
+        let kind = match (as_f64(old), as_f64(new)) {
+            (Some(old), Some(new)) if old == new => None,
+            // Multiples of `new` are multiples of `old` as well
+            (Some(old), Some(new)) if is_multiple_of(new, old) => Some(ChangeKind::Narrowing),
+            (Some(old), Some(new)) if is_multiple_of(old, new) => Some(ChangeKind::Widening),
+            (Some(_), Some(_)) => Some(ChangeKind::Incompatible),
+            (None, Some(_)) => Some(ChangeKind::Narrowing),
+            (Some(_), None) => Some(ChangeKind::Widening),
+            (None, None) => None,
+        };
+        if let Some(kind) = kind {
+            self.record(&["multipleOf"], old, new, kind, mode)
+        }
+    }
+
+    /// Keywords that are either present or not, like `pattern` and `format`.
+    fn compare_strings(&mut self, sides: &Sides<'_>, mode: Mode) {
+        for keyword in &["pattern", "format", "contentMediaType", "contentEncoding"] {
+            let (old, new) = sides.get(keyword);
+            let kind = match (old, new) {
+                (Some(old), Some(new)) if old == new => continue,
+                (None, None) => continue,
+                (None, Some(_)) => ChangeKind::Narrowing,
+                (Some(_), None) => ChangeKind::Widening,
+                _ => ChangeKind::Incompatible,
+            };
+            self.record(&[keyword], old, new, kind, mode)
+        }
+    }
+
+    /// Every added or removed `required` entry is a separate change.
+    fn compare_required(&mut self, sides: &Sides<'_>, mode: Mode) {
+        let names = |value: Option<&Value>| -> Vec<Value> {
+            match value {
+                Some(Value::Array(names)) => names.clone(),
+                _ => vec![],
+            }
+        };
+        let (old, new) = sides.get("required");
+        let (old, new) = (names(old), names(new));
+        for name in &new {
+            if !old.contains(name) {
+                self.record(&["required"], None, Some(name), ChangeKind::Narrowing, mode)
+            }
+        }
+        for name in &old {
+            if !new.contains(name) {
+                self.record(&["required"], Some(name), None, ChangeKind::Widening, mode)
+            }
+        }
+    }
+
+    /// `properties`, `patternProperties`, `additionalProperties`, `propertyNames` and
+    /// `dependencies`.
+    fn compare_properties<'s>(
+        &mut self,
+        sides: &Sides<'s>,
+        old: &Node<'s>,
+        new: &Node<'s>,
+        mode: Mode,
+    ) -> Result<()> {
+        let names = |keywords: &'s Map<String, Value>, keyword| -> Vec<&'s String> {
+            match keywords.get(keyword) {
+                Some(Value::Object(object)) => object.keys().collect(),
+                _ => vec![],
+            }
+        };
+        // Properties are compared against schemas that applied to them before they were
+        // listed (or after they were removed), e.g. `additionalProperties`
+        let mut all = names(sides.old, "properties");
+        for name in names(sides.new, "properties") {
+            if !all.contains(&name) {
+                all.push(name)
+            }
+        }
+        let (old_patterns, new_patterns) = (patterns(sides.old), patterns(sides.new));
+        for name in all {
+            self.nested(
+                &["properties", name],
+                &old.child(effective_property(sides.old, &old_patterns, name)),
+                &new.child(effective_property(sides.new, &new_patterns, name)),
+                mode,
+            )?;
+        }
+        let mut patterns = names(sides.old, "patternProperties");
+        for pattern in names(sides.new, "patternProperties") {
+            if !patterns.contains(&pattern) {
+                patterns.push(pattern)
+            }
+        }
+        for pattern in patterns {
+            let schema = |keywords: &'s Map<String, Value>| {
+                keywords
+                    .get("patternProperties")
+                    .and_then(|patterns| patterns.get(pattern))
+                    .or_else(|| keywords.get("additionalProperties"))
+            };
+            self.nested(
+                &["patternProperties", pattern],
+                &old.child(schema(sides.old)),
+                &new.child(schema(sides.new)),
+                mode,
+            )?;
+        }
+        for keyword in &["additionalProperties", "propertyNames"] {
+            let (old_schema, new_schema) = sides.get(keyword);
+            if old_schema.is_some() || new_schema.is_some() {
+                self.nested(
+                    &[keyword],
+                    &old.child(old_schema),
+                    &new.child(new_schema),
+                    mode,
+                )?;
+            }
+        }
+        let (old_dependencies, new_dependencies) = sides.get("dependencies");
+        let mut names: Vec<&String> = vec![];
+        for dependencies in old_dependencies.iter().chain(new_dependencies.iter()) {
+            if let Value::Object(dependencies) = dependencies {
+                for name in dependencies.keys() {
+                    if !names.contains(&name) {
+                        names.push(name)
+                    }
+                }
+            }
+        }
+        for name in names {
+            let old_value = old_dependencies.and_then(|dependencies| dependencies.get(name));
+            let new_value = new_dependencies.and_then(|dependencies| dependencies.get(name));
+            match (old_value, new_value) {
+                (Some(Value::Array(_)), _) | (_, Some(Value::Array(_))) => {
+                    let is_subset = |names: Option<&Value>, of: Option<&Value>| match (names, of) {
+                        (None, _) => true,
+                        (Some(Value::Array(names)), Some(Value::Array(of))) => {
+                            names.iter().all(|name| of.contains(name))
+                        }
+                        _ => false,
+                    };
+                    // More dependencies accept fewer instances
+                    if let Some(kind) = compare_sets(
+                        is_subset(new_value, old_value),
+                        is_subset(old_value, new_value),
+                    ) {
+                        self.record(&["dependencies", name], old_value, new_value, kind, mode)
+                    }
+                }
+                _ => self.nested(
+                    &["dependencies", name],
+                    &old.child(old_value),
+                    &new.child(new_value),
+                    mode,
+                )?,
+            }
+        }
+        Ok(())
+    }
+
+    /// `items`, `additionalItems` and `contains`.
+    fn compare_items<'s>(
+        &mut self,
+        sides: &Sides<'s>,
+        old: &Node<'s>,
+        new: &Node<'s>,
+        mode: Mode,
+    ) -> Result<()> {
+        let (old_items, new_items) = sides.get("items");
+        match (old_items, new_items) {
+            (Some(Value::Array(_)), _) | (_, Some(Value::Array(_))) => {
+                // Positional items are compared one by one, with `additionalItems` for
+                // positions beyond the list
+                let length = [old_items, new_items]
+                    .iter()
+                    .filter_map(|items| items.and_then(Value::as_array).map(Vec::len))
+                    .max()
+                    .unwrap_or(0);
+                for index in 0..length {
+                    let chunk = index.to_string();
+                    self.nested(
+                        &["items", &chunk],
+                        &old.child(effective_item(sides.old, index)),
+                        &new.child(effective_item(sides.new, index)),
+                        mode,
+                    )?;
+                }
+                let (old_additional, new_additional) = sides.get("additionalItems");
+                if old_additional.is_some() || new_additional.is_some() {
+                    self.nested(
+                        &["additionalItems"],
+                        &old.child(effective_item(sides.old, length)),
+                        &new.child(effective_item(sides.new, length)),
+                        mode,
+                    )?;
+                }
+            }
+            (None, None) => {}
+            _ => self.nested(
+                &["items"],
+                &old.child(old_items),
+                &new.child(new_items),
+                mode,
+            )?,
+        }
+        let (old_contains, new_contains) = sides.get("contains");
+        match (old_contains, new_contains) {
+            (None, None) => {}
+            (Some(_), Some(_)) => self.nested(
+                &["contains"],
+                &old.child(old_contains),
+                &new.child(new_contains),
+                mode,
+            )?,
+            // An array without items does not satisfy even `contains: true`
+            _ => {
+                let kind = if new_contains.is_some() {
+                    ChangeKind::Narrowing
+                } else {
+                    ChangeKind::Widening
+                };
+                self.record(&["contains"], old_contains, new_contains, kind, mode)
+            }
+        }
+        Ok(())
+    }
+
+    /// `allOf`, `anyOf`, `oneOf`, `not` and `if` / `then` / `else`.
+    fn compare_applicators<'s>(
+        &mut self,
+        sides: &Sides<'s>,
+        old: &Node<'s>,
+        new: &Node<'s>,
+        mode: Mode,
+    ) -> Result<()> {
+        for keyword in &["allOf", "anyOf", "oneOf"] {
+            let (old_list, new_list) = sides.get(keyword);
+            let list = |value: Option<&'s Value>| -> &'s [Value] {
+                match value {
+                    Some(Value::Array(items)) => items,
+                    _ => &[],
+                }
+            };
+            let (old_list, new_list) = (list(old_list), list(new_list));
+            for index in 0..old_list.len().min(new_list.len()) {
+                let chunk = index.to_string();
+                self.nested(
+                    &[keyword, &chunk],
+                    &old.child(old_list.get(index)),
+                    &new.child(new_list.get(index)),
+                    mode,
+                )?;
+            }
+            for index in old_list.len().min(new_list.len())..old_list.len().max(new_list.len()) {
+                let chunk = index.to_string();
+                let (old_value, new_value) = (old_list.get(index), new_list.get(index));
+                match *keyword {
+                    // A new `allOf` item may only add constraints
+                    "allOf" => self.nested(
+                        &[keyword, &chunk],
+                        &old.child(old_value),
+                        &new.child(new_value),
+                        mode,
+                    )?,
+                    "anyOf" => {
+                        let kind = if new_value.is_some() {
+                            ChangeKind::Widening
+                        } else {
+                            ChangeKind::Narrowing
+                        };
+                        self.record(&[keyword, &chunk], old_value, new_value, kind, mode)
+                    }
+                    // Instances may match several `oneOf` items
+                    _ => self.record(
+                        &[keyword, &chunk],
+                        old_value,
+                        new_value,
+                        ChangeKind::Incompatible,
+                        mode,
+                    ),
+                }
+            }
+        }
+        let (old_not, new_not) = sides.get("not");
+        match (old_not, new_not) {
+            (None, None) => {}
+            // `not` with a missing schema is the same as `not: false`
+            _ => self.nested(
+                &["not"],
+                &old.child(old_not.or(Some(&FALSE))),
+                &new.child(new_not.or(Some(&FALSE))),
+                mode.nested(Mode::Invert),
+            )?,
+        }
+        let (old_if, new_if) = sides.get("if");
+        if old_if.is_some() || new_if.is_some() {
+            self.nested(
+                &["if"],
+                &old.child(old_if),
+                &new.child(new_if),
+                mode.nested(Mode::Unknown),
+            )?;
+            for keyword in &["then", "else"] {
+                let (old_value, new_value) = (sides.old.get(*keyword), sides.new.get(*keyword));
+                if old_value.is_some() || new_value.is_some() {
+                    self.nested(
+                        &[keyword],
+                        &old.child(old_value),
+                        &new.child(new_value),
+                        mode,
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> JSONSchema<'a> {
+    /// Compare this schema with a newer version of it and return the differences, keyword by
+    /// keyword.
+    ///
+    /// References are followed, so moving a subschema into `definitions` is not a change.
+    /// Annotations like `title` or `default` are ignored. Both schemas are expected to use the
+    /// same draft. The classification is conservative: e.g. any change inside `if` is
+    /// `ChangeKind::Incompatible`.
+    ///
+    /// ```rust
+    /// use jsonschema::{ChangeKind, JSONSchema};
+    /// use serde_json::json;
+    ///
+    /// let old = json!({"properties": {"name": {"maxLength": 10}}});
+    /// let new = json!({"properties": {"name": {"maxLength": 5}}, "required": ["name"]});
+    /// let old = JSONSchema::compile(&old).unwrap();
+    /// let new = JSONSchema::compile(&new).unwrap();
+    /// let changes = old.compare(&new).unwrap();
+    /// assert_eq!(changes.len(), 2);
+    /// assert_eq!(changes[0].to_string(), r#"/required: <absent> -> "name" (narrowing)"#);
+    /// assert_eq!(changes[1].to_string(), "/properties/name/maxLength: 10 -> 5 (narrowing)");
+    /// assert!(changes.iter().all(|change| change.kind == ChangeKind::Narrowing));
+    /// ```
+    pub fn compare(
+        &self,
+        new: &JSONSchema<'_>,
+    ) -> std::result::Result<Vec<SchemaChange>, ValidationError<'static>> {
+        let old_root = Node {
+            schema: Cow::Borrowed(self.schema),
+            scope: self.context.scope.clone().into_owned(),
+        };
+        let new_root = Node {
+            schema: Cow::Borrowed(new.schema),
+            scope: new.context.scope.clone().into_owned(),
+        };
+        // Recursive `$ref: "#"` should not compare the root twice
+        let reference = |root: &Node<'_>| Url::options().base_url(Some(&root.scope)).parse("#");
+        let mut comparison = Comparison {
+            old: self,
+            new,
+            path: vec![],
+            seen: vec![(Some(reference(&old_root)?), Some(reference(&new_root)?))],
+            changes: vec![],
+        };
+        comparison.compare(&old_root, &new_root, Mode::Preserve)?;
+        Ok(comparison.changes)
+    }
+}
+
+static FALSE: Value = Value::Bool(false);
+
+/// Keywords of the old and the new schema.
+struct Sides<'c> {
+    old_draft: Draft,
+    new_draft: Draft,
+    old: &'c Map<String, Value>,
+    new: &'c Map<String, Value>,
+}
+
+impl<'c> Sides<'c> {
+    /// Values of a keyword if it is supported by the corresponding draft.
+    fn get(&self, keyword: &str) -> (Option<&'c Value>, Option<&'c Value>) {
+        let get = |draft: Draft, keywords: &'c Map<String, Value>| {
+            draft
+                .get_validator(keyword)
+                .and_then(|_| keywords.get(keyword))
+        };
+        (get(self.old_draft, self.old), get(self.new_draft, self.new))
+    }
+
+    /// Values of a keyword regardless of the draft.
+    fn raw(&self, keyword: &str) -> (Option<&'c Value>, Option<&'c Value>) {
+        (self.old.get(keyword), self.new.get(keyword))
+    }
+}
+
+/// Compiled `patternProperties` with their schemas. Invalid patterns are skipped.
+fn patterns(keywords: &Map<String, Value>) -> Vec<(Regex, &Value)> {
+    match keywords.get("patternProperties") {
+        Some(Value::Object(patterns)) => patterns
+            .iter()
+            .filter_map(|(pattern, schema)| Some((convert_regex(pattern).ok()?, schema)))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// The schema that applies to the `name` property.
+fn effective_property<'c>(
+    keywords: &'c Map<String, Value>,
+    patterns: &[(Regex, &'c Value)],
+    name: &str,
+) -> Option<&'c Value> {
+    if let Some(schema) = keywords
+        .get("properties")
+        .and_then(|properties| properties.get(name))
+    {
+        return Some(schema);
+    }
+    patterns
+        .iter()
+        .find(|(pattern, _)| pattern.is_match(name))
+        .map(|(_, schema)| *schema)
+        .or_else(|| keywords.get("additionalProperties"))
+}
+
+/// The schema that applies to the array item at `index`.
+fn effective_item(keywords: &Map<String, Value>, index: usize) -> Option<&Value> {
+    match keywords.get("items") {
+        Some(Value::Array(items)) => items.get(index).or_else(|| keywords.get("additionalItems")),
+        items => items,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChangeKind, SchemaChange};
+    use crate::JSONSchema;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(&json!({"required": ["a"]}), &json!({"required": ["a", "b"]}), &[("/required", ChangeKind::Narrowing)]; "required added")]
+    #[test_case(&json!({"required": ["a"]}), &json!({}), &[("/required", ChangeKind::Widening)]; "required removed")]
+    #[test_case(&json!({"maxLength": 10}), &json!({"maxLength": 5}), &[("/maxLength", ChangeKind::Narrowing)]; "max length tightened")]
+    #[test_case(&json!({"minimum": 1}), &json!({"minimum": 0}), &[("/minimum", ChangeKind::Widening)]; "minimum relaxed")]
+    #[test_case(&json!({"minimum": 1}), &json!({}), &[("/minimum", ChangeKind::Widening)]; "minimum removed")]
+    #[test_case(&json!({"maximum": 5, "exclusiveMaximum": false, "$schema": "http://json-schema.org/draft-04/schema#"}), &json!({"maximum": 5, "exclusiveMaximum": true, "$schema": "http://json-schema.org/draft-04/schema#"}), &[("/exclusiveMaximum", ChangeKind::Narrowing)]; "draft 4 exclusive flag")]
+    #[test_case(&json!({"multipleOf": 2}), &json!({"multipleOf": 4}), &[("/multipleOf", ChangeKind::Narrowing)]; "multiple of narrowed")]
+    #[test_case(&json!({"multipleOf": 2}), &json!({"multipleOf": 3}), &[("/multipleOf", ChangeKind::Incompatible)]; "multiple of changed")]
+    #[test_case(&json!({"multipleOf": 0.1}), &json!({"multipleOf": 0.3}), &[("/multipleOf", ChangeKind::Narrowing)]; "fractional multiple of narrowed")]
+    #[test_case(&json!({"multipleOf": 0.3}), &json!({"multipleOf": 0.1}), &[("/multipleOf", ChangeKind::Widening)]; "fractional multiple of widened")]
+    #[test_case(&json!({"enum": ["a", "b"]}), &json!({"enum": ["a"]}), &[("/enum", ChangeKind::Narrowing)]; "enum value removed")]
+    #[test_case(&json!({"enum": ["a"]}), &json!({"enum": ["a", "b"]}), &[("/enum", ChangeKind::Widening)]; "enum value added")]
+    #[test_case(&json!({"enum": ["a", "b"]}), &json!({"enum": ["b", "c"]}), &[("/enum", ChangeKind::Incompatible)]; "enum value replaced")]
+    #[test_case(&json!({"type": "number"}), &json!({"type": "integer"}), &[("/type", ChangeKind::Narrowing)]; "type narrowed")]
+    #[test_case(&json!({"type": "string"}), &json!({"type": ["string", "null"]}), &[("/type", ChangeKind::Widening)]; "type widened")]
+    #[test_case(&json!({"pattern": "^a"}), &json!({"pattern": "^b"}), &[("/pattern", ChangeKind::Incompatible)]; "pattern changed")]
+    #[test_case(&json!({"properties": {"a": {}}}), &json!({"properties": {"a": {}}, "additionalProperties": false}), &[("/additionalProperties", ChangeKind::Narrowing)]; "additional properties disallowed")]
+    #[test_case(&json!({"additionalProperties": false}), &json!({"properties": {"a": {"type": "string"}}, "additionalProperties": false}), &[("/properties/a", ChangeKind::Widening)]; "property added to closed object")]
+    #[test_case(&json!({"properties": {"a": {"type": "string"}}}), &json!({}), &[("/properties/a/type", ChangeKind::Widening)]; "property removed")]
+    #[test_case(&json!({"patternProperties": {"^\\d+$": {"type": "string"}}}), &json!({"patternProperties": {"^\\d+$": {"type": "string"}}, "properties": {"\u{661}": {"type": "string"}}}), &[("/properties/\u{661}/type", ChangeKind::Narrowing)]; "ecma 262 patterns")]
+    #[test_case(&json!({"items": {"maxLength": 3}}), &json!({"items": {"maxLength": 2}}), &[("/items/maxLength", ChangeKind::Narrowing)]; "items")]
+    #[test_case(&json!({"items": [{}, {"type": "string"}]}), &json!({"items": [{}, {"type": "integer"}]}), &[("/items/1/type", ChangeKind::Incompatible)]; "positional items")]
+    #[test_case(&json!({"anyOf": [{"type": "string"}]}), &json!({"anyOf": [{"type": "string"}, {"type": "null"}]}), &[("/anyOf/1", ChangeKind::Widening)]; "any of branch added")]
+    #[test_case(&json!({"not": {"type": "string"}}), &json!({"not": {"type": ["string", "null"]}}), &[("/not/type", ChangeKind::Narrowing)]; "not inverts")]
+    #[test_case(&json!({"if": {"minimum": 1}, "then": {}}), &json!({"if": {"minimum": 2}, "then": {}}), &[("/if/minimum", ChangeKind::Incompatible)]; "if is unknown")]
+    #[test_case(&json!({"title": "A", "maxLength": 1}), &json!({"title": "B", "maxLength": 1}), &[]; "annotations are ignored")]
+    #[test_case(&json!({"properties": {"a": {"$ref": "#/definitions/a"}}, "definitions": {"a": {"maxLength": 3}}}), &json!({"properties": {"a": {"maxLength": 3}}}), &[]; "references are followed")]
+    #[test_case(&json!({"properties": {"a": {"$ref": "#/definitions/a"}}, "definitions": {"a": {"maxLength": 3}}}), &json!({"properties": {"a": {"$ref": "#/definitions/a"}}, "definitions": {"a": {"maxLength": 4}}}), &[("/properties/a/maxLength", ChangeKind::Widening)]; "referenced definitions")]
+    #[test_case(&json!({"properties": {"child": {"$ref": "#"}, "name": {"type": "string"}}}), &json!({"properties": {"child": {"$ref": "#"}, "name": {"type": "integer"}}}), &[("/properties/name/type", ChangeKind::Incompatible)]; "recursive references")]
+    #[test_case(&json!({"properties": {"a": true}}), &json!({"properties": {"a": false}}), &[("/properties/a", ChangeKind::Narrowing)]; "boolean schemas")]
+    fn compare(old: &Value, new: &Value, expected: &[(&str, ChangeKind)]) {
+        let old = JSONSchema::compile(old).unwrap();
+        let new = JSONSchema::compile(new).unwrap();
+        let changes: Vec<_> = old
+            .compare(&new)
+            .unwrap()
+            .iter()
+            .map(|change| (change.path.to_string(), change.kind))
+            .collect();
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|(path, kind)| ((*path).to_string(), *kind))
+            .collect();
+        assert_eq!(changes, expected);
+    }
+
+    #[test_case(ChangeKind::Narrowing, true, false)]
+    #[test_case(ChangeKind::Widening, false, true)]
+    #[test_case(ChangeKind::Incompatible, true, true)]
+    fn breaking(kind: ChangeKind, producers: bool, consumers: bool) {
+        let change = SchemaChange {
+            path: vec![].into(),
+            old: None,
+            new: None,
+            kind,
+        };
+        assert_eq!(change.is_breaking_for_producers(), producers);
+        assert_eq!(change.is_breaking_for_consumers(), consumers);
+    }
+}
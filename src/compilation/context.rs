@@ -4,11 +4,54 @@ use serde_json::Value;
 use std::borrow::Cow;
 use url::{ParseError, Url};
 
-/// Context holds information about used draft and current scope.
+/// JSON Pointer chunks leading to the currently compiled keyword from the root of the
+/// compiled document.
+/// Every context owns only its last chunk and borrows the preceding ones from its parent,
+/// hence nested contexts are created without copying the whole path.
+#[derive(Debug)]
+pub(crate) enum SchemaPath<'a> {
+    /// Chunks of the compiled document root, e.g. a fragment of a resolved `$ref`.
+    Root(Vec<String>),
+    /// The same location as the parent context.
+    Same(&'a SchemaPath<'a>),
+    /// A chunk after the parent location.
+    Chunk(&'a SchemaPath<'a>, Cow<'a, str>),
+}
+
+impl<'a> SchemaPath<'a> {
+    /// The path where the last chunk is replaced with `chunk`.
+    fn with_sibling(&'a self, chunk: Cow<'a, str>) -> SchemaPath<'a> {
+        match self {
+            SchemaPath::Root(chunks) => {
+                let mut chunks = chunks.clone();
+                chunks.pop();
+                chunks.push(chunk.into_owned());
+                SchemaPath::Root(chunks)
+            }
+            SchemaPath::Same(parent) => parent.with_sibling(chunk),
+            SchemaPath::Chunk(parent, _) => SchemaPath::Chunk(parent, chunk),
+        }
+    }
+
+    /// Chunks from the root to this location.
+    fn collect<'s>(&'s self, chunks: &mut Vec<&'s str>) {
+        match self {
+            SchemaPath::Root(root) => chunks.extend(root.iter().map(String::as_str)),
+            SchemaPath::Same(parent) => parent.collect(chunks),
+            SchemaPath::Chunk(parent, chunk) => {
+                parent.collect(chunks);
+                chunks.push(chunk)
+            }
+        }
+    }
+}
+
+/// Context holds information about used draft, current scope and location in the schema.
 #[derive(Debug)]
 pub(crate) struct CompilationContext<'a> {
     pub(crate) scope: Cow<'a, Url>,
     pub(crate) config: Cow<'a, CompilationOptions>,
+    pub(crate) schema_path: SchemaPath<'a>,
 }
 
 impl<'a> CompilationContext<'a> {
@@ -16,6 +59,7 @@ impl<'a> CompilationContext<'a> {
         CompilationContext {
             scope: Cow::Owned(scope),
             config,
+            schema_path: SchemaPath::Root(vec![]),
         }
     }
 
@@ -36,15 +80,48 @@ impl<'a> CompilationContext<'a> {
             Ok(CompilationContext {
                 scope: Cow::Owned(scope),
                 config: Cow::Borrowed(&self.config),
+                schema_path: SchemaPath::Same(&self.schema_path),
             })
         } else {
             Ok(CompilationContext {
                 scope: Cow::Borrowed(self.scope.as_ref()),
                 config: Cow::Borrowed(&self.config),
+                schema_path: SchemaPath::Same(&self.schema_path),
             })
         }
     }
 
+    /// Create a context for a nested location in the schema, e.g. a keyword or a property name.
+    #[inline]
+    pub(crate) fn with_path(&'a self, chunk: impl Into<Cow<'a, str>>) -> Self {
+        CompilationContext {
+            scope: Cow::Borrowed(self.scope.as_ref()),
+            config: Cow::Borrowed(&self.config),
+            schema_path: SchemaPath::Chunk(&self.schema_path, chunk.into()),
+        }
+    }
+
+    /// Create a context for a keyword next to the current one.
+    /// For example, `then` & `else` are compiled as a part of `if`.
+    #[inline]
+    pub(crate) fn with_sibling_path(&'a self, chunk: impl Into<Cow<'a, str>>) -> Self {
+        CompilationContext {
+            scope: Cow::Borrowed(self.scope.as_ref()),
+            config: Cow::Borrowed(&self.config),
+            schema_path: self.schema_path.with_sibling(chunk.into()),
+        }
+    }
+
+    /// JSON Pointer to the current location in the schema.
+    pub(crate) fn schema_pointer(&self) -> String {
+        let mut chunks = vec![];
+        self.schema_path.collect(&mut chunks);
+        chunks
+            .iter()
+            .map(|chunk| format!("/{}", chunk.replace('~', "~0").replace('/', "~1")))
+            .collect()
+    }
+
     /// Build a new URL. Used for `ref` compilation to keep their full paths.
     pub(crate) fn build_url(&self, reference: &str) -> Result<Url, ParseError> {
         Url::options().base_url(Some(&self.scope)).parse(reference)
@@ -2,15 +2,19 @@
 //! The main idea is to compile the input JSON Schema to a validators tree that will contain
 //! everything needed to perform such validation in runtime.
 pub(crate) mod context;
+mod optimization;
 pub(crate) mod options;
 
 use crate::{
-    error::{CompilationError, ErrorIterator},
+    analysis::CompilationWarning,
+    error::{CompilationError, ErrorIterator, ValidationError},
     keywords,
     keywords::Validators,
     resolver::Resolver,
+    single_pass::Plan,
 };
 use context::CompilationContext;
+use once_cell::sync::OnceCell;
 use options::CompilationOptions;
 use serde_json::Value;
 
@@ -23,8 +27,11 @@ pub(crate) const DEFAULT_ROOT_URL: &str = "json-schema:///";
 pub struct JSONSchema<'a> {
     pub(crate) schema: &'a Value,
     pub(crate) validators: Validators,
+    /// Used to validate documents while they are deserialized, built on first use
+    pub(crate) plan: OnceCell<Option<Plan>>,
     pub(crate) resolver: Resolver<'a>,
     pub(crate) context: CompilationContext<'a>,
+    pub(crate) warnings: Vec<CompilationWarning>,
 }
 
 lazy_static::lazy_static! {
@@ -57,14 +64,63 @@ impl<'a> JSONSchema<'a> {
         Self::options().compile(schema)
     }
 
+    /// Compile the input schema into a validation tree, loading remote documents asynchronously.
+    ///
+    /// The method is equivalent to `JSONSchema::options().compile_async(schema)`
+    #[cfg(feature = "async")]
+    pub async fn compile_async(schema: &'a Value) -> Result<JSONSchema<'a>, CompilationError> {
+        Self::options().compile_async(schema).await
+    }
+
     /// Run validation against `instance` and return an iterator over `ValidationError` in the error case.
+    ///
+    /// Errors are produced lazily, as the iterator is consumed. If the schema is compiled with
+    /// `CompilationOptions::with_max_errors`, the iterator yields at most that many errors.
     #[inline]
     pub fn validate(&'a self, instance: &'a Value) -> Result<(), ErrorIterator<'a>> {
-        let mut errors = self
-            .validators
+        match self.context.config.max_errors() {
+            Some(limit) => self.validate_with_limit(instance, limit),
+            None => Self::into_result(Box::new(self.errors(instance))),
+        }
+    }
+
+    /// Run validation against `instance` and return an iterator over at most `limit` errors.
+    /// Validation stops as soon as `limit` errors are found.
+    ///
+    /// # Panics
+    ///
+    /// If `limit` is zero, since an invalid instance could not report any error.
+    ///
+    /// ```rust
+    /// use jsonschema::JSONSchema;
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({"items": {"type": "integer"}});
+    /// let compiled = JSONSchema::compile(&schema).unwrap();
+    /// let instance = json!(["a", "b", "c"]);
+    /// let errors = compiled.validate_with_limit(&instance, 2).unwrap_err();
+    /// assert_eq!(errors.count(), 2);
+    /// ```
+    #[inline]
+    pub fn validate_with_limit(
+        &'a self,
+        instance: &'a Value,
+        limit: usize,
+    ) -> Result<(), ErrorIterator<'a>> {
+        assert!(limit > 0, "The error limit must be greater than zero");
+        Self::into_result(Box::new(self.errors(instance).take(limit)))
+    }
+
+    #[inline]
+    fn errors(&'a self, instance: &'a Value) -> impl Iterator<Item = ValidationError<'a>> {
+        self.validators
             .iter()
             .flat_map(move |validator| validator.validate(self, instance))
-            .peekable();
+    }
+
+    #[inline]
+    fn into_result(errors: ErrorIterator<'a>) -> Result<(), ErrorIterator<'a>> {
+        let mut errors = errors.peekable();
         if errors.peek().is_none() {
             Ok(())
         } else {
@@ -82,6 +138,40 @@ impl<'a> JSONSchema<'a> {
             .iter()
             .all(|validator| validator.is_valid(self, instance))
     }
+
+    /// Problems found in the schema that do not prevent its compilation, e.g. subschemas
+    /// that no instance can satisfy.
+    ///
+    /// ```rust
+    /// use jsonschema::JSONSchema;
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({"type": "string", "minLength": 5, "maxLength": 2});
+    /// let compiled = JSONSchema::compile(&schema).unwrap();
+    /// assert_eq!(
+    ///     compiled.warnings()[0].to_string(),
+    ///     "The schema can't be satisfied: no string is at least 5 and at most 2 characters long"
+    /// );
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn warnings(&self) -> &[CompilationWarning] {
+        &self.warnings
+    }
+
+    /// Plan for validating documents while they are deserialized.
+    /// It is built on first use, hence schemas that are only used with `Value` do not pay for it.
+    /// The validators of the same schema are already compiled, therefore if the plan fails to
+    /// compile, the whole document is materialized instead.
+    pub(crate) fn plan(&self) -> Option<&Plan> {
+        self.plan
+            .get_or_init(|| {
+                Plan::compile_root(self.schema, &self.context)
+                    .ok()
+                    .flatten()
+            })
+            .as_ref()
+    }
 }
 
 /// Compile JSON schema into a tree of validators.
@@ -98,15 +188,52 @@ pub(crate) fn compile_validators(
         Value::Object(object) => {
             if let Some(reference) = object.get("$ref") {
                 if let Value::String(reference) = reference {
-                    Ok(vec![keywords::ref_::compile(schema, reference, &context)
-                        .expect("Should always return Some")?])
+                    Ok(vec![keywords::ref_::compile(
+                        schema,
+                        reference,
+                        &context.with_path("$ref"),
+                    )
+                    .expect("Should always return Some")?])
                 } else {
                     Err(CompilationError::SchemaError)
                 }
             } else {
+                let draft = context.config.draft();
+                let is_range = optimization::is_range(draft, object);
                 let mut validators = Vec::with_capacity(object.len());
-                for (keyword, subschema) in object {
-                    if let Some(compilation_func) = context.config.draft().get_validator(keyword) {
+                for (keyword, subschema) in optimization::keywords(object) {
+                    let context = context.with_path(keyword.as_str());
+                    match (keyword.as_str(), subschema) {
+                        ("allOf", Value::Array(items)) => {
+                            let mut items = items
+                                .iter()
+                                .enumerate()
+                                .filter(|(_, item)| !optimization::is_trivially_true(item));
+                            if let (Some((idx, item)), None) = (items.next(), items.next()) {
+                                // The only subschema is checked as a part of this schema
+                                validators.extend(compile_validators(
+                                    item,
+                                    &context.with_path(idx.to_string()),
+                                )?);
+                                continue;
+                            }
+                        }
+                        ("enum", Value::Array(items)) if items.len() == 1 => {
+                            validators.push(keywords::enum_::SingleValueEnumValidator::compile(
+                                object, subschema, &context,
+                            )?);
+                            continue;
+                        }
+                        ("minimum", Value::Number(minimum)) if is_range => {
+                            if let Some(Value::Number(maximum)) = object.get("maximum") {
+                                validators.push(keywords::range::compile(minimum, maximum)?);
+                            }
+                            continue;
+                        }
+                        ("maximum", _) if is_range => continue,
+                        _ => {}
+                    }
+                    if let Some(compilation_func) = draft.get_validator(keyword) {
                         if let Some(validator) = compilation_func(object, subschema, &context) {
                             validators.push(validator?)
                         }
@@ -125,6 +252,7 @@ mod tests {
     use crate::{error::ValidationError, schemas};
     use serde_json::{from_str, json, Value};
     use std::{borrow::Cow, fs::File, io::Read, path::Path};
+    use test_case::test_case;
     use url::Url;
 
     fn load(path: &str, idx: usize) -> Value {
@@ -199,4 +327,40 @@ mod tests {
             r#"'"a"' is shorter than 3 characters"#
         );
     }
+
+    #[test_case(1, 1)]
+    #[test_case(2, 2)]
+    #[test_case(100, 20)]
+    fn validate_with_limit(limit: usize, expected: usize) {
+        let schema = json!({"items": {"type": "integer", "$ref": "#/definitions/a"}, "definitions": {"a": {"type": "integer"}}});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let instance = json!(vec!["a"; 20]);
+        let errors = compiled.validate_with_limit(&instance, limit).unwrap_err();
+        assert_eq!(errors.count(), expected);
+        assert!(compiled.validate_with_limit(&json!([1]), limit).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "The error limit must be greater than zero")]
+    fn validate_with_zero_limit() {
+        let schema = json!({"type": "integer"});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let _ = compiled.validate_with_limit(&json!("a"), 0);
+    }
+
+    #[test]
+    fn max_errors() {
+        let schema = json!({"additionalProperties": {"type": "string"}, "maxProperties": 1});
+        let compiled = JSONSchema::options()
+            .with_max_errors(1)
+            .compile(&schema)
+            .unwrap();
+        let instance = json!({"a": 1, "b": 2});
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            r#"{"a":1,"b":2} has more than 1 property"#
+        );
+    }
 }
@@ -0,0 +1,135 @@
+//! Simplifications of the validator tree that do not change validation results.
+use crate::schemas::Draft;
+use serde_json::{Map, Value};
+
+/// Relative cost of a keyword validator. Cheap validators run first, hence `is_valid` returns
+/// early on invalid instances without running the expensive ones.
+fn cost(keyword: &str) -> u8 {
+    match keyword {
+        "type" | "const" | "enum" | "required" => 0,
+        "minimum" | "maximum" | "exclusiveMinimum" | "exclusiveMaximum" | "multipleOf"
+        | "minLength" | "maxLength" | "minItems" | "maxItems" | "minProperties"
+        | "maxProperties" => 1,
+        "pattern" | "format" | "uniqueItems" | "contentEncoding" | "contentMediaType" => 3,
+        // Applicators are as expensive as their subschemas
+        _ => 2,
+    }
+}
+
+/// Whether `schema` is valid for any instance.
+pub(crate) fn is_trivially_true(schema: &Value) -> bool {
+    match schema {
+        Value::Bool(value) => *value,
+        Value::Object(object) => object.is_empty(),
+        _ => false,
+    }
+}
+
+/// Whether the keyword has no effect on validation.
+fn is_redundant(keyword: &str, value: &Value) -> bool {
+    match keyword {
+        "additionalItems" | "additionalProperties" | "items" | "propertyNames" => {
+            is_trivially_true(value)
+        }
+        "allOf" => matches!(value, Value::Array(items) if items.iter().all(is_trivially_true)),
+        _ => false,
+    }
+}
+
+/// Whether `minimum` and `maximum` can be checked by a single validator.
+///
+/// A single validator reports only one error, therefore contradictory limits, that fail every
+/// number on both keywords, are checked separately.
+pub(crate) fn is_range(draft: Draft, object: &Map<String, Value>) -> bool {
+    // Draft 4 `exclusiveMinimum` & `exclusiveMaximum` modify `minimum` & `maximum`
+    let has_modifiers = draft == Draft::Draft4
+        && (object.contains_key("exclusiveMinimum") || object.contains_key("exclusiveMaximum"));
+    let limit = |keyword| match object.get(keyword) {
+        Some(Value::Number(limit)) => limit.as_f64(),
+        _ => None,
+    };
+    match (limit("minimum"), limit("maximum")) {
+        (Some(minimum), Some(maximum)) => !has_modifiers && minimum <= maximum,
+        _ => false,
+    }
+}
+
+/// Keywords of the schema in the order their validators should run, without the ones that
+/// have no effect.
+pub(crate) fn keywords(object: &Map<String, Value>) -> Vec<(&String, &Value)> {
+    let mut keywords: Vec<_> = object
+        .iter()
+        .filter(|(keyword, value)| !is_redundant(keyword, value))
+        .collect();
+    // The sort is stable, hence keywords of the same cost keep the document order
+    keywords.sort_by_key(|(keyword, _)| cost(keyword));
+    keywords
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compilation::JSONSchema;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(&json!({"pattern": "^a", "maxLength": 3, "type": "string"}), "[type: string, maxLength: 3, pattern: ^a]"; "cheap keywords first")]
+    #[test_case(&json!({"allOf": [{"minimum": 1}]}), "minimum: 1"; "single schema in allOf")]
+    #[test_case(&json!({"allOf": [true, {"minimum": 1}, {}]}), "minimum: 1"; "trivial schemas in allOf")]
+    #[test_case(&json!({"allOf": [{"minimum": 1}, {"maximum": 2}]}), "allOf: [{minimum: 1}, {maximum: 2}]"; "several schemas in allOf")]
+    #[test_case(&json!({"minimum": 1, "maximum": 2.5}), "minimum: 1, maximum: 2.5"; "range")]
+    #[test_case(&json!({"$schema": "http://json-schema.org/draft-04/schema#", "minimum": 1, "maximum": 2, "exclusiveMaximum": true}), "[exclusiveMaximum: 2, minimum: 1]"; "draft 4 exclusive limits")]
+    #[test_case(&json!({"enum": ["a"]}), r#"const: "a""#; "single enum value")]
+    #[test_case(&json!({"items": true, "additionalProperties": {}, "propertyNames": true, "allOf": [{}]}), "[]"; "trivial subschemas")]
+    fn optimized(schema: &Value, expected: &str) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        let representation = match compiled.validators.len() {
+            1 => format!("{:?}", compiled.validators[0]),
+            _ => format!("{:?}", compiled.validators),
+        };
+        assert_eq!(representation, expected);
+    }
+
+    #[test_case(&json!({"minimum": 1, "maximum": 3}), &json!(0), "0 is less than the minimum of 1")]
+    #[test_case(&json!({"minimum": 1, "maximum": 3}), &json!(4), "4 is greater than the maximum of 3")]
+    #[test_case(&json!({"allOf": [{"type": "string"}]}), &json!(1), r#"'1' is not of type 'string'"#)]
+    #[test_case(&json!({"enum": ["a"]}), &json!("b"), r#"'"b"' is not one of '["a"]'"#)]
+    fn errors(schema: &Value, instance: &Value, expected: &str) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        let errors: Vec<_> = compiled
+            .validate(instance)
+            .expect_err("Should be invalid")
+            .map(|error| error.to_string())
+            .collect();
+        assert_eq!(errors, vec![expected]);
+    }
+
+    fn error_messages(schema: &Value, instance: &Value) -> Vec<String> {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        let mut errors: Vec<_> = match compiled.validate(instance) {
+            Ok(()) => vec![],
+            Err(errors) => errors.map(|error| error.to_string()).collect(),
+        };
+        errors.sort();
+        errors
+    }
+
+    #[test_case(1, 3)]
+    #[test_case(3, 1)]
+    #[test_case(2, 2)]
+    #[test_case(1.5, 2.5)]
+    fn range_errors(minimum: impl Into<Value>, maximum: impl Into<Value>) {
+        let (minimum, maximum) = (minimum.into(), maximum.into());
+        let range = json!({"minimum": minimum, "maximum": maximum});
+        // Not inlined, hence compiled without the range optimization
+        let separate = json!({"allOf": [{"minimum": minimum}, {"maximum": maximum}]});
+        for instance in &[json!(0), json!(2), json!(4), json!(-1.5), json!("a")] {
+            assert_eq!(
+                error_messages(&range, instance),
+                error_messages(&separate, instance),
+                "{} against {}",
+                instance,
+                range
+            );
+        }
+    }
+}
@@ -1,4 +1,6 @@
 use crate::{
+    analysis::analyze,
+    cache::DocumentCache,
     compilation::{compile_validators, context::CompilationContext, JSONSchema, DEFAULT_SCOPE},
     content_encoding::{
         ContentEncodingCheckType, ContentEncodingConverterType,
@@ -9,6 +11,7 @@ use crate::{
     resolver::Resolver,
     schemas,
 };
+use once_cell::sync::OnceCell;
 use serde_json::Value;
 use std::{borrow::Cow, collections::HashMap, fmt};
 
@@ -22,6 +25,9 @@ pub struct CompilationOptions {
     content_media_type_checks: HashMap<&'static str, Option<ContentMediaTypeCheckType>>,
     content_encoding_checks_and_converters:
         HashMap<&'static str, Option<(ContentEncodingCheckType, ContentEncodingConverterType)>>,
+    document_cache: DocumentCache,
+    max_errors: Option<usize>,
+    allow_file_references: bool,
 }
 
 impl CompilationOptions {
@@ -29,8 +35,59 @@ impl CompilationOptions {
         self.draft.unwrap_or_default()
     }
 
+    pub(crate) const fn max_errors(&self) -> Option<usize> {
+        self.max_errors
+    }
+
     /// Compile `schema` into `JSONSchema` using the currently defined options.
     pub fn compile<'a>(&self, schema: &'a Value) -> Result<JSONSchema<'a>, CompilationError> {
+        let config = self.with_detected_draft(schema);
+        let draft = config.draft();
+        let scope = scope_of(draft, schema)?;
+        let resolver = Resolver::new(
+            draft,
+            &scope,
+            schema,
+            self.document_cache.clone(),
+            self.allow_file_references,
+        )?;
+        build(config, scope, resolver, schema)
+    }
+
+    /// Compile `schema` into `JSONSchema` using the currently defined options.
+    ///
+    /// Unlike `compile`, all remote documents referenced by the schema are loaded upfront
+    /// without blocking the current thread, therefore it is safe to use inside an async runtime.
+    /// The returned `JSONSchema` does not perform any network requests during validation.
+    ///
+    /// ```rust
+    /// # use jsonschema::{CompilationError, JSONSchema};
+    /// # async fn foo() -> Result<(), CompilationError> {
+    /// let schema = serde_json::json!({"$ref": "http://json-schema.org/draft-07/schema#"});
+    /// let compiled = JSONSchema::options().compile_async(&schema).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn compile_async<'a>(
+        &self,
+        schema: &'a Value,
+    ) -> Result<JSONSchema<'a>, CompilationError> {
+        let config = self.with_detected_draft(schema);
+        let draft = config.draft();
+        let scope = scope_of(draft, schema)?;
+        let mut resolver = Resolver::new(
+            draft,
+            &scope,
+            schema,
+            self.document_cache.clone(),
+            self.allow_file_references,
+        )?;
+        resolver.prefetch(draft, &scope, schema).await?;
+        build(config, scope, resolver, schema)
+    }
+
+    fn with_detected_draft(&self, schema: &Value) -> CompilationOptions {
         // Draft is detected in the following precedence order:
         //   - Explicitly specified;
         //   - $schema field in the document;
@@ -48,25 +105,7 @@ impl CompilationOptions {
                 config.with_draft(draft);
             }
         }
-        let processed_config: Cow<'_, CompilationOptions> = Cow::Owned(config);
-        let draft = processed_config.draft();
-
-        let scope = match schemas::id_of(draft, schema) {
-            Some(url) => url::Url::parse(url)?,
-            None => DEFAULT_SCOPE.clone(),
-        };
-        let resolver = Resolver::new(draft, &scope, schema)?;
-        let context = CompilationContext::new(scope, processed_config);
-
-        let mut validators = compile_validators(schema, &context)?;
-        validators.shrink_to_fit();
-
-        Ok(JSONSchema {
-            schema,
-            resolver,
-            validators,
-            context,
-        })
+        config
     }
 
     /// Ensure that the schema is going to be compiled using the defined Draft.
@@ -82,6 +121,61 @@ impl CompilationOptions {
         self
     }
 
+    /// Use `cache` to store remote documents loaded during reference resolution.
+    ///
+    /// By default, only schemas compiled with the same `CompilationOptions` share the cache.
+    /// Sharing a cache between many `CompilationOptions` makes each remote document to be
+    /// loaded only once.
+    ///
+    /// ```rust
+    /// # use jsonschema::{CompilationOptions, DocumentCache};
+    /// let cache = DocumentCache::default();
+    /// let mut first = CompilationOptions::default();
+    /// first.with_document_cache(cache.clone());
+    /// let mut second = CompilationOptions::default();
+    /// second.with_document_cache(cache);
+    /// ```
+    #[inline]
+    pub fn with_document_cache(&mut self, cache: DocumentCache) -> &mut Self {
+        self.document_cache = cache;
+        self
+    }
+
+    /// Allow `$ref` to point to local files via the `file` scheme.
+    ///
+    /// It is disabled by default, as a schema from an untrusted source could otherwise read
+    /// any file that is readable by the current process.
+    ///
+    /// ```rust
+    /// # use jsonschema::CompilationOptions;
+    /// # let mut options = CompilationOptions::default();
+    /// options.with_file_references();
+    /// ```
+    #[inline]
+    pub fn with_file_references(&mut self) -> &mut Self {
+        self.allow_file_references = true;
+        self
+    }
+
+    /// Stop validation in `JSONSchema::validate` once `limit` errors are found.
+    /// Validators that would report further errors are not evaluated at all.
+    ///
+    /// # Panics
+    ///
+    /// If `limit` is zero, since an invalid instance could not report any error.
+    ///
+    /// ```rust
+    /// # use jsonschema::CompilationOptions;
+    /// # let mut options = CompilationOptions::default();
+    /// options.with_max_errors(10);
+    /// ```
+    #[inline]
+    pub fn with_max_errors(&mut self, limit: usize) -> &mut Self {
+        assert!(limit > 0, "The error limit must be greater than zero");
+        self.max_errors = Some(limit);
+        self
+    }
+
     pub(crate) fn content_media_type_check(
         &self,
         media_type: &str,
@@ -253,6 +347,35 @@ impl CompilationOptions {
     }
 }
 
+fn scope_of(draft: schemas::Draft, schema: &Value) -> Result<url::Url, CompilationError> {
+    match schemas::id_of(draft, schema) {
+        Some(url) => Ok(url::Url::parse(url)?),
+        None => Ok(DEFAULT_SCOPE.clone()),
+    }
+}
+
+fn build<'a>(
+    config: CompilationOptions,
+    scope: url::Url,
+    resolver: Resolver<'a>,
+    schema: &'a Value,
+) -> Result<JSONSchema<'a>, CompilationError> {
+    let context = CompilationContext::new(scope, Cow::Owned(config));
+
+    let mut validators = compile_validators(schema, &context)?;
+    validators.shrink_to_fit();
+    let warnings = analyze(context.config.draft(), schema);
+
+    Ok(JSONSchema {
+        schema,
+        resolver,
+        validators,
+        plan: OnceCell::new(),
+        context,
+        warnings,
+    })
+}
+
 impl fmt::Debug for CompilationOptions {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt.debug_struct("CompilationConfig")
@@ -262,6 +385,9 @@ impl fmt::Debug for CompilationOptions {
                 "content_encoding",
                 &self.content_encoding_checks_and_converters.keys(),
             )
+            .field("document_cache", &self.document_cache)
+            .field("max_errors", &self.max_errors)
+            .field("allow_file_references", &self.allow_file_references)
             .finish()
     }
 }
@@ -269,7 +395,7 @@ impl fmt::Debug for CompilationOptions {
 #[cfg(test)]
 mod tests {
     use super::CompilationOptions;
-    use crate::schemas::Draft;
+    use crate::{schemas::Draft, DocumentCache};
     use serde_json::{json, Value};
     use test_case::test_case;
 
@@ -287,4 +413,72 @@ mod tests {
         let compiled = options.compile(schema).unwrap();
         compiled.context.config.draft()
     }
+
+    #[test]
+    fn test_document_cache_is_shared_between_schemas() {
+        let mut server = mockito::Server::new();
+        let remote = server
+            .mock("GET", "/integer.json")
+            .with_body(r#"{"type": "integer"}"#)
+            .expect(1)
+            .create();
+        let schema = json!({"$ref": format!("{}/integer.json", server.url())});
+        let cache = DocumentCache::default();
+        for _ in 0..2 {
+            let compiled = CompilationOptions::default()
+                .with_document_cache(cache.clone())
+                .compile(&schema)
+                .unwrap();
+            assert!(compiled.is_valid(&json!(1)));
+            assert!(!compiled.is_valid(&json!("a")));
+        }
+        remote.assert();
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_compile_async_loads_remote_documents_upfront() {
+        let mut server = mockito::Server::new_async().await;
+        let root = server
+            .mock("GET", "/root.json")
+            .with_body(r#"{"items": {"$ref": "item.json"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let item = server
+            .mock("GET", "/item.json")
+            .with_body(r#"{"type": "integer"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let schema = json!({"$ref": format!("{}/root.json", server.url())});
+        let compiled = CompilationOptions::default()
+            .compile_async(&schema)
+            .await
+            .unwrap();
+        root.assert_async().await;
+        item.assert_async().await;
+        // Validation is served from the loaded documents
+        assert!(compiled.is_valid(&json!([1, 2])));
+        assert!(!compiled.is_valid(&json!([1, "a"])));
+        root.assert_async().await;
+        item.assert_async().await;
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_compile_async_unreachable_document() {
+        let mut server = mockito::Server::new_async().await;
+        let _missing = server
+            .mock("GET", "/missing.json")
+            .with_status(404)
+            .create_async()
+            .await;
+        let schema = json!({"$ref": format!("{}/missing.json", server.url())});
+        assert!(CompilationOptions::default()
+            .compile_async(&schema)
+            .await
+            .is_err());
+    }
 }
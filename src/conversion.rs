@@ -0,0 +1,684 @@
+//! Converting schemas between drafts.
+use crate::{
+    paths::{JSONPointer, PathChunk},
+    schemas::{subschemas_mut, Draft, Position, ARRAY, MAP, SINGLE},
+};
+use serde_json::{json, Map, Value};
+use std::fmt;
+
+/// Keywords that are kept next to `$ref`.
+const REFERENCE_SIBLINGS: [&str; 7] = [
+    "$comment",
+    "$defs",
+    "$id",
+    "$schema",
+    "definitions",
+    "description",
+    "title",
+];
+
+/// JSON Schema versions that schemas can be converted between.
+///
+/// Draft 2019-09 is only supported as a source or a target of conversion, compiling such schemas
+/// is not supported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SchemaVersion {
+    /// JSON Schema Draft 4
+    Draft4,
+    /// JSON Schema Draft 6
+    Draft6,
+    /// JSON Schema Draft 7
+    Draft7,
+    /// JSON Schema Draft 2019-09
+    Draft201909,
+}
+
+impl SchemaVersion {
+    /// Detect the version from the `$schema` keyword.
+    #[must_use]
+    pub fn detect(schema: &Value) -> Option<Self> {
+        match schema.get("$schema").and_then(Value::as_str)? {
+            "http://json-schema.org/draft-04/schema#" => Some(SchemaVersion::Draft4),
+            "http://json-schema.org/draft-06/schema#" => Some(SchemaVersion::Draft6),
+            "http://json-schema.org/draft-07/schema#" => Some(SchemaVersion::Draft7),
+            "https://json-schema.org/draft/2019-09/schema"
+            | "https://json-schema.org/draft/2019-09/schema#" => Some(SchemaVersion::Draft201909),
+            _ => None,
+        }
+    }
+
+    fn url(self) -> &'static str {
+        match self {
+            SchemaVersion::Draft4 => "http://json-schema.org/draft-04/schema#",
+            SchemaVersion::Draft6 => "http://json-schema.org/draft-06/schema#",
+            SchemaVersion::Draft7 => "http://json-schema.org/draft-07/schema#",
+            SchemaVersion::Draft201909 => "https://json-schema.org/draft/2019-09/schema",
+        }
+    }
+}
+
+impl From<Draft> for SchemaVersion {
+    fn from(draft: Draft) -> Self {
+        match draft {
+            Draft::Draft4 => SchemaVersion::Draft4,
+            Draft::Draft6 => SchemaVersion::Draft6,
+            Draft::Draft7 => SchemaVersion::Draft7,
+        }
+    }
+}
+
+/// A construct that could not be converted exactly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionIssue {
+    /// Location of the keyword in the converted schema.
+    pub path: JSONPointer,
+    /// What happened to the keyword.
+    pub message: String,
+}
+
+impl fmt::Display for ConversionIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// The result of converting a schema to another draft.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conversion {
+    /// The converted schema.
+    pub schema: Value,
+    /// Constructs that have no equivalent in the target draft.
+    pub issues: Vec<ConversionIssue>,
+}
+
+/// Convert a schema from one draft to another.
+///
+/// Conversion goes through the intermediate drafts one by one and rewrites keywords whose
+/// syntax changed, e.g. boolean `exclusiveMinimum` in Draft 4, `dependencies` in Draft 2019-09 or
+/// `if` / `then` / `else` that are expressed via `anyOf` for Draft 6 and older. Local `$ref`
+/// pointers are updated when `definitions` or `dependencies` are renamed.
+/// Keywords without an equivalent are kept as they are and reported in `Conversion::issues`.
+/// `$schema` of the result is set to the target draft.
+///
+/// ```rust
+/// use jsonschema::{convert, SchemaVersion};
+/// use serde_json::json;
+///
+/// let schema = json!({"id": "http://example.com/", "minimum": 5, "exclusiveMinimum": true});
+/// let conversion = convert(&schema, SchemaVersion::Draft4, SchemaVersion::Draft7);
+/// assert_eq!(
+///     conversion.schema,
+///     json!({
+///         "$schema": "http://json-schema.org/draft-07/schema#",
+///         "$id": "http://example.com/",
+///         "exclusiveMinimum": 5
+///     })
+/// );
+/// assert!(conversion.issues.is_empty());
+/// ```
+#[must_use]
+pub fn convert(schema: &Value, from: SchemaVersion, to: SchemaVersion) -> Conversion {
+    let mut schema = schema.clone();
+    let mut issues = vec![];
+    let mut version = from;
+    while version != to {
+        let (next, step): (_, Option<Step>) = if version < to {
+            match version {
+                SchemaVersion::Draft4 => (SchemaVersion::Draft6, Some(upgrade_draft4)),
+                SchemaVersion::Draft6 => (SchemaVersion::Draft7, None),
+                SchemaVersion::Draft7 | SchemaVersion::Draft201909 => {
+                    (SchemaVersion::Draft201909, Some(upgrade_draft7))
+                }
+            }
+        } else {
+            match version {
+                SchemaVersion::Draft201909 => (SchemaVersion::Draft7, Some(downgrade_draft201909)),
+                SchemaVersion::Draft7 => (SchemaVersion::Draft6, Some(downgrade_draft7)),
+                SchemaVersion::Draft6 | SchemaVersion::Draft4 => {
+                    (SchemaVersion::Draft4, Some(downgrade_draft6))
+                }
+            }
+        };
+        if let Some(step) = step {
+            walk(
+                &mut schema,
+                &mut vec![],
+                None,
+                &mut |schema, path, keyword| {
+                    step(
+                        schema,
+                        &mut Context {
+                            path,
+                            keyword,
+                            issues: &mut issues,
+                        },
+                    )
+                },
+            );
+        }
+        version = next;
+    }
+    if let Value::Object(object) = &mut schema {
+        object.insert("$schema".to_string(), Value::String(to.url().to_string()));
+    }
+    Conversion { schema, issues }
+}
+
+/// Rewrites a single subschema for the next draft.
+type Step = fn(&mut Value, &mut Context<'_>);
+
+struct Context<'a> {
+    /// Location of the subschema.
+    path: &'a [PathChunk],
+    /// The keyword that contains the subschema if it is the only subschema of this keyword.
+    keyword: Option<&'a str>,
+    issues: &'a mut Vec<ConversionIssue>,
+}
+
+impl Context<'_> {
+    fn report(&mut self, keyword: &str, message: String) {
+        let mut path = self.path.to_vec();
+        path.push(PathChunk::Property(keyword.to_string()));
+        self.issues.push(ConversionIssue {
+            path: JSONPointer::from(path),
+            message,
+        })
+    }
+
+    fn unsupported(&mut self, object: &Map<String, Value>, keywords: &[&str], draft: &str) {
+        for keyword in keywords {
+            if object.contains_key(*keyword) {
+                self.report(
+                    keyword,
+                    format!("`{}` is not supported by {} and is ignored", keyword, draft),
+                )
+            }
+        }
+    }
+}
+
+/// Called with a subschema, its location and the keyword that contains it.
+type Visitor<'v> = dyn FnMut(&mut Value, &[PathChunk], Option<&str>) + 'v;
+
+/// Call `visit` on every subschema, before visiting its own subschemas.
+fn walk(
+    schema: &mut Value,
+    path: &mut Vec<PathChunk>,
+    keyword: Option<&str>,
+    visit: &mut Visitor<'_>,
+) {
+    visit(schema, path, keyword);
+    if let Value::Object(object) = schema {
+        for (keyword, position, subschema) in subschemas_mut(object) {
+            let depth = path.len();
+            path.extend(position.path(keyword));
+            let keyword = match position {
+                Position::Whole => Some(keyword),
+                _ => None,
+            };
+            walk(subschema, path, keyword, visit);
+            path.truncate(depth);
+        }
+    }
+}
+
+/// Add a constraint to the schema. If the keyword is already used, the constraint goes to
+/// `allOf`.
+fn add_constraint(object: &mut Map<String, Value>, keyword: &str, value: Value) {
+    if object.contains_key(keyword) {
+        let mut constraint = Map::new();
+        constraint.insert(keyword.to_string(), value);
+        match object
+            .entry("allOf")
+            .or_insert_with(|| Value::Array(vec![]))
+        {
+            Value::Array(items) => items.push(Value::Object(constraint)),
+            other => *other = json!([other.clone(), constraint]),
+        }
+    } else {
+        object.insert(keyword.to_string(), value);
+    }
+}
+
+/// Remove the keyword if its value is an object.
+fn take_object(object: &mut Map<String, Value>, keyword: &str) -> Option<Map<String, Value>> {
+    match object.get(keyword) {
+        Some(Value::Object(_)) => match object.remove(keyword) {
+            Some(Value::Object(map)) => Some(map),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Move entries into an object under `keyword`. Entries with the same name are kept.
+fn merge_into(
+    object: &mut Map<String, Value>,
+    keyword: &str,
+    entries: Map<String, Value>,
+    context: &mut Context<'_>,
+) {
+    if entries.is_empty() {
+        return;
+    }
+    match object
+        .entry(keyword)
+        .or_insert_with(|| Value::Object(Map::new()))
+    {
+        Value::Object(existing) => {
+            for (name, value) in entries {
+                if existing.contains_key(&name) {
+                    context.report(
+                        keyword,
+                        format!("`{}` is already defined and is not replaced", name),
+                    )
+                } else {
+                    existing.insert(name, value);
+                }
+            }
+        }
+        _ => context.report(keyword, format!("`{}` is not an object", keyword)),
+    }
+}
+
+/// Rename keywords within the JSON pointer in a local `$ref`.
+fn rename_reference(object: &mut Map<String, Value>, renames: &[(&str, &str)]) {
+    let reference = match object.get_mut("$ref") {
+        Some(Value::String(reference)) if reference.starts_with("#/") => reference,
+        _ => return,
+    };
+    let mut segments: Vec<String> = reference.split('/').map(str::to_string).collect();
+    // Whether the next segment is a keyword, i.e. the pointer so far points to a subschema
+    let mut at_schema = true;
+    let mut previous = String::new();
+    for segment in segments.iter_mut().skip(1) {
+        if at_schema {
+            if previous == "items" && segment.parse::<usize>().is_ok() {
+                previous.clear();
+                continue;
+            }
+            if let Some((_, to)) = renames.iter().find(|(from, _)| from == segment) {
+                *segment = (*to).to_string();
+            }
+            if MAP.contains(&segment.as_str()) || ARRAY.contains(&segment.as_str()) {
+                at_schema = segment == "items";
+            } else if !SINGLE.contains(&segment.as_str()) {
+                break;
+            }
+            previous = segment.clone();
+        } else {
+            at_schema = true;
+            previous.clear();
+        }
+    }
+    *reference = segments.join("/");
+}
+
+/// Draft 4 -> Draft 6.
+fn upgrade_draft4(schema: &mut Value, context: &mut Context<'_>) {
+    let object = match schema {
+        Value::Object(object) => object,
+        _ => return,
+    };
+    if let Some(id) = object.remove("id") {
+        if object.contains_key("$id") {
+            context.report(
+                "id",
+                "both `id` and `$id` are present, `id` is dropped".to_string(),
+            )
+        } else {
+            object.insert("$id".to_string(), id);
+        }
+    }
+    for (exclusive, limit) in &[
+        ("exclusiveMinimum", "minimum"),
+        ("exclusiveMaximum", "maximum"),
+    ] {
+        match object.get(*exclusive) {
+            Some(Value::Bool(true)) => match object.remove(*limit) {
+                Some(value) => {
+                    object.insert((*exclusive).to_string(), value);
+                }
+                None => {
+                    object.remove(*exclusive);
+                    context.report(
+                        exclusive,
+                        format!(
+                            "`{}` without `{}` has no effect and is dropped",
+                            exclusive, limit
+                        ),
+                    )
+                }
+            },
+            Some(Value::Bool(false)) => {
+                object.remove(*exclusive);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Draft 6 -> Draft 4.
+fn downgrade_draft6(schema: &mut Value, context: &mut Context<'_>) {
+    let object = match schema {
+        Value::Bool(value) => {
+            // These keywords accept booleans in Draft 4 as well
+            if !matches!(
+                context.keyword,
+                Some("additionalProperties") | Some("additionalItems")
+            ) {
+                *schema = if *value {
+                    json!({})
+                } else {
+                    json!({"not": {}})
+                };
+            }
+            return;
+        }
+        Value::Object(object) => object,
+        _ => return,
+    };
+    if let Some(id) = object.remove("$id") {
+        if object.contains_key("id") {
+            context.report(
+                "$id",
+                "both `id` and `$id` are present, `$id` is dropped".to_string(),
+            )
+        } else {
+            object.insert("id".to_string(), id);
+        }
+    }
+    for (exclusive, limit, is_lower) in &[
+        ("exclusiveMinimum", "minimum", true),
+        ("exclusiveMaximum", "maximum", false),
+    ] {
+        let bound = match object.get(*exclusive) {
+            Some(bound) if bound.is_number() => bound.clone(),
+            _ => continue,
+        };
+        object.remove(*exclusive);
+        let limit_value = object.get(*limit).and_then(Value::as_f64);
+        // The inclusive limit is kept if it is stricter than the exclusive one
+        match (limit_value, bound.as_f64()) {
+            (Some(limit), Some(bound)) if limit != bound && (limit > bound) == *is_lower => {}
+            _ => {
+                object.insert((*limit).to_string(), bound);
+                object.insert((*exclusive).to_string(), Value::Bool(true));
+            }
+        }
+    }
+    if let Some(value) = object.remove("const") {
+        add_constraint(object, "enum", json!([value]));
+    }
+    if let Some(value) = object.remove("contains") {
+        // Instances that are not arrays are valid under `contains`
+        add_constraint(
+            object,
+            "not",
+            json!({"type": "array", "items": {"not": value}}),
+        );
+    }
+    if object.get("required") == Some(&json!([])) {
+        object.remove("required");
+    }
+    context.unsupported(object, &["propertyNames"], "Draft 4");
+}
+
+/// Draft 7 -> Draft 6.
+fn downgrade_draft7(schema: &mut Value, context: &mut Context<'_>) {
+    let object = match schema {
+        Value::Object(object) => object,
+        _ => return,
+    };
+    if let Some(condition) = object.remove("if") {
+        let then = object.remove("then");
+        let otherwise = object.remove("else");
+        if then.is_some() || otherwise.is_some() {
+            let matched = match then {
+                Some(then) => json!({"allOf": [condition.clone(), then]}),
+                None => condition.clone(),
+            };
+            let unmatched = match otherwise {
+                Some(otherwise) => json!({"allOf": [{"not": condition}, otherwise]}),
+                None => json!({"not": condition}),
+            };
+            add_constraint(object, "anyOf", json!([matched, unmatched]));
+        }
+    }
+    context.unsupported(object, &["contentMediaType", "contentEncoding"], "Draft 6");
+}
+
+/// Draft 7 -> Draft 2019-09.
+fn upgrade_draft7(schema: &mut Value, context: &mut Context<'_>) {
+    let object = match schema {
+        Value::Object(object) => object,
+        _ => return,
+    };
+    if let Some(definitions) = take_object(object, "definitions") {
+        merge_into(object, "$defs", definitions, context);
+    }
+    if let Some(dependencies) = take_object(object, "dependencies") {
+        let (mut required, mut schemas) = (Map::new(), Map::new());
+        for (name, value) in dependencies {
+            if value.is_array() {
+                required.insert(name, value);
+            } else {
+                schemas.insert(name, value);
+            }
+        }
+        merge_into(object, "dependentRequired", required, context);
+        merge_into(object, "dependentSchemas", schemas, context);
+    }
+    rename_reference(
+        object,
+        &[
+            ("definitions", "$defs"),
+            ("dependencies", "dependentSchemas"),
+        ],
+    );
+    // Keywords next to `$ref` are ignored before Draft 2019-09
+    if object.contains_key("$ref") {
+        let ignored: Vec<String> = object
+            .keys()
+            .filter(|keyword| *keyword != "$ref" && !REFERENCE_SIBLINGS.contains(&keyword.as_str()))
+            .cloned()
+            .collect();
+        for keyword in ignored {
+            object.remove(&keyword);
+            context.report(
+                &keyword,
+                format!(
+                    "`{}` next to `$ref` is ignored in Draft 7 and is dropped",
+                    keyword
+                ),
+            )
+        }
+    }
+}
+
+/// Draft 2019-09 -> Draft 7.
+fn downgrade_draft201909(schema: &mut Value, context: &mut Context<'_>) {
+    let object = match schema {
+        Value::Object(object) => object,
+        _ => return,
+    };
+    if let Some(definitions) = take_object(object, "$defs") {
+        merge_into(object, "definitions", definitions, context);
+    }
+    let required = take_object(object, "dependentRequired").unwrap_or_default();
+    let schemas = take_object(object, "dependentSchemas").unwrap_or_default();
+    let mut dependencies = required;
+    for (name, schema) in schemas {
+        let schema = match dependencies.remove(&name) {
+            Some(names) => json!({"allOf": [{"required": names}, schema]}),
+            None => schema,
+        };
+        dependencies.insert(name, schema);
+    }
+    merge_into(object, "dependencies", dependencies, context);
+    rename_reference(
+        object,
+        &[
+            ("$defs", "definitions"),
+            ("dependentSchemas", "dependencies"),
+        ],
+    );
+    // Keywords next to `$ref` are ignored in Draft 7, unless both are in `allOf`
+    let has_siblings = object
+        .keys()
+        .any(|keyword| keyword != "$ref" && !REFERENCE_SIBLINGS.contains(&keyword.as_str()));
+    if has_siblings {
+        if let Some(reference) = object.remove("$ref") {
+            match object
+                .entry("allOf")
+                .or_insert_with(|| Value::Array(vec![]))
+            {
+                Value::Array(items) => items.push(json!({ "$ref": reference })),
+                other => *other = json!([other.clone(), { "$ref": reference }]),
+            }
+        }
+    }
+    context.unsupported(
+        object,
+        &[
+            "$anchor",
+            "$recursiveAnchor",
+            "$recursiveRef",
+            "$vocabulary",
+            "maxContains",
+            "minContains",
+            "unevaluatedItems",
+            "unevaluatedProperties",
+        ],
+        "Draft 7",
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert, SchemaVersion};
+    use crate::tests_util;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(
+        &json!({"minimum": 1, "exclusiveMinimum": true, "maximum": 5, "exclusiveMaximum": false}),
+        SchemaVersion::Draft4,
+        SchemaVersion::Draft6,
+        &json!({"exclusiveMinimum": 1, "maximum": 5}),
+        &[];
+        "draft 4 exclusive limits"
+    )]
+    #[test_case(
+        &json!({"id": "http://example.com/", "properties": {"id": {"exclusiveMaximum": true}}}),
+        SchemaVersion::Draft4,
+        SchemaVersion::Draft7,
+        &json!({"$id": "http://example.com/", "properties": {"id": {}}}),
+        &["/properties/id/exclusiveMaximum"];
+        "draft 4 id"
+    )]
+    #[test_case(
+        &json!({"$id": "http://example.com/", "const": 1, "exclusiveMinimum": 0, "minimum": -1, "properties": {"a": true, "b": false}, "additionalProperties": false}),
+        SchemaVersion::Draft6,
+        SchemaVersion::Draft4,
+        &json!({"id": "http://example.com/", "enum": [1], "minimum": 0, "exclusiveMinimum": true, "properties": {"a": {}, "b": {"not": {}}}, "additionalProperties": false}),
+        &[];
+        "draft 6 to draft 4"
+    )]
+    #[test_case(
+        &json!({"contains": {"type": "string"}, "propertyNames": {"maxLength": 3}}),
+        SchemaVersion::Draft6,
+        SchemaVersion::Draft4,
+        &json!({"not": {"type": "array", "items": {"not": {"type": "string"}}}, "propertyNames": {"maxLength": 3}}),
+        &["/propertyNames"];
+        "contains and property names"
+    )]
+    #[test_case(
+        &json!({"if": {"minimum": 0}, "then": {"multipleOf": 2}, "else": {"multipleOf": 3}}),
+        SchemaVersion::Draft7,
+        SchemaVersion::Draft6,
+        &json!({"anyOf": [{"allOf": [{"minimum": 0}, {"multipleOf": 2}]}, {"allOf": [{"not": {"minimum": 0}}, {"multipleOf": 3}]}]}),
+        &[];
+        "if then else"
+    )]
+    #[test_case(
+        &json!({"$ref": "#/definitions/a", "definitions": {"a": {"dependencies": {"b": ["c"], "d": {"$ref": "#/definitions/a/dependencies/b"}}}}}),
+        SchemaVersion::Draft7,
+        SchemaVersion::Draft201909,
+        &json!({"$ref": "#/$defs/a", "$defs": {"a": {"dependentRequired": {"b": ["c"]}, "dependentSchemas": {"d": {"$ref": "#/$defs/a/dependentSchemas/b"}}}}}),
+        &[];
+        "draft 7 to draft 2019-09"
+    )]
+    #[test_case(
+        &json!({"properties": {"definitions": {"$ref": "#/properties/definitions"}}}),
+        SchemaVersion::Draft7,
+        SchemaVersion::Draft201909,
+        &json!({"properties": {"definitions": {"$ref": "#/properties/definitions"}}}),
+        &[];
+        "property names in references are kept"
+    )]
+    #[test_case(
+        &json!({"$ref": "#/definitions/a", "type": "string", "definitions": {"a": {}}}),
+        SchemaVersion::Draft7,
+        SchemaVersion::Draft201909,
+        &json!({"$ref": "#/$defs/a", "$defs": {"a": {}}}),
+        &["/type"];
+        "keywords next to references are dropped"
+    )]
+    #[test_case(
+        &json!({"$ref": "#/$defs/a", "type": "string", "$defs": {"a": {"unevaluatedProperties": false}}, "dependentRequired": {"a": ["b"]}, "dependentSchemas": {"a": {"maxProperties": 3}}}),
+        SchemaVersion::Draft201909,
+        SchemaVersion::Draft7,
+        &json!({"allOf": [{"$ref": "#/definitions/a"}], "type": "string", "definitions": {"a": {"unevaluatedProperties": false}}, "dependencies": {"a": {"allOf": [{"required": ["b"]}, {"maxProperties": 3}]}}}),
+        &["/definitions/a/unevaluatedProperties"];
+        "draft 2019-09 to draft 7"
+    )]
+    #[test_case(
+        &json!({"contentMediaType": "application/json", "const": "a", "enum": ["a", "b"]}),
+        SchemaVersion::Draft7,
+        SchemaVersion::Draft4,
+        &json!({"contentMediaType": "application/json", "enum": ["a", "b"], "allOf": [{"enum": ["a"]}]}),
+        &["/contentMediaType"];
+        "draft 7 to draft 4"
+    )]
+    fn conversion(
+        schema: &Value,
+        from: SchemaVersion,
+        to: SchemaVersion,
+        expected: &Value,
+        issues: &[&str],
+    ) {
+        let conversion = convert(schema, from, to);
+        let mut expected = expected.clone();
+        expected["$schema"] = json!(to.url());
+        assert_eq!(conversion.schema, expected);
+        let paths: Vec<_> = conversion
+            .issues
+            .iter()
+            .map(|issue| issue.path.to_string())
+            .collect();
+        assert_eq!(paths, issues);
+    }
+
+    #[test_case(SchemaVersion::Draft4, SchemaVersion::Draft201909)]
+    #[test_case(SchemaVersion::Draft201909, SchemaVersion::Draft4)]
+    fn schema_is_updated(from: SchemaVersion, to: SchemaVersion) {
+        let schema = json!({"$schema": from.url()});
+        let conversion = convert(&schema, from, to);
+        assert_eq!(SchemaVersion::detect(&conversion.schema), Some(to));
+    }
+
+    #[test_case(&json!(5), true; "not an array")]
+    #[test_case(&json!([1, "a"]), true; "array with a match")]
+    #[test_case(&json!([1, 2]), false; "array without a match")]
+    #[test_case(&json!([]), false; "empty array")]
+    fn downgraded_contains(instance: &Value, expected: bool) {
+        let schema = json!({"contains": {"type": "string"}});
+        let conversion = convert(&schema, SchemaVersion::Draft6, SchemaVersion::Draft4);
+        if expected {
+            tests_util::is_valid(&conversion.schema, instance)
+        } else {
+            tests_util::is_not_valid(&conversion.schema, instance)
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! Filling instances with values of the `default` keyword.
+use crate::{
+    compilation::JSONSchema,
+    error::{ErrorIterator, ValidationError},
+    subschemas::{self, collect, Result, Source},
+};
+use serde_json::Value;
+use std::{borrow::Cow, iter::once};
+use url::Url;
+
+fn default_of(compiled: &JSONSchema, schema: &Value, scope: &Url) -> Result<Option<Value>> {
+    let mut sources = vec![];
+    collect(
+        compiled,
+        Cow::Borrowed(schema),
+        scope,
+        &mut sources,
+        &mut vec![],
+    )?;
+    Ok(sources
+        .iter()
+        .find_map(|source| source.schema.get("default"))
+        .cloned())
+}
+
+fn apply(compiled: &JSONSchema, sources: &[Source<'_>], instance: &mut Value) -> Result<()> {
+    match instance {
+        Value::Object(object) => {
+            let present: Vec<String> = object.keys().cloned().collect();
+            for source in sources {
+                if let Some(Value::Object(properties)) = source.schema.get("properties") {
+                    for (name, property) in properties {
+                        if !object.contains_key(name) {
+                            if let Some(default) = default_of(compiled, property, &source.scope)? {
+                                object.insert(name.clone(), default);
+                            }
+                        }
+                    }
+                }
+            }
+            // Inserted defaults are taken as they are
+            for name in present {
+                let children = subschemas::property(compiled, sources, &name)?;
+                if let Some(value) = object.get_mut(&name) {
+                    apply(compiled, &children, value)?;
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                let children = subschemas::item(compiled, sources, index)?;
+                apply(compiled, &children, item)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+impl<'a> JSONSchema<'a> {
+    /// Insert values of the `default` keyword for missing object properties and validate the
+    /// result.
+    ///
+    /// Defaults are taken from `properties` of the schemas that apply to each object, including
+    /// ones referenced via `$ref` and listed in `allOf`. Existing properties and array items are
+    /// filled recursively. If several schemas provide a default for the same property, the first
+    /// one wins, in the order: the schema itself, then its `allOf` items. Inserted values are
+    /// not filled further.
+    ///
+    /// ```rust
+    /// use jsonschema::JSONSchema;
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({"properties": {"port": {"type": "integer", "default": 8080}}});
+    /// let compiled = JSONSchema::compile(&schema).unwrap();
+    /// let mut config = json!({});
+    /// assert!(compiled.apply_defaults(&mut config).is_ok());
+    /// assert_eq!(config, json!({"port": 8080}));
+    /// ```
+    pub fn apply_defaults(
+        &self,
+        instance: &mut Value,
+    ) -> std::result::Result<(), ErrorIterator<'static>> {
+        subschemas::root(self)
+            .and_then(|sources| apply(self, &sources, instance))
+            .map_err(|error| -> ErrorIterator<'static> { Box::new(once(error)) })?;
+        let errors: Vec<_> = match self.validate(instance) {
+            Ok(()) => return Ok(()),
+            Err(errors) => errors.map(ValidationError::into_owned).collect(),
+        };
+        Err(Box::new(errors.into_iter()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::JSONSchema;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(
+        &json!({"properties": {"a": {"default": 1}, "b": {"default": 2}}}),
+        json!({"b": 3}),
+        &json!({"a": 1, "b": 3});
+        "missing properties"
+    )]
+    #[test_case(
+        &json!({"properties": {"nested": {"properties": {"a": {"default": 1}}}}}),
+        json!({"nested": {}}),
+        &json!({"nested": {"a": 1}});
+        "nested objects"
+    )]
+    #[test_case(
+        &json!({"properties": {"nested": {"default": {}, "properties": {"a": {"default": 1}}}}}),
+        json!({}),
+        &json!({"nested": {}});
+        "inserted values are not filled"
+    )]
+    #[test_case(
+        &json!({
+            "$ref": "#/definitions/config",
+            "definitions": {
+                "config": {"properties": {"a": {"$ref": "#/definitions/a"}}},
+                "a": {"default": 1}
+            }
+        }),
+        json!({}),
+        &json!({"a": 1});
+        "references"
+    )]
+    #[test_case(
+        &json!({
+            "properties": {"a": {"default": 1}},
+            "allOf": [{"properties": {"a": {"default": 2}, "b": {"default": 3}}}]
+        }),
+        json!({}),
+        &json!({"a": 1, "b": 3});
+        "all of"
+    )]
+    #[test_case(
+        &json!({"items": {"properties": {"a": {"default": 1}}}}),
+        json!([{}, {"a": 2}, 3]),
+        &json!([{"a": 1}, {"a": 2}, 3]);
+        "items"
+    )]
+    #[test_case(
+        &json!({"items": [{"properties": {"a": {"default": 1}}}]}),
+        json!([{}, {}]),
+        &json!([{"a": 1}, {}]);
+        "items array"
+    )]
+    #[test_case(
+        &json!({
+            "definitions": {"node": {"properties": {"child": {"$ref": "#/definitions/node"}, "value": {"default": 0}}}},
+            "$ref": "#/definitions/node"
+        }),
+        json!({"child": {"child": {}}}),
+        &json!({"value": 0, "child": {"value": 0, "child": {"value": 0}}});
+        "recursive references"
+    )]
+    fn apply_defaults(schema: &Value, mut instance: Value, expected: &Value) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        assert!(compiled.apply_defaults(&mut instance).is_ok());
+        assert_eq!(&instance, expected);
+    }
+
+    #[test]
+    fn invalid_result() {
+        let schema = json!({"properties": {"a": {"type": "string", "default": 1}}});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let mut instance = json!({});
+        let errors: Vec<_> = compiled
+            .apply_defaults(&mut instance)
+            .expect_err("The default is invalid")
+            .collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].instance_path().to_string(), "/a");
+    }
+}
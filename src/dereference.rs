@@ -0,0 +1,280 @@
+//! Replacing `$ref` with the schemas they point to.
+use crate::{
+    compilation::JSONSchema,
+    error::ValidationError,
+    paths::{JSONPointer, PathChunk},
+    schemas::{id_of, is_schema, nesting, Draft, Nesting},
+    subschemas::Result,
+};
+use serde_json::{Map, Value};
+use url::Url;
+
+/// A schema with references replaced by the schemas they point to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dereferenced {
+    /// The expanded schema.
+    pub schema: Value,
+    /// Locations of recursive references that were left in place.
+    pub recursive: Vec<JSONPointer>,
+}
+
+struct Expansion<'c, 'a> {
+    compiled: &'c JSONSchema<'a>,
+    draft: Draft,
+    /// References that are being expanded
+    stack: Vec<Url>,
+    path: Vec<PathChunk>,
+    recursive: Vec<JSONPointer>,
+}
+
+impl Expansion<'_, '_> {
+    /// Expand references in `schema`.
+    ///
+    /// `scope` is the base URL of `schema` in its original document, and `output_scope` is the
+    /// base URL of the location it is copied to. They differ when `schema` is reached via a
+    /// reference to another resource.
+    fn expand(
+        &mut self,
+        schema: &Value,
+        scope: &Url,
+        output_scope: &Url,
+        inlined: bool,
+    ) -> Result<Value> {
+        let object = match schema {
+            Value::Object(object) => object,
+            _ => return Ok(schema.clone()),
+        };
+        let id_keyword = if self.draft == Draft::Draft4 {
+            "id"
+        } else {
+            "$id"
+        };
+        let (scope, output_scope, id) = match id_of(self.draft, schema) {
+            Some(id) => {
+                let new_scope = Url::options().base_url(Some(scope)).parse(id)?;
+                // A relative identifier has a different meaning in another resource
+                let id = if scope == output_scope {
+                    id.to_string()
+                } else {
+                    new_scope.to_string()
+                };
+                (new_scope.clone(), new_scope, Some(id))
+            }
+            None => (scope.clone(), output_scope.clone(), None),
+        };
+        if let Some(Value::String(reference)) = object.get("$ref") {
+            let url = Url::options().base_url(Some(&scope)).parse(reference)?;
+            // `#` points to the same schema as the fragment-less URL
+            let mut location = url.clone();
+            if location.fragment() == Some("") {
+                location.set_fragment(None);
+            }
+            if self.stack.contains(&location) {
+                self.recursive.push(JSONPointer::from(self.path.clone()));
+                let reference = if scope == output_scope {
+                    reference.clone()
+                } else {
+                    url.to_string()
+                };
+                let mut result = Map::new();
+                result.insert("$ref".to_string(), Value::String(reference));
+                return Ok(Value::Object(result));
+            }
+            let (resolved_scope, resolved) = self
+                .compiled
+                .resolver
+                .resolve_fragment(self.draft, &url, self.compiled.schema)
+                .map_err(ValidationError::into_owned)?;
+            self.stack.push(location);
+            // Keywords next to `$ref` are ignored, except for the root `definitions` that
+            // recursive references may point to
+            let mut result = self.expand(&resolved, &resolved_scope, &output_scope, true)?;
+            self.stack.pop();
+            if !inlined {
+                if let (Value::Object(result), Some(definitions)) =
+                    (&mut result, object.get("definitions"))
+                {
+                    if !result.contains_key("definitions") {
+                        result.insert("definitions".to_string(), definitions.clone());
+                    }
+                }
+            }
+            return Ok(result);
+        }
+        let mut result = Map::new();
+        for (keyword, value) in object {
+            if keyword == id_keyword {
+                if let Some(id) = &id {
+                    result.insert(keyword.clone(), Value::String(id.clone()));
+                    continue;
+                }
+            }
+            self.path.push(PathChunk::Property(keyword.clone()));
+            let value = match (keyword.as_str(), nesting(keyword, value), value) {
+                // Only recursive references may point to definitions and they are resolved
+                // against the original document
+                ("definitions", _, _) if inlined => {
+                    self.path.pop();
+                    continue;
+                }
+                ("definitions", _, _) | (_, None, _) => value.clone(),
+                (_, Some(Nesting::Map), Value::Object(subschemas)) => {
+                    let mut expanded = Map::new();
+                    for (name, subschema) in subschemas {
+                        let subschema = if is_schema(subschema) {
+                            self.path.push(PathChunk::Property(name.clone()));
+                            let subschema =
+                                self.expand(subschema, &scope, &output_scope, inlined)?;
+                            self.path.pop();
+                            subschema
+                        } else {
+                            subschema.clone()
+                        };
+                        expanded.insert(name.clone(), subschema);
+                    }
+                    Value::Object(expanded)
+                }
+                (_, Some(Nesting::Array), Value::Array(items)) => {
+                    let mut expanded = Vec::with_capacity(items.len());
+                    for (index, item) in items.iter().enumerate() {
+                        self.path.push(PathChunk::Index(index));
+                        expanded.push(self.expand(item, &scope, &output_scope, inlined)?);
+                        self.path.pop();
+                    }
+                    Value::Array(expanded)
+                }
+                _ => self.expand(value, &scope, &output_scope, inlined)?,
+            };
+            self.path.pop();
+            result.insert(keyword.clone(), value);
+        }
+        Ok(Value::Object(result))
+    }
+}
+
+impl<'a> JSONSchema<'a> {
+    /// Return a copy of the schema with every `$ref` replaced by the schema it points to.
+    ///
+    /// Recursive references can't be expanded, they are left in place and their locations are
+    /// reported in `Dereferenced::recursive`. These references stay resolvable: the root
+    /// `definitions` are kept as they are and references from other resources are made
+    /// absolute. Keywords next to `$ref` are ignored by the supported drafts and are dropped.
+    ///
+    /// ```rust
+    /// use jsonschema::JSONSchema;
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({
+    ///     "properties": {
+    ///         "name": {"$ref": "#/definitions/name"},
+    ///         "children": {"items": {"$ref": "#"}}
+    ///     },
+    ///     "definitions": {"name": {"type": "string"}}
+    /// });
+    /// let compiled = JSONSchema::compile(&schema).unwrap();
+    /// let dereferenced = compiled.dereference().unwrap();
+    /// assert_eq!(dereferenced.schema["properties"]["name"], json!({"type": "string"}));
+    /// assert_eq!(dereferenced.recursive[0].to_string(), "/properties/children/items");
+    /// ```
+    pub fn dereference(&self) -> std::result::Result<Dereferenced, ValidationError<'static>> {
+        let mut expansion = Expansion {
+            compiled: self,
+            draft: self.context.config.draft(),
+            stack: vec![],
+            path: vec![],
+            recursive: vec![],
+        };
+        let scope = self.context.scope.clone().into_owned();
+        expansion.stack.push(scope.clone());
+        let schema = expansion.expand(self.schema, &scope, &scope, false)?;
+        Ok(Dereferenced {
+            schema,
+            recursive: expansion.recursive,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DocumentCache, JSONSchema};
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+    use test_case::test_case;
+
+    #[test_case(
+        &json!({"properties": {"a": {"$ref": "#/definitions/a"}}, "definitions": {"a": {"$ref": "#/definitions/b"}, "b": {"type": "integer"}}}),
+        &json!({"properties": {"a": {"type": "integer"}}, "definitions": {"a": {"$ref": "#/definitions/b"}, "b": {"type": "integer"}}}),
+        &[];
+        "chained references"
+    )]
+    #[test_case(
+        &json!({"$ref": "#/definitions/node", "definitions": {"node": {"properties": {"next": {"$ref": "#/definitions/node"}}}}}),
+        &json!({"properties": {"next": {"$ref": "#/definitions/node"}}, "definitions": {"node": {"properties": {"next": {"$ref": "#/definitions/node"}}}}}),
+        &["/properties/next"];
+        "recursive references"
+    )]
+    #[test_case(
+        &json!({"items": {"$ref": "#foo"}, "definitions": {"a": {"$id": "#foo", "maxLength": 3}}}),
+        &json!({"items": {"$id": "#foo", "maxLength": 3}, "definitions": {"a": {"$id": "#foo", "maxLength": 3}}}),
+        &[];
+        "location-independent identifiers"
+    )]
+    #[test_case(
+        &json!({"allOf": [{"$ref": "#/definitions/a", "type": "string"}], "enum": [{"$ref": "#/definitions/a"}], "definitions": {"a": {}}}),
+        &json!({"allOf": [{}], "enum": [{"$ref": "#/definitions/a"}], "definitions": {"a": {}}}),
+        &[];
+        "siblings and instances"
+    )]
+    fn dereference(schema: &Value, expected: &Value, recursive: &[&str]) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        let dereferenced = compiled.dereference().unwrap();
+        assert_eq!(dereferenced.schema, *expected);
+        let paths: Vec<_> = dereferenced
+            .recursive
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(paths, recursive);
+    }
+
+    #[test]
+    fn scope_changes() {
+        let schema = json!({"properties": {"tree": {"$ref": "http://example.com/tree.json"}}});
+        let tree = json!({
+            "properties": {
+                "value": {"$ref": "#/definitions/value"},
+                "children": {"items": {"$ref": "#"}},
+                "meta": {"$id": "nested/", "properties": {"tag": {"$ref": "tag.json"}}}
+            },
+            "definitions": {"value": {"type": "integer"}}
+        });
+        let tag = json!({"type": "string"});
+        let cache = DocumentCache::default();
+        cache.insert("http://example.com/tree.json".to_string(), Arc::new(tree));
+        cache.insert(
+            "http://example.com/nested/tag.json".to_string(),
+            Arc::new(tag),
+        );
+        let compiled = JSONSchema::options()
+            .with_document_cache(cache.clone())
+            .compile(&schema)
+            .unwrap();
+        let dereferenced = compiled.dereference().unwrap();
+        assert_eq!(
+            dereferenced.schema,
+            json!({"properties": {"tree": {
+                "properties": {
+                    "value": {"type": "integer"},
+                    "children": {"items": {"$ref": "http://example.com/tree.json#"}},
+                    "meta": {"$id": "http://example.com/nested/", "properties": {"tag": {"type": "string"}}}
+                }
+            }}})
+        );
+        let compiled = JSONSchema::options()
+            .with_document_cache(cache)
+            .compile(&dereferenced.schema)
+            .unwrap();
+        assert!(compiled.is_valid(&json!({"tree": {"value": 1, "children": [{"value": 2}]}})));
+        assert!(!compiled.is_valid(&json!({"tree": {"children": [{"value": "2"}]}})));
+    }
+}
@@ -1,4 +1,8 @@
-use crate::primitive_type::{PrimitiveType, PrimitiveTypesBitMap};
+use crate::{
+    paths::{JSONPointer, PathChunk},
+    primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
+    source::Span,
+};
 use serde_json::{Map, Number, Value};
 use std::{
     borrow::Cow,
@@ -15,9 +19,17 @@ use std::{
 /// It includes cases when during validation a reference is resolved into an invalid schema,
 /// which we can't know upfront because schemas can be in remote locations.
 #[derive(Debug, PartialEq)]
+#[non_exhaustive]
 pub enum CompilationError {
     /// Invalid schema structure
     SchemaError,
+    /// A remote document referenced via `$ref` could not be loaded
+    RemoteDocument {
+        /// URL of the document
+        url: String,
+        /// Textual representation of the underlying error
+        reason: String,
+    },
 }
 
 impl error::Error for CompilationError {}
@@ -25,7 +37,12 @@ impl error::Error for CompilationError {}
 impl fmt::Display for CompilationError {
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        write!(f, "Schema compilation error")
+        match self {
+            CompilationError::SchemaError => write!(f, "Schema compilation error"),
+            CompilationError::RemoteDocument { url, reason } => {
+                write!(f, "Failed to load remote document '{}': {}", url, reason)
+            }
+        }
     }
 }
 
@@ -43,12 +60,42 @@ impl From<url::ParseError> for CompilationError {
 }
 
 /// An error that can occur during validation.
+///
+/// Besides the cases when the instance is invalid, it may represent a failure to load the schema
+/// itself (e.g. a remote document referenced via `$ref` is not available).
+/// Use `ValidationError::is_schema_error` to distinguish these cases.
 #[derive(Debug)]
 pub struct ValidationError<'a> {
     instance: Cow<'a, Value>,
+    /// Boxed since it is empty for errors at the root of the instance, which keeps errors small
+    location: Option<Box<InstanceLocation>>,
     kind: ValidationErrorKind,
 }
 
+/// Where the invalid value is located inside the instance.
+#[derive(Debug, Default)]
+struct InstanceLocation {
+    /// Chunks of the instance path from the invalid value up to the root. Errors propagate
+    /// from nested values to their containers, hence every container appends its own chunk.
+    reversed_path: Vec<PathChunk>,
+    span: Option<Span>,
+}
+
+/// Information about a `$ref` that failed to resolve.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReferenceContext {
+    /// The `$ref` value as it is written in the schema.
+    pub reference: String,
+    /// Absolute URL the reference points to.
+    pub url: String,
+    /// Base URI that was used to resolve the reference.
+    pub base_uri: String,
+    /// JSON Pointer to the `$ref` keyword, i.e. the keyword path from the root of the compiled
+    /// schema. Inside schemas reached via another `$ref`, the path starts at the JSON Pointer
+    /// of that reference.
+    pub schema_path: String,
+}
+
 /// An iterator over instances of `ValidationError` that represent validation error for the
 /// input instance.
 ///
@@ -104,19 +151,40 @@ pub(crate) enum ValidationErrorKind {
     /// Everything is invalid for `false` schema.
     FalseSchema,
     /// If the referenced file is not found during ref resolution.
-    FileNotFound { error: io::Error },
+    FileNotFound {
+        error: io::Error,
+        reference: Option<Box<ReferenceContext>>,
+    },
     /// When the input doesn't match to the specified format.
     Format { format: &'static str },
     /// May happen in `contentEncoding` validation if `base64` encoded data is invalid.
     FromUtf8 { error: FromUtf8Error },
     /// Invalid UTF-8 string during percent encoding when resolving happens
-    Utf8 { error: Utf8Error },
+    Utf8 {
+        error: Utf8Error,
+        reference: Option<Box<ReferenceContext>>,
+    },
     /// May happen during ref resolution when remote document is not a valid JSON.
-    JSONParse { error: serde_json::Error },
+    JSONParse {
+        error: serde_json::Error,
+        reference: Option<Box<ReferenceContext>>,
+    },
+    /// May happen during ref resolution when a YAML document is not valid.
+    #[cfg(feature = "yaml")]
+    YAMLParse {
+        error: serde_yaml::Error,
+        reference: Option<Box<ReferenceContext>>,
+    },
     /// `ref` value is not valid.
-    InvalidReference { reference: String },
+    InvalidReference {
+        reference: String,
+        context: Option<Box<ReferenceContext>>,
+    },
     /// Invalid URL, e.g. invalid port number or IP address
-    InvalidURL { error: url::ParseError },
+    InvalidURL {
+        error: url::ParseError,
+        reference: Option<Box<ReferenceContext>>,
+    },
     /// Too many items in an array.
     MaxItems { limit: u64 },
     /// Value is too large.
@@ -146,15 +214,25 @@ pub(crate) enum ValidationErrorKind {
     /// When a required property is missing.
     Required { property: String },
     /// Any error that happens during network request via `reqwest` crate
-    Reqwest { error: reqwest::Error },
+    Reqwest {
+        error: reqwest::Error,
+        reference: Option<Box<ReferenceContext>>,
+    },
     /// Resolved schema failed to compile.
-    Schema,
+    Schema {
+        reference: Option<Box<ReferenceContext>>,
+    },
+    /// The input value can not be represented as JSON.
+    Serialization { error: serde_json::Error },
     /// When the input value doesn't match one or multiple required types.
     Type { kind: TypeKind },
     /// When the input array has non-unique elements.
     UniqueItems,
     /// Reference contains unknown scheme.
-    UnknownReferenceScheme { scheme: String },
+    UnknownReferenceScheme {
+        scheme: String,
+        reference: Option<Box<ReferenceContext>>,
+    },
 
     /// Unexpected error. This usually represent a bug into the validation
     Unexpected { validator_representation: String },
@@ -171,6 +249,7 @@ impl<'a> ValidationError<'a> {
     pub(crate) fn into_owned(self) -> ValidationError<'static> {
         ValidationError {
             instance: Cow::Owned(self.instance.into_owned()),
+            location: self.location,
             kind: self.kind,
         }
     }
@@ -178,12 +257,14 @@ impl<'a> ValidationError<'a> {
     pub(crate) fn additional_items(instance: &'a Value, limit: usize) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::AdditionalItems { limit },
         }
     }
     pub(crate) fn any_of(instance: &'a Value) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::AnyOf,
         }
     }
@@ -193,6 +274,7 @@ impl<'a> ValidationError<'a> {
     ) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::Constant {
                 expected_value: Value::Array(expected_value.to_vec()),
             },
@@ -204,6 +286,7 @@ impl<'a> ValidationError<'a> {
     ) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::Constant {
                 expected_value: Value::Bool(expected_value),
             },
@@ -212,6 +295,7 @@ impl<'a> ValidationError<'a> {
     pub(crate) fn constant_null(instance: &'a Value) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::Constant {
                 expected_value: Value::Null,
             },
@@ -223,6 +307,7 @@ impl<'a> ValidationError<'a> {
     ) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::Constant {
                 expected_value: Value::Number(expected_value.clone()),
             },
@@ -234,6 +319,7 @@ impl<'a> ValidationError<'a> {
     ) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::Constant {
                 expected_value: Value::Object(expected_value.clone()),
             },
@@ -245,6 +331,7 @@ impl<'a> ValidationError<'a> {
     ) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::Constant {
                 expected_value: Value::String(expected_value.to_string()),
             },
@@ -253,12 +340,14 @@ impl<'a> ValidationError<'a> {
     pub(crate) fn contains(instance: &'a Value) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::Contains,
         }
     }
     pub(crate) fn content_encoding(instance: &'a Value, encoding: &str) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::ContentEncoding {
                 content_encoding: encoding.to_string(),
             },
@@ -267,6 +356,7 @@ impl<'a> ValidationError<'a> {
     pub(crate) fn content_media_type(instance: &'a Value, media_type: &str) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::ContentMediaType {
                 content_media_type: media_type.to_string(),
             },
@@ -275,6 +365,7 @@ impl<'a> ValidationError<'a> {
     pub(crate) fn enumeration(instance: &'a Value, options: &Value) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::Enum {
                 options: options.clone(),
             },
@@ -283,151 +374,209 @@ impl<'a> ValidationError<'a> {
     pub(crate) fn exclusive_maximum(instance: &'a Value, limit: f64) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::ExclusiveMaximum { limit },
         }
     }
     pub(crate) fn exclusive_minimum(instance: &'a Value, limit: f64) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::ExclusiveMinimum { limit },
         }
     }
     pub(crate) fn false_schema(instance: &'a Value) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::FalseSchema,
         }
     }
     pub(crate) fn file_not_found(error: io::Error) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Owned(Value::Null),
-            kind: ValidationErrorKind::FileNotFound { error },
+            location: None,
+            kind: ValidationErrorKind::FileNotFound {
+                error,
+                reference: None,
+            },
         }
     }
     pub(crate) fn format(instance: &'a Value, format: &'static str) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::Format { format },
         }
     }
     pub(crate) fn from_utf8(error: FromUtf8Error) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Owned(Value::Null),
+            location: None,
             kind: ValidationErrorKind::FromUtf8 { error },
         }
     }
     pub(crate) fn json_parse(error: serde_json::Error) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Owned(Value::Null),
-            kind: ValidationErrorKind::JSONParse { error },
+            location: None,
+            kind: ValidationErrorKind::JSONParse {
+                error,
+                reference: None,
+            },
+        }
+    }
+    #[cfg(feature = "yaml")]
+    pub(crate) fn yaml_parse(error: serde_yaml::Error) -> ValidationError<'a> {
+        ValidationError {
+            instance: Cow::Owned(Value::Null),
+            location: None,
+            kind: ValidationErrorKind::YAMLParse {
+                error,
+                reference: None,
+            },
         }
     }
     pub(crate) fn invalid_reference(reference: String) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Owned(Value::Null),
-            kind: ValidationErrorKind::InvalidReference { reference },
+            location: None,
+            kind: ValidationErrorKind::InvalidReference {
+                reference,
+                context: None,
+            },
         }
     }
     pub(crate) fn invalid_url(error: url::ParseError) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Owned(Value::Null),
-            kind: ValidationErrorKind::InvalidURL { error },
+            location: None,
+            kind: ValidationErrorKind::InvalidURL {
+                error,
+                reference: None,
+            },
         }
     }
     pub(crate) fn max_items(instance: &'a Value, limit: u64) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::MaxItems { limit },
         }
     }
     pub(crate) fn maximum(instance: &'a Value, limit: f64) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::Maximum { limit },
         }
     }
     pub(crate) fn max_length(instance: &'a Value, limit: u64) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::MaxLength { limit },
         }
     }
     pub(crate) fn max_properties(instance: &'a Value, limit: u64) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::MaxProperties { limit },
         }
     }
     pub(crate) fn min_items(instance: &'a Value, limit: u64) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::MinItems { limit },
         }
     }
     pub(crate) fn minimum(instance: &'a Value, limit: f64) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::Minimum { limit },
         }
     }
     pub(crate) fn min_length(instance: &'a Value, limit: u64) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::MinLength { limit },
         }
     }
     pub(crate) fn min_properties(instance: &'a Value, limit: u64) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::MinProperties { limit },
         }
     }
     pub(crate) fn multiple_of(instance: &'a Value, multiple_of: f64) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::MultipleOf { multiple_of },
         }
     }
     pub(crate) fn not(instance: &'a Value, schema: Value) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::Not { schema },
         }
     }
     pub(crate) fn one_of_multiple_valid(instance: &'a Value) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::OneOfMultipleValid,
         }
     }
     pub(crate) fn one_of_not_valid(instance: &'a Value) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::OneOfNotValid,
         }
     }
     pub(crate) fn pattern(instance: &'a Value, pattern: String) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::Pattern { pattern },
         }
     }
     pub(crate) fn required(instance: &'a Value, property: String) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::Required { property },
         }
     }
     pub(crate) fn reqwest(error: reqwest::Error) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Owned(Value::Null),
-            kind: ValidationErrorKind::Reqwest { error },
+            location: None,
+            kind: ValidationErrorKind::Reqwest {
+                error,
+                reference: None,
+            },
         }
     }
     pub(crate) fn schema() -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Owned(Value::Null),
-            kind: ValidationErrorKind::Schema,
+            location: None,
+            kind: ValidationErrorKind::Schema { reference: None },
+        }
+    }
+    pub(crate) fn serialization(error: serde_json::Error) -> ValidationError<'a> {
+        ValidationError {
+            instance: Cow::Owned(Value::Null),
+            location: None,
+            kind: ValidationErrorKind::Serialization { error },
         }
     }
     pub(crate) fn single_type_error(
@@ -436,6 +585,7 @@ impl<'a> ValidationError<'a> {
     ) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::Type {
                 kind: TypeKind::Single(type_name),
             },
@@ -447,6 +597,7 @@ impl<'a> ValidationError<'a> {
     ) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::Type {
                 kind: TypeKind::Multiple(types),
             },
@@ -455,21 +606,137 @@ impl<'a> ValidationError<'a> {
     pub(crate) fn unique_items(instance: &'a Value) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::UniqueItems,
         }
     }
     pub(crate) fn unknown_reference_scheme(scheme: String) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Owned(Value::Null),
-            kind: ValidationErrorKind::UnknownReferenceScheme { scheme },
+            location: None,
+            kind: ValidationErrorKind::UnknownReferenceScheme {
+                scheme,
+                reference: None,
+            },
+        }
+    }
+    /// Attach information about the `$ref` that was being resolved when the error happened.
+    /// Only errors that may happen during reference resolution are affected.
+    pub(crate) fn with_reference_context(mut self, context: ReferenceContext) -> Self {
+        match &mut self.kind {
+            ValidationErrorKind::FileNotFound { reference, .. }
+            | ValidationErrorKind::Utf8 { reference, .. }
+            | ValidationErrorKind::JSONParse { reference, .. }
+            | ValidationErrorKind::InvalidReference {
+                context: reference, ..
+            }
+            | ValidationErrorKind::InvalidURL { reference, .. }
+            | ValidationErrorKind::Reqwest { reference, .. }
+            | ValidationErrorKind::Schema { reference }
+            | ValidationErrorKind::UnknownReferenceScheme { reference, .. } => {
+                *reference = Some(Box::new(context))
+            }
+            #[cfg(feature = "yaml")]
+            ValidationErrorKind::YAMLParse { reference, .. } => {
+                *reference = Some(Box::new(context))
+            }
+            _ => {}
+        }
+        self
+    }
+    /// Mark the error as happened inside the given array item or object property.
+    /// Containers call it while errors propagate up, so the final path starts at the root.
+    #[inline]
+    pub(crate) fn within(mut self, chunk: impl Into<PathChunk>) -> Self {
+        self.location
+            .get_or_insert_with(Box::default)
+            .reversed_path
+            .push(chunk.into());
+        self
+    }
+
+    /// Location of the invalid value inside the validated instance.
+    #[must_use]
+    #[inline]
+    pub fn instance_path(&self) -> JSONPointer {
+        match &self.location {
+            Some(location) => JSONPointer::from(
+                location
+                    .reversed_path
+                    .iter()
+                    .rev()
+                    .cloned()
+                    .collect::<Vec<_>>(),
+            ),
+            None => JSONPointer::default(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn with_span(mut self, span: Option<Span>) -> Self {
+        if span.is_some() || self.location.is_some() {
+            self.location.get_or_insert_with(Box::default).span = span;
+        }
+        self
+    }
+
+    /// Location of the invalid value in the source text.
+    /// It is available only for documents validated via `JSONSchema::validate_document`.
+    #[must_use]
+    #[inline]
+    pub fn span(&self) -> Option<Span> {
+        self.location.as_ref().and_then(|location| location.span)
+    }
+
+    /// Whether the schema (or a document referenced by it) could not be loaded.
+    /// In this case the error does not say anything about the validity of the instance.
+    #[must_use]
+    #[inline]
+    pub fn is_schema_error(&self) -> bool {
+        match self.kind {
+            ValidationErrorKind::FileNotFound { .. }
+            | ValidationErrorKind::Utf8 { .. }
+            | ValidationErrorKind::JSONParse { .. }
+            | ValidationErrorKind::InvalidReference { .. }
+            | ValidationErrorKind::InvalidURL { .. }
+            | ValidationErrorKind::Reqwest { .. }
+            | ValidationErrorKind::Schema { .. }
+            | ValidationErrorKind::UnknownReferenceScheme { .. } => true,
+            #[cfg(feature = "yaml")]
+            ValidationErrorKind::YAMLParse { .. } => true,
+            _ => false,
         }
     }
+
+    /// Information about the `$ref` that failed to resolve, if the error happened
+    /// during reference resolution.
+    #[must_use]
+    #[inline]
+    pub fn reference_context(&self) -> Option<&ReferenceContext> {
+        match &self.kind {
+            ValidationErrorKind::FileNotFound { reference, .. }
+            | ValidationErrorKind::Utf8 { reference, .. }
+            | ValidationErrorKind::JSONParse { reference, .. }
+            | ValidationErrorKind::InvalidReference {
+                context: reference, ..
+            }
+            | ValidationErrorKind::InvalidURL { reference, .. }
+            | ValidationErrorKind::Reqwest { reference, .. }
+            | ValidationErrorKind::Schema { reference }
+            | ValidationErrorKind::UnknownReferenceScheme { reference, .. } => reference.as_deref(),
+            #[cfg(feature = "yaml")]
+            ValidationErrorKind::YAMLParse { reference, .. } => reference.as_deref(),
+            _ => None,
+        }
+    }
+
     /// Unexpected `ValidationError`
     ///
     /// This validation error is the only `ValidationError` that can be created by external crates.
     pub fn unexpected(instance: &'a Value, validator_representation: &str) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Borrowed(instance),
+            location: None,
             kind: ValidationErrorKind::Unexpected {
                 validator_representation: validator_representation.to_string(),
             },
@@ -478,7 +745,11 @@ impl<'a> ValidationError<'a> {
     pub(crate) fn utf8(error: Utf8Error) -> ValidationError<'a> {
         ValidationError {
             instance: Cow::Owned(Value::Null),
-            kind: ValidationErrorKind::Utf8 { error },
+            location: None,
+            kind: ValidationErrorKind::Utf8 {
+                error,
+                reference: None,
+            },
         }
     }
 }
@@ -496,6 +767,13 @@ impl From<serde_json::Error> for ValidationError<'_> {
         ValidationError::json_parse(err)
     }
 }
+#[cfg(feature = "yaml")]
+impl From<serde_yaml::Error> for ValidationError<'_> {
+    #[inline]
+    fn from(err: serde_yaml::Error) -> Self {
+        ValidationError::yaml_parse(err)
+    }
+}
 impl From<io::Error> for ValidationError<'_> {
     #[inline]
     fn from(err: io::Error) -> Self {
@@ -532,13 +810,23 @@ impl fmt::Display for ValidationError<'_> {
     #[allow(clippy::too_many_lines)] // The function is long but it does formatting only
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(reference) = self.reference_context() {
+            write!(
+                f,
+                "Failed to resolve '{}' (url: {}, base URI: {}, schema path: '{}'): ",
+                reference.reference, reference.url, reference.base_uri, reference.schema_path
+            )?;
+        }
         match &self.kind {
-            ValidationErrorKind::Schema => write!(f, "Schema error"),
-            ValidationErrorKind::JSONParse { error } => write!(f, "{}", error),
-            ValidationErrorKind::Reqwest { error } => write!(f, "{}", error),
-            ValidationErrorKind::FileNotFound { error } => write!(f, "{}", error),
-            ValidationErrorKind::InvalidURL { error } => write!(f, "{}", error),
-            ValidationErrorKind::UnknownReferenceScheme { scheme } => {
+            ValidationErrorKind::Schema { .. } => write!(f, "Schema error"),
+            ValidationErrorKind::JSONParse { error, .. }
+            | ValidationErrorKind::Serialization { error } => write!(f, "{}", error),
+            #[cfg(feature = "yaml")]
+            ValidationErrorKind::YAMLParse { error, .. } => write!(f, "{}", error),
+            ValidationErrorKind::Reqwest { error, .. } => write!(f, "{}", error),
+            ValidationErrorKind::FileNotFound { error, .. } => write!(f, "{}", error),
+            ValidationErrorKind::InvalidURL { error, .. } => write!(f, "{}", error),
+            ValidationErrorKind::UnknownReferenceScheme { scheme, .. } => {
                 write!(f, "Unknown scheme: {}", scheme)
             }
             ValidationErrorKind::Format { format } => {
@@ -592,7 +880,7 @@ impl fmt::Display for ValidationError<'_> {
                 write!(f, "'{}' is not compliant with media_type={}", self.instance, content_media_type)
             }
             ValidationErrorKind::FromUtf8 { error } => write!(f, "{}", error),
-            ValidationErrorKind::Utf8 { error } => write!(f, "{}", error),
+            ValidationErrorKind::Utf8 { error, .. } => write!(f, "{}", error),
             ValidationErrorKind::Enum { options } => {
                 write!(f, "'{}' is not one of '{}'", self.instance, options)
             }
@@ -609,7 +897,7 @@ impl fmt::Display for ValidationError<'_> {
             ValidationErrorKind::FalseSchema => {
                 write!(f, "False schema does not allow '{}'", self.instance)
             }
-            ValidationErrorKind::InvalidReference { reference } => {
+            ValidationErrorKind::InvalidReference { reference, .. } => {
                 write!(f, "Invalid reference: {}", reference)
             }
             ValidationErrorKind::Maximum { limit } => write!(
@@ -711,7 +999,9 @@ impl fmt::Display for ValidationError<'_> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::JSONSchema;
     use serde_json::json;
+    use test_case::test_case;
 
     #[test]
     fn single_type_error() {
@@ -729,4 +1019,21 @@ mod tests {
         );
         assert_eq!(err.to_string(), "'42' is not of types 'number', 'string'")
     }
+
+    #[test_case(&json!({"properties": {"a/b": {"properties": {"c": {"type": "string"}}}}}), &json!({"a/b": {"c": 1}}), "/a~1b/c")]
+    #[test_case(&json!({"items": {"type": "string"}}), &json!(["a", 1]), "/1")]
+    #[test_case(&json!({"items": {"type": "string"}}), &json!(["a", "b", "c", "d", "e", "f", "g", "h", 1]), "/8"; "parallel items")]
+    #[test_case(&json!({"items": [{}, {"type": "string"}]}), &json!([1, 2]), "/1")]
+    #[test_case(&json!({"items": [{}], "additionalItems": {"type": "string"}}), &json!([1, 2]), "/1")]
+    #[test_case(&json!({"additionalProperties": {"type": "string"}}), &json!({"a": 1}), "/a")]
+    #[test_case(&json!({"properties": {"a": {}}, "additionalProperties": {"type": "string"}}), &json!({"a": 1, "b": 2}), "/b")]
+    #[test_case(&json!({"patternProperties": {"^a": {"type": "string"}}}), &json!({"ab": 1}), "/ab")]
+    #[test_case(&json!({"additionalProperties": false}), &json!({"a": 1}), ""; "false schema reports the object")]
+    #[test_case(&json!({"allOf": [{"items": {"required": ["a"]}}]}), &json!([{}]), "/0")]
+    fn instance_path(schema: &Value, instance: &Value, expected: &str) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        let errors: Vec<_> = compiled.validate(instance).unwrap_err().collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].instance_path().to_string(), expected)
+    }
 }
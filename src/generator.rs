@@ -0,0 +1,1213 @@
+//! Generating instances from a schema, e.g. sample documents for contract tests.
+//!
+//! Instances are built by walking the keywords that apply to each location (following `$ref`,
+//! `allOf` and a randomly chosen branch of `anyOf`, `oneOf` and `if`). Every candidate is
+//! validated before it is returned, and generation is retried a bounded number of times.
+use crate::{
+    compilation::JSONSchema,
+    error::ValidationError,
+    keywords::pattern::convert_regex,
+    paths::{JSONPointer, PathChunk},
+    primitive_type::PrimitiveType,
+    schemas::Draft,
+    subschemas::{self, collect, Result, Source},
+};
+use regex_syntax::hir::{Class, Hir, HirKind};
+use serde_json::{Map, Value};
+use std::{borrow::Cow, convert::TryFrom, error, fmt};
+use url::Url;
+
+/// How many candidates are produced before giving up.
+const ATTEMPTS: usize = 100;
+/// Nesting level after which optional properties and array items are not generated.
+pub(crate) const MAX_DEPTH: usize = 8;
+pub(crate) const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+const TYPES: [PrimitiveType; 7] = [
+    PrimitiveType::Null,
+    PrimitiveType::Boolean,
+    PrimitiveType::Integer,
+    PrimitiveType::Number,
+    PrimitiveType::String,
+    PrimitiveType::Array,
+    PrimitiveType::Object,
+];
+/// Keywords that imply the type of the instance if `type` is absent.
+const TYPE_HINTS: [(PrimitiveType, &[&str]); 4] = [
+    (
+        PrimitiveType::Number,
+        &[
+            "minimum",
+            "maximum",
+            "exclusiveMinimum",
+            "exclusiveMaximum",
+            "multipleOf",
+        ],
+    ),
+    (
+        PrimitiveType::String,
+        &["minLength", "maxLength", "pattern", "format"],
+    ),
+    (
+        PrimitiveType::Array,
+        &[
+            "items",
+            "additionalItems",
+            "minItems",
+            "maxItems",
+            "uniqueItems",
+            "contains",
+        ],
+    ),
+    (
+        PrimitiveType::Object,
+        &[
+            "properties",
+            "patternProperties",
+            "additionalProperties",
+            "required",
+            "minProperties",
+            "maxProperties",
+        ],
+    ),
+];
+
+/// An error that prevents an instance from being generated.
+#[derive(Debug)]
+pub enum GenerationError {
+    /// The schema contains a reference that could not be resolved.
+    Schema(ValidationError<'static>),
+    /// No suitable instance was found within the limit of attempts. E.g. the schema is
+    /// unsatisfiable, relies on keywords the generator does not take into account
+    /// (like `not` or `propertyNames`), or the requested keyword can not be violated.
+    Exhausted,
+}
+
+impl error::Error for GenerationError {}
+
+impl fmt::Display for GenerationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenerationError::Schema(error) => write!(f, "{}", error),
+            GenerationError::Exhausted => f.write_str("No suitable instance was generated"),
+        }
+    }
+}
+
+impl From<ValidationError<'static>> for GenerationError {
+    #[inline]
+    fn from(error: ValidationError<'static>) -> Self {
+        GenerationError::Schema(error)
+    }
+}
+
+/// A pseudo-random number generator (`SplitMix64`).
+/// Its output depends only on the seed, therefore generated instances are reproducible.
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `[low, high]`.
+    fn range(&mut self, low: u64, high: u64) -> u64 {
+        if high <= low {
+            low
+        } else if high - low == u64::MAX {
+            self.next_u64()
+        } else {
+            low + self.next_u64() % (high - low + 1)
+        }
+    }
+
+    /// A number in `[low, high]`.
+    fn range_i64(&mut self, low: i64, high: i64) -> i64 {
+        if high <= low {
+            low
+        } else {
+            let offset = self.range(0, high.wrapping_sub(low) as u64);
+            low.wrapping_add(offset as i64)
+        }
+    }
+
+    /// An index below `length`, which should not be zero.
+    #[allow(clippy::cast_possible_truncation)]
+    fn below(&mut self, length: usize) -> usize {
+        self.range(0, length.saturating_sub(1) as u64) as usize
+    }
+
+    /// A number in `[0, 1)`.
+    fn float(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+
+    fn chance(&mut self) -> bool {
+        self.next_u64() & 1 == 0
+    }
+
+    fn choose<'v, T>(&mut self, items: &'v [T]) -> Option<&'v T> {
+        if items.is_empty() {
+            None
+        } else {
+            items.get(self.below(items.len()))
+        }
+    }
+
+    fn alphanumeric(&mut self, length: u64) -> String {
+        (0..length)
+            .map(|_| char::from(ALPHABET[self.below(ALPHABET.len())]))
+            .collect()
+    }
+}
+
+/// Produces instances for a compiled schema from a seeded pseudo-random number generator.
+///
+/// ```rust
+/// use jsonschema::JSONSchema;
+/// use serde_json::json;
+///
+/// let schema = json!({
+///     "type": "object",
+///     "properties": {"id": {"type": "integer", "minimum": 1}, "name": {"type": "string"}},
+///     "required": ["id", "name"]
+/// });
+/// let compiled = JSONSchema::compile(&schema).unwrap();
+/// let mut generator = compiled.generator(42);
+/// let valid = generator.generate().unwrap();
+/// assert!(compiled.is_valid(&valid));
+/// let invalid = generator.generate_invalid("required").unwrap();
+/// assert!(!compiled.is_valid(&invalid));
+/// ```
+#[derive(Debug)]
+pub struct Generator<'s, 'a> {
+    compiled: &'s JSONSchema<'a>,
+    rng: Rng,
+}
+
+impl<'s, 'a> Generator<'s, 'a> {
+    /// Generate an instance that is valid against the schema.
+    ///
+    /// The generator takes into account `type`, `enum`, `const`, numeric ranges, `multipleOf`,
+    /// string lengths, `pattern` (if it can be parsed), common `format` values, `items`,
+    /// `additionalItems`, array lengths, `uniqueItems`, `contains`, `properties`,
+    /// `patternProperties`, `additionalProperties`, `required`, object sizes and array
+    /// `dependencies`. Other keywords are checked by validating the result.
+    pub fn generate(&mut self) -> std::result::Result<Value, GenerationError> {
+        for _ in 0..ATTEMPTS {
+            let sources = subschemas::root(self.compiled)?;
+            if let Some(instance) = generate(self.compiled, &mut self.rng, sources, 0)? {
+                if self.compiled.is_valid(&instance) {
+                    return Ok(instance);
+                }
+            }
+        }
+        Err(GenerationError::Exhausted)
+    }
+
+    /// Generate an instance that is invalid because it violates `keyword` at some location.
+    ///
+    /// A valid instance is generated first, then the value at a location where `keyword`
+    /// applies is replaced with one that violates it. Supported keywords are `type`, `enum`,
+    /// `const`, numeric ranges, `multipleOf`, string lengths, `pattern`, `format`, array and
+    /// object sizes, `uniqueItems`, `contains`, `required`, `additionalProperties: false` and
+    /// `not`. Branches of `anyOf`, `oneOf` and `if` are not searched for the keyword.
+    pub fn generate_invalid(
+        &mut self,
+        keyword: &str,
+    ) -> std::result::Result<Value, GenerationError> {
+        for _ in 0..ATTEMPTS {
+            let sources = subschemas::root(self.compiled)?;
+            let mut instance = match generate(self.compiled, &mut self.rng, sources, 0)? {
+                Some(instance) if self.compiled.is_valid(&instance) => instance,
+                _ => continue,
+            };
+            let mut candidates = vec![];
+            locate(
+                self.compiled,
+                &mut self.rng,
+                &subschemas::root(self.compiled)?,
+                &instance,
+                keyword,
+                &mut vec![],
+                &mut candidates,
+            )?;
+            if candidates.is_empty() {
+                continue;
+            }
+            let (pointer, replacement) = candidates.swap_remove(self.rng.below(candidates.len()));
+            if let Some(target) = instance.pointer_mut(&pointer.to_string()) {
+                *target = replacement;
+            }
+            if !self.compiled.is_valid(&instance) {
+                return Ok(instance);
+            }
+        }
+        Err(GenerationError::Exhausted)
+    }
+}
+
+impl<'a> JSONSchema<'a> {
+    /// Create a generator of instances for this schema.
+    /// Generators with the same seed produce the same sequence of instances.
+    #[must_use]
+    pub fn generator(&self, seed: u64) -> Generator<'_, 'a> {
+        Generator {
+            compiled: self,
+            rng: Rng(seed),
+        }
+    }
+}
+
+/// The value of `keyword` if it is supported by the draft.
+pub(crate) fn get<'v>(draft: Draft, schema: &'v Value, keyword: &str) -> Option<&'v Value> {
+    draft
+        .get_validator(keyword)
+        .and_then(|_| schema.get(keyword))
+}
+
+fn lookup<'v>(schema: &'v Value, keyword: &str, index: Option<usize>) -> Option<&'v Value> {
+    let value = schema.get(keyword)?;
+    match index {
+        Some(index) => value.get(index),
+        None => Some(value),
+    }
+}
+
+/// The subschema under `keyword` (and `index` for arrays of subschemas).
+pub(crate) fn subschema<'s>(
+    schema: &Cow<'s, Value>,
+    keyword: &str,
+    index: Option<usize>,
+) -> Option<Cow<'s, Value>> {
+    match schema {
+        Cow::Borrowed(schema) => lookup(schema, keyword, index).map(Cow::Borrowed),
+        Cow::Owned(schema) => lookup(schema, keyword, index).cloned().map(Cow::Owned),
+    }
+}
+
+/// Add subschemas of randomly chosen branches of `anyOf`, `oneOf` and `if` to `sources`.
+fn choose_branches<'s>(
+    compiled: &'s JSONSchema,
+    rng: &mut Rng,
+    mut sources: Vec<Source<'s>>,
+) -> Result<Vec<Source<'s>>> {
+    let draft = compiled.context.config.draft();
+    let mut references = vec![];
+    let mut index = 0;
+    while index < sources.len() {
+        let mut chosen = vec![];
+        let source = &sources[index];
+        for keyword in &["anyOf", "oneOf"] {
+            if let Some(Value::Array(branches)) = get(draft, &source.schema, keyword) {
+                if !branches.is_empty() {
+                    let branch = rng.below(branches.len());
+                    chosen.extend(subschema(&source.schema, keyword, Some(branch)));
+                }
+            }
+        }
+        if get(draft, &source.schema, "if").is_some() {
+            if rng.chance() {
+                chosen.extend(subschema(&source.schema, "if", None));
+                chosen.extend(subschema(&source.schema, "then", None));
+            } else {
+                chosen.extend(subschema(&source.schema, "else", None));
+            }
+        }
+        let scope = source.scope.clone();
+        for schema in chosen {
+            collect(compiled, schema, &scope, &mut sources, &mut references)?;
+        }
+        index += 1;
+    }
+    Ok(sources)
+}
+
+fn type_names(value: &Value) -> Vec<PrimitiveType> {
+    match value {
+        Value::String(name) => PrimitiveType::try_from(name.as_str()).into_iter().collect(),
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .filter_map(|name| PrimitiveType::try_from(name).ok())
+            .collect(),
+        _ => vec![],
+    }
+}
+
+#[inline]
+fn is_listed(listed: &[PrimitiveType], primitive_type: PrimitiveType) -> bool {
+    listed.contains(&primitive_type)
+        || (primitive_type == PrimitiveType::Integer && listed.contains(&PrimitiveType::Number))
+}
+
+/// Types an instance could have. They are taken from `type` or, if it is absent, inferred
+/// from other keywords.
+pub(crate) fn candidate_types(sources: &[Source<'_>]) -> Vec<PrimitiveType> {
+    let mut types: Option<Vec<PrimitiveType>> = None;
+    for source in sources {
+        if let Some(value) = source.schema.get("type") {
+            let listed = type_names(value);
+            types = Some(
+                types
+                    .unwrap_or_else(|| TYPES.to_vec())
+                    .into_iter()
+                    .filter(|primitive_type| is_listed(&listed, *primitive_type))
+                    .collect(),
+            );
+        }
+    }
+    if let Some(types) = types {
+        return types;
+    }
+    let mut inferred = vec![];
+    for (primitive_type, keywords) in &TYPE_HINTS {
+        if sources.iter().any(|source| {
+            keywords
+                .iter()
+                .any(|keyword| source.schema.get(keyword).is_some())
+        }) {
+            inferred.push(*primitive_type)
+        }
+    }
+    if inferred.is_empty() {
+        TYPES.to_vec()
+    } else {
+        inferred
+    }
+}
+
+fn generate(
+    compiled: &JSONSchema,
+    rng: &mut Rng,
+    sources: Vec<Source<'_>>,
+    depth: usize,
+) -> Result<Option<Value>> {
+    let draft = compiled.context.config.draft();
+    let sources = choose_branches(compiled, rng, sources)?;
+    if sources
+        .iter()
+        .any(|source| *source.schema == Value::Bool(false))
+    {
+        return Ok(None);
+    }
+    for source in &sources {
+        if let Some(value) = get(draft, &source.schema, "const") {
+            return Ok(Some(value.clone()));
+        }
+    }
+    for source in &sources {
+        if let Some(Value::Array(options)) = get(draft, &source.schema, "enum") {
+            return Ok(rng.choose(options).cloned());
+        }
+    }
+    let types = candidate_types(&sources);
+    Ok(match rng.choose(&types) {
+        Some(PrimitiveType::Null) => Some(Value::Null),
+        Some(PrimitiveType::Boolean) => Some(Value::Bool(rng.chance())),
+        Some(PrimitiveType::Integer) => integer(rng, draft, &sources),
+        Some(PrimitiveType::Number) => {
+            if rng.chance() {
+                integer(rng, draft, &sources)
+            } else {
+                number(rng, draft, &sources)
+            }
+        }
+        Some(PrimitiveType::String) => string(rng, draft, &sources).map(Value::String),
+        Some(PrimitiveType::Array) => array(compiled, rng, &sources, depth)?,
+        Some(PrimitiveType::Object) => object(compiled, rng, &sources, depth)?,
+        None => None,
+    })
+}
+
+/// The tightest lower and upper limits from numeric keywords. `true` marks exclusive limits.
+#[allow(clippy::type_complexity)]
+pub(crate) fn limits(
+    draft: Draft,
+    sources: &[Source<'_>],
+) -> (Option<(f64, bool)>, Option<(f64, bool)>) {
+    fn tighten(limit: &mut Option<(f64, bool)>, value: f64, exclusive: bool, lower: bool) {
+        let is_tighter = match limit {
+            Some((current, current_exclusive)) => {
+                (lower && value > *current)
+                    || (!lower && value < *current)
+                    || (value == *current && exclusive && !*current_exclusive)
+            }
+            None => true,
+        };
+        if is_tighter {
+            *limit = Some((value, exclusive))
+        }
+    }
+    let (mut lower, mut upper) = (None, None);
+    for source in sources {
+        let schema = &source.schema;
+        // In Draft 4 `exclusiveMinimum` & `exclusiveMaximum` are booleans modifying the limits
+        let is_exclusive =
+            |keyword| draft == Draft::Draft4 && schema.get(keyword) == Some(&Value::Bool(true));
+        if let Some(value) = get(draft, schema, "minimum").and_then(Value::as_f64) {
+            tighten(&mut lower, value, is_exclusive("exclusiveMinimum"), true)
+        }
+        if let Some(value) = get(draft, schema, "exclusiveMinimum").and_then(Value::as_f64) {
+            tighten(&mut lower, value, true, true)
+        }
+        if let Some(value) = get(draft, schema, "maximum").and_then(Value::as_f64) {
+            tighten(&mut upper, value, is_exclusive("exclusiveMaximum"), false)
+        }
+        if let Some(value) = get(draft, schema, "exclusiveMaximum").and_then(Value::as_f64) {
+            tighten(&mut upper, value, true, false)
+        }
+    }
+    (lower, upper)
+}
+
+/// Fill in absent limits, so the range has a reasonable size.
+pub(crate) fn with_defaults(low: Option<f64>, high: Option<f64>) -> (f64, f64) {
+    match (low, high) {
+        (Some(low), Some(high)) => (low, high),
+        (Some(low), None) => (low, low + 100.0),
+        (None, Some(high)) => (high - 100.0, high),
+        (None, None) => (-100.0, 100.0),
+    }
+}
+
+pub(crate) fn multiple_of(draft: Draft, sources: &[Source<'_>]) -> Option<f64> {
+    sources
+        .iter()
+        .filter_map(|source| get(draft, &source.schema, "multipleOf").and_then(Value::as_f64))
+        .find(|multiple| *multiple > 0.0)
+}
+
+/// Convert a number to JSON, preferring integers for integral values.
+#[allow(clippy::cast_possible_truncation)]
+fn to_json(value: f64) -> Value {
+    // Integers are exactly representable up to 2^53
+    if value.fract() == 0.0 && value.abs() < 9_007_199_254_740_992.0 {
+        Value::from(value as i64)
+    } else {
+        Value::from(value)
+    }
+}
+
+/// Integers allowed by numeric keywords: `factor * multiple` (or just `factor` without
+/// `multipleOf`) for factors in `[low, high]`.
+pub(crate) struct Integers {
+    pub(crate) low: i64,
+    pub(crate) high: i64,
+    pub(crate) multiple: Option<f64>,
+}
+
+impl Integers {
+    pub(crate) fn value(&self, factor: i64) -> Value {
+        match self.multiple {
+            Some(multiple) => to_json(factor as f64 * multiple),
+            None => Value::from(factor),
+        }
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+pub(crate) fn integers(draft: Draft, sources: &[Source<'_>]) -> Option<Integers> {
+    const LIMIT: f64 = 9_007_199_254_740_992.0;
+    let (lower, upper) = limits(draft, sources);
+    let low = lower.map(|(value, exclusive)| {
+        if exclusive && value.fract() == 0.0 {
+            value + 1.0
+        } else {
+            value.ceil()
+        }
+    });
+    let high = upper.map(|(value, exclusive)| {
+        if exclusive && value.fract() == 0.0 {
+            value - 1.0
+        } else {
+            value.floor()
+        }
+    });
+    let (low, high) = with_defaults(low, high);
+    let multiple = multiple_of(draft, sources);
+    let (low, high) = match multiple {
+        Some(multiple) => ((low / multiple).ceil(), (high / multiple).floor()),
+        None => (low.max(-LIMIT), high.min(LIMIT)),
+    };
+    if low > high || low.abs() > LIMIT || high.abs() > LIMIT {
+        return None;
+    }
+    Some(Integers {
+        low: low as i64,
+        high: high as i64,
+        multiple,
+    })
+}
+
+fn integer(rng: &mut Rng, draft: Draft, sources: &[Source<'_>]) -> Option<Value> {
+    let integers = integers(draft, sources)?;
+    Some(integers.value(rng.range_i64(integers.low, integers.high)))
+}
+
+fn number(rng: &mut Rng, draft: Draft, sources: &[Source<'_>]) -> Option<Value> {
+    if multiple_of(draft, sources).is_some() {
+        return integer(rng, draft, sources);
+    }
+    let (lower, upper) = limits(draft, sources);
+    let (low, high) = with_defaults(lower.map(|(value, _)| value), upper.map(|(value, _)| value));
+    if low > high {
+        return None;
+    }
+    let mut value = low + rng.float() * (high - low);
+    let is_excluded = |value: f64| {
+        matches!(lower, Some((limit, true)) if value <= limit)
+            || matches!(upper, Some((limit, true)) if value >= limit)
+    };
+    if is_excluded(value) {
+        value = low + (high - low) / 2.0;
+    }
+    if is_excluded(value) || !value.is_finite() {
+        return None;
+    }
+    Some(Value::from(value))
+}
+
+/// A value of a known `format`. `number` should be in `[1, 28]`.
+pub(crate) fn format_sample(format: &str, number: u64) -> Option<String> {
+    Some(match format {
+        "date" => format!("2020-01-{:02}", number),
+        "date-time" => format!("2020-01-{:02}T12:30:00Z", number),
+        "time" => format!("12:30:{:02}Z", number),
+        "email" | "idn-email" => format!("user{}@example.com", number),
+        "hostname" | "idn-hostname" => format!("host{}.example.com", number),
+        "ipv4" => format!("192.168.0.{}", number),
+        "ipv6" => format!("2001:db8::{}", number),
+        "uri" | "iri" => format!("https://example.com/{}", number),
+        "uri-reference" | "iri-reference" | "json-pointer" => format!("/items/{}", number),
+        "uri-template" => "/items/{id}".to_string(),
+        "relative-json-pointer" => format!("{}/items", number),
+        "regex" => "^[a-z]+$".to_string(),
+        _ => return None,
+    })
+}
+
+/// Produce a string matching the regular expression. Unbounded repetitions are repeated at
+/// most `extra` times more than required.
+#[allow(clippy::cast_possible_truncation)]
+fn from_hir(rng: &mut Rng, hir: &Hir, extra: u32, output: &mut String) {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => {}
+        HirKind::Literal(literal) => output.push_str(&String::from_utf8_lossy(&literal.0)),
+        HirKind::Class(Class::Unicode(class)) => {
+            let printable: Vec<_> = class
+                .ranges()
+                .iter()
+                .filter_map(|range| {
+                    let start = u32::from(range.start()).max(0x21);
+                    let end = u32::from(range.end()).min(0x7E);
+                    if start <= end {
+                        Some((start, end))
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            let range = match rng.choose(&printable) {
+                Some(range) => Some(*range),
+                None => rng
+                    .choose(class.ranges())
+                    .map(|range| (u32::from(range.start()), u32::from(range.end()))),
+            };
+            if let Some((start, end)) = range {
+                let code = rng.range(u64::from(start), u64::from(end));
+                output.extend(char::from_u32(code as u32).or_else(|| char::from_u32(start)))
+            }
+        }
+        HirKind::Class(Class::Bytes(class)) => {
+            if let Some(range) = rng.choose(class.ranges()) {
+                let byte = rng.range(u64::from(range.start()), u64::from(range.end()));
+                output.push(char::from(byte as u8))
+            }
+        }
+        HirKind::Repetition(repetition) => {
+            let max = repetition
+                .max
+                .unwrap_or_else(|| repetition.min.saturating_add(extra));
+            let count = rng.range(u64::from(repetition.min), u64::from(max));
+            for _ in 0..count {
+                from_hir(rng, &repetition.sub, extra, output)
+            }
+        }
+        HirKind::Capture(capture) => from_hir(rng, &capture.sub, extra, output),
+        HirKind::Concat(items) => {
+            for item in items {
+                from_hir(rng, item, extra, output)
+            }
+        }
+        HirKind::Alternation(items) => {
+            if let Some(item) = rng.choose(items) {
+                from_hir(rng, item, extra, output)
+            }
+        }
+    }
+}
+
+pub(crate) fn parse_pattern(pattern: &str) -> Option<Hir> {
+    let converted = convert_regex(pattern).ok()?;
+    regex_syntax::Parser::new().parse(converted.as_str()).ok()
+}
+
+/// The tightest limits from a pair of keywords like `minLength` & `maxLength`.
+pub(crate) fn size_limits(
+    draft: Draft,
+    sources: &[Source<'_>],
+    min_keyword: &str,
+    max_keyword: &str,
+) -> (u64, Option<u64>) {
+    let min = sources
+        .iter()
+        .filter_map(|source| get(draft, &source.schema, min_keyword).and_then(Value::as_u64))
+        .max()
+        .unwrap_or(0);
+    let max = sources
+        .iter()
+        .filter_map(|source| get(draft, &source.schema, max_keyword).and_then(Value::as_u64))
+        .min();
+    (min, max)
+}
+
+fn string(rng: &mut Rng, draft: Draft, sources: &[Source<'_>]) -> Option<String> {
+    let (min_length, max_length) = size_limits(draft, sources, "minLength", "maxLength");
+    for source in sources {
+        if let Some(Value::String(format)) = get(draft, &source.schema, "format") {
+            if let Some(sample) = format_sample(format, rng.range(1, 28)) {
+                return Some(sample);
+            }
+        }
+    }
+    for source in sources {
+        if let Some(hir) = get(draft, &source.schema, "pattern")
+            .and_then(Value::as_str)
+            .and_then(parse_pattern)
+        {
+            let extra = u32::try_from(min_length).unwrap_or(u32::MAX).max(3);
+            let mut output = String::new();
+            from_hir(rng, &hir, extra, &mut output);
+            return Some(output);
+        }
+    }
+    let high = max_length.map_or(min_length + 8, |max| max.min(min_length + 8));
+    if min_length > high {
+        return None;
+    }
+    let length = rng.range(min_length, high);
+    Some(rng.alphanumeric(length))
+}
+
+/// Subschemas of `contains` together with their base URLs.
+pub(crate) fn contains<'s>(draft: Draft, sources: &'s [Source<'_>]) -> Vec<(&'s Value, &'s Url)> {
+    sources
+        .iter()
+        .filter_map(|source| {
+            get(draft, &source.schema, "contains").map(|schema| (schema, &source.scope))
+        })
+        .collect()
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn array(
+    compiled: &JSONSchema,
+    rng: &mut Rng,
+    sources: &[Source<'_>],
+    depth: usize,
+) -> Result<Option<Value>> {
+    let draft = compiled.context.config.draft();
+    let (min_items, max_items) = size_limits(draft, sources, "minItems", "maxItems");
+    let is_unique = sources
+        .iter()
+        .any(|source| get(draft, &source.schema, "uniqueItems") == Some(&Value::Bool(true)));
+    let contains = contains(draft, sources);
+    let low = if contains.is_empty() {
+        min_items
+    } else {
+        min_items.max(1)
+    };
+    let high = if depth >= MAX_DEPTH { low } else { low + 3 }.min(max_items.unwrap_or(u64::MAX));
+    if low > high {
+        return Ok(None);
+    }
+    let length = rng.range(low, high) as usize;
+    let contains_at = if contains.is_empty() {
+        None
+    } else {
+        Some(rng.below(length))
+    };
+    let mut items = Vec::with_capacity(length);
+    for index in 0..length {
+        let mut item = None;
+        for _ in 0..if is_unique { 10 } else { 1 } {
+            let mut children = subschemas::item(compiled, sources, index)?;
+            if contains_at == Some(index) {
+                for (schema, scope) in &contains {
+                    collect(
+                        compiled,
+                        Cow::Borrowed(*schema),
+                        scope,
+                        &mut children,
+                        &mut vec![],
+                    )?;
+                }
+            }
+            match generate(compiled, rng, children, depth + 1)? {
+                Some(candidate) if !(is_unique && items.contains(&candidate)) => {
+                    item = Some(candidate);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        match item {
+            Some(item) => items.push(item),
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(Value::Array(items)))
+}
+
+/// Names of required properties and other properties listed in `properties`.
+pub(crate) fn property_names<'s>(
+    draft: Draft,
+    sources: &'s [Source<'_>],
+) -> (Vec<&'s str>, Vec<&'s str>) {
+    let mut required: Vec<&str> = vec![];
+    let mut optional: Vec<&str> = vec![];
+    for source in sources {
+        if let Some(Value::Array(names)) = get(draft, &source.schema, "required") {
+            for name in names.iter().filter_map(Value::as_str) {
+                if !required.contains(&name) {
+                    required.push(name)
+                }
+            }
+        }
+    }
+    for source in sources {
+        if let Some(Value::Object(properties)) = get(draft, &source.schema, "properties") {
+            for name in properties.keys() {
+                if !required.contains(&name.as_str()) && !optional.contains(&name.as_str()) {
+                    optional.push(name)
+                }
+            }
+        }
+    }
+    (required, optional)
+}
+
+/// A name for a property that is not listed in `properties`.
+fn additional_name(rng: &mut Rng, draft: Draft, sources: &[Source<'_>], index: usize) -> String {
+    for source in sources {
+        if let Some(Value::Object(patterns)) = get(draft, &source.schema, "patternProperties") {
+            let patterns: Vec<_> = patterns.keys().collect();
+            if let Some(hir) = rng
+                .choose(&patterns)
+                .and_then(|pattern| parse_pattern(pattern))
+            {
+                let mut name = String::new();
+                from_hir(rng, &hir, 3, &mut name);
+                return name;
+            }
+        }
+    }
+    format!("property{}", index)
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn object(
+    compiled: &JSONSchema,
+    rng: &mut Rng,
+    sources: &[Source<'_>],
+    depth: usize,
+) -> Result<Option<Value>> {
+    let draft = compiled.context.config.draft();
+    let (required, optional) = property_names(draft, sources);
+    let (min_properties, max_properties) =
+        size_limits(draft, sources, "minProperties", "maxProperties");
+    let mut names: Vec<String> = required.iter().map(|name| (*name).to_string()).collect();
+    let mut spare = vec![];
+    for name in optional {
+        if depth < MAX_DEPTH && rng.chance() {
+            names.push(name.to_string())
+        } else {
+            spare.push(name.to_string())
+        }
+    }
+    while (names.len() as u64) < min_properties {
+        let name = match spare.pop() {
+            Some(name) => name,
+            None => {
+                let name = additional_name(rng, draft, sources, names.len());
+                if names.contains(&name) {
+                    format!("property{}", names.len())
+                } else {
+                    name
+                }
+            }
+        };
+        names.push(name)
+    }
+    // Properties required by the presence of other properties
+    let mut index = 0;
+    while index < names.len() {
+        for source in sources {
+            if let Some(Value::Array(dependencies)) = get(draft, &source.schema, "dependencies")
+                .and_then(|dependencies| dependencies.get(&names[index]))
+            {
+                for dependency in dependencies.iter().filter_map(Value::as_str) {
+                    if !names.iter().any(|name| name == dependency) {
+                        names.push(dependency.to_string())
+                    }
+                }
+            }
+        }
+        index += 1;
+    }
+    if let Some(max_properties) = max_properties {
+        names.truncate(max_properties as usize)
+    }
+    let mut object = Map::new();
+    for name in names {
+        let children = subschemas::property(compiled, sources, &name)?;
+        match generate(compiled, rng, children, depth + 1)? {
+            Some(value) => {
+                object.insert(name, value);
+            }
+            None if required.contains(&name.as_str()) => return Ok(None),
+            None => {}
+        }
+    }
+    Ok(Some(Value::Object(object)))
+}
+
+/// Find locations in `instance` where `keyword` applies and values that violate it there.
+fn locate(
+    compiled: &JSONSchema,
+    rng: &mut Rng,
+    sources: &[Source<'_>],
+    instance: &Value,
+    keyword: &str,
+    path: &mut Vec<PathChunk>,
+    found: &mut Vec<(JSONPointer, Value)>,
+) -> Result<()> {
+    let draft = compiled.context.config.draft();
+    for source in sources {
+        if get(draft, &source.schema, keyword).is_some() {
+            if let Some(replacement) = violate(compiled, rng, sources, source, instance, keyword)? {
+                found.push((JSONPointer::from(path.clone()), replacement))
+            }
+        }
+    }
+    match instance {
+        Value::Object(object) => {
+            for (name, value) in object {
+                let children = subschemas::property(compiled, sources, name)?;
+                path.push(PathChunk::Property(name.clone()));
+                locate(compiled, rng, &children, value, keyword, path, found)?;
+                path.pop();
+            }
+        }
+        Value::Array(items) => {
+            for (index, value) in items.iter().enumerate() {
+                let children = subschemas::item(compiled, sources, index)?;
+                path.push(PathChunk::Index(index));
+                locate(compiled, rng, &children, value, keyword, path, found)?;
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn sample(primitive_type: PrimitiveType) -> Value {
+    match primitive_type {
+        PrimitiveType::Array => Value::Array(vec![]),
+        PrimitiveType::Boolean => Value::Bool(true),
+        PrimitiveType::Integer => Value::from(1),
+        PrimitiveType::Null => Value::Null,
+        PrimitiveType::Number => Value::from(0.5),
+        PrimitiveType::Object => Value::Object(Map::new()),
+        PrimitiveType::String => Value::String(String::new()),
+    }
+}
+
+/// A value that violates `keyword` of `source`, based on the current `instance` value.
+#[allow(clippy::cast_possible_truncation)]
+fn violate(
+    compiled: &JSONSchema,
+    rng: &mut Rng,
+    sources: &[Source<'_>],
+    source: &Source<'_>,
+    instance: &Value,
+    keyword: &str,
+) -> Result<Option<Value>> {
+    let draft = compiled.context.config.draft();
+    let schema = &source.schema;
+    let argument = &schema[keyword];
+    let is_exclusive =
+        |keyword| draft == Draft::Draft4 && schema.get(keyword) == Some(&Value::Bool(true));
+    let items = || instance.as_array().cloned().unwrap_or_default();
+    let object = || instance.as_object().cloned().unwrap_or_default();
+    Ok(match keyword {
+        "type" => {
+            let listed = type_names(argument);
+            let others: Vec<_> = TYPES
+                .iter()
+                .filter(|primitive_type| !is_listed(&listed, **primitive_type))
+                .collect();
+            rng.choose(&others)
+                .map(|primitive_type| sample(**primitive_type))
+        }
+        "enum" | "const" => {
+            let excluded = match argument {
+                Value::Array(options) if keyword == "enum" => options.clone(),
+                _ => vec![argument.clone()],
+            };
+            TYPES
+                .iter()
+                .map(|primitive_type| sample(*primitive_type))
+                .chain(Some(Value::String(rng.alphanumeric(8))))
+                .find(|value| !excluded.contains(value))
+        }
+        "minimum" => argument.as_f64().map(|limit| {
+            if is_exclusive("exclusiveMinimum") {
+                to_json(limit)
+            } else {
+                to_json(limit - 1.0)
+            }
+        }),
+        "maximum" => argument.as_f64().map(|limit| {
+            if is_exclusive("exclusiveMaximum") {
+                to_json(limit)
+            } else {
+                to_json(limit + 1.0)
+            }
+        }),
+        "exclusiveMinimum" | "exclusiveMaximum" => argument.as_f64().map(to_json),
+        // `1.5 * multiple` is never a multiple of `multiple`
+        "multipleOf" => argument.as_f64().map(|multiple| to_json(multiple * 1.5)),
+        "minLength" => match argument.as_u64() {
+            Some(length) if length > 0 => Some(Value::String("a".repeat(length as usize - 1))),
+            _ => None,
+        },
+        "maxLength" => argument
+            .as_u64()
+            .map(|length| Value::String("a".repeat(length as usize + 1))),
+        "pattern" => argument
+            .as_str()
+            .and_then(|pattern| convert_regex(pattern).ok())
+            .and_then(|pattern| {
+                let mut candidates = vec![String::new(), " ".to_string()];
+                candidates.extend((1..=8).map(|length| rng.alphanumeric(length)));
+                candidates
+                    .into_iter()
+                    .find(|candidate| !pattern.is_match(candidate))
+            })
+            .map(Value::String),
+        // Not a valid value for any of the supported formats
+        "format" => Some(Value::String("[".to_string())),
+        "minItems" => match argument.as_u64() {
+            Some(length) if length > 0 => {
+                let mut items = items();
+                items.truncate(length as usize - 1);
+                Some(Value::Array(items))
+            }
+            _ => None,
+        },
+        "maxItems" => match argument.as_u64() {
+            Some(length) => {
+                let mut items = items();
+                while items.len() <= length as usize {
+                    let children = subschemas::item(compiled, sources, items.len())?;
+                    items.push(generate(compiled, rng, children, MAX_DEPTH)?.unwrap_or(Value::Null))
+                }
+                Some(Value::Array(items))
+            }
+            None => None,
+        },
+        "uniqueItems" if *argument == Value::Bool(true) => {
+            let mut items = items();
+            if items.is_empty() {
+                let children = subschemas::item(compiled, sources, 0)?;
+                items.push(generate(compiled, rng, children, MAX_DEPTH)?.unwrap_or(Value::Null))
+            }
+            items.push(items[0].clone());
+            Some(Value::Array(items))
+        }
+        "contains" => Some(Value::Array(vec![])),
+        "minProperties" => match argument.as_u64() {
+            Some(size) if size > 0 => {
+                let object: Map<String, Value> =
+                    object().into_iter().take(size as usize - 1).collect();
+                Some(Value::Object(object))
+            }
+            _ => None,
+        },
+        "maxProperties" => match argument.as_u64() {
+            Some(size) => {
+                let mut object = object();
+                let mut index = 0;
+                while object.len() <= size as usize {
+                    let name = format!("property{}", index);
+                    if !object.contains_key(&name) {
+                        let children = subschemas::property(compiled, sources, &name)?;
+                        let value = generate(compiled, rng, children, MAX_DEPTH)?;
+                        object.insert(name, value.unwrap_or(Value::Null));
+                    }
+                    index += 1;
+                }
+                Some(Value::Object(object))
+            }
+            None => None,
+        },
+        "required" => {
+            let mut object = object();
+            let present: Vec<_> = argument
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .filter(|name| object.contains_key(*name))
+                .collect();
+            rng.choose(&present).map(|name| {
+                object.remove(*name);
+                Value::Object(object)
+            })
+        }
+        "additionalProperties" if *argument == Value::Bool(false) => {
+            let mut object = object();
+            let mut index = 0;
+            loop {
+                let name = format!("additional{}", index);
+                let children = subschemas::property(compiled, sources, &name)?;
+                if !object.contains_key(&name)
+                    && children
+                        .iter()
+                        .any(|child| *child.schema == Value::Bool(false))
+                {
+                    object.insert(name, Value::Null);
+                    break;
+                }
+                index += 1;
+                if index > 10 {
+                    return Ok(None);
+                }
+            }
+            Some(Value::Object(object))
+        }
+        "not" => match subschema(schema, "not", None) {
+            Some(negated) => {
+                let mut children = vec![];
+                collect(compiled, negated, &source.scope, &mut children, &mut vec![])?;
+                generate(compiled, rng, children, MAX_DEPTH)?
+            }
+            None => None,
+        },
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GenerationError;
+    use crate::JSONSchema;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(&json!({"type": "integer", "minimum": 10, "exclusiveMaximum": 13}); "integer range")]
+    #[test_case(&json!({"type": "number", "minimum": 0.5, "maximum": 0.75}); "number range")]
+    #[test_case(&json!({"type": "integer", "multipleOf": 7, "minimum": 1}); "multiple of")]
+    #[test_case(&json!({"type": "string", "minLength": 3, "maxLength": 5}); "string length")]
+    #[test_case(&json!({"type": "string", "pattern": "^[A-Z]{2}-\\d{3,5}$"}); "pattern")]
+    #[test_case(&json!({"type": "string", "format": "date"}); "format")]
+    #[test_case(&json!({"enum": ["a", "b", 3]}); "enum values")]
+    #[test_case(&json!({"const": {"a": [1]}}); "const value")]
+    #[test_case(&json!({"type": "array", "items": {"type": "integer", "maximum": 3}, "minItems": 3, "uniqueItems": true}); "unique items")]
+    #[test_case(&json!({"type": "array", "items": [{"type": "string"}, {"type": "null"}], "additionalItems": false}); "tuple")]
+    #[test_case(&json!({"type": "array", "contains": {"const": 42}}); "contains")]
+    #[test_case(
+        &json!({
+            "type": "object",
+            "properties": {"id": {"type": "integer"}, "tags": {"type": "array", "items": {"type": "string"}}},
+            "required": ["id"],
+            "patternProperties": {"^x-[a-z]+$": {"type": "boolean"}},
+            "additionalProperties": false,
+            "minProperties": 4
+        });
+        "object"
+    )]
+    #[test_case(
+        &json!({
+            "definitions": {"node": {"type": "object", "properties": {"children": {"type": "array", "items": {"$ref": "#/definitions/node"}}}}},
+            "$ref": "#/definitions/node"
+        });
+        "recursive references"
+    )]
+    #[test_case(&json!({"anyOf": [{"type": "string"}, {"type": "integer"}], "allOf": [{"minimum": 5}]}); "combinators")]
+    #[test_case(&json!({"if": {"minimum": 0}, "then": {"multipleOf": 2}, "else": {"type": "string"}}); "conditionals")]
+    #[test_case(&json!({"dependencies": {"a": ["b"]}, "properties": {"a": {}, "b": {}}, "required": ["a"]}); "dependencies")]
+    fn generate_valid(schema: &Value) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        let mut generator = compiled.generator(7);
+        for _ in 0..20 {
+            let instance = generator.generate().unwrap();
+            assert!(compiled.is_valid(&instance), "{} should be valid", instance);
+        }
+    }
+
+    #[test_case(&json!({"type": "integer"}), "type")]
+    #[test_case(&json!({"enum": [1, 2]}), "enum")]
+    #[test_case(&json!({"minimum": 3, "type": "integer"}), "minimum")]
+    #[test_case(&json!({"$schema": "http://json-schema.org/draft-04/schema#", "maximum": 3, "exclusiveMaximum": true}), "maximum")]
+    #[test_case(&json!({"multipleOf": 2, "type": "integer"}), "multipleOf")]
+    #[test_case(&json!({"minLength": 2, "type": "string"}), "minLength")]
+    #[test_case(&json!({"pattern": "^a+$", "type": "string"}), "pattern")]
+    #[test_case(&json!({"format": "ipv4", "type": "string"}), "format")]
+    #[test_case(&json!({"maxItems": 2, "items": {"type": "integer"}}), "maxItems")]
+    #[test_case(&json!({"uniqueItems": true, "type": "array"}), "uniqueItems")]
+    #[test_case(&json!({"properties": {"a": {"type": "integer"}}, "additionalProperties": false}), "additionalProperties")]
+    #[test_case(&json!({"properties": {"nested": {"required": ["a"], "properties": {"a": {"type": "string"}}}}, "required": ["nested"]}), "required")]
+    #[test_case(&json!({"type": "integer", "not": {"minimum": 10}}), "not")]
+    fn generate_invalid(schema: &Value, keyword: &str) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        let mut generator = compiled.generator(7);
+        for _ in 0..10 {
+            let instance = generator.generate_invalid(keyword).unwrap();
+            assert!(
+                !compiled.is_valid(&instance),
+                "{} should be invalid",
+                instance
+            );
+        }
+    }
+
+    #[test]
+    fn reproducible() {
+        let schema = json!({"type": "array", "items": {"type": ["string", "number", "object"]}});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let (mut first, mut second) = (compiled.generator(1), compiled.generator(1));
+        for _ in 0..10 {
+            assert_eq!(first.generate().unwrap(), second.generate().unwrap());
+        }
+    }
+
+    #[test_case(&json!(false), None; "unsatisfiable")]
+    #[test_case(&json!({"type": "string"}), Some("minimum"); "keyword is not used")]
+    fn exhausted(schema: &Value, keyword: Option<&str>) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        let mut generator = compiled.generator(0);
+        let result = match keyword {
+            Some(keyword) => generator.generate_invalid(keyword),
+            None => generator.generate(),
+        };
+        assert!(matches!(result, Err(GenerationError::Exhausted)));
+    }
+}
@@ -0,0 +1,288 @@
+//! Validation of instances that are not represented as `serde_json::Value`.
+use crate::{
+    compilation::JSONSchema,
+    primitive_type::PrimitiveType,
+    single_pass::{Node, Number, ObjectState, Plan},
+};
+use serde_json::{Map, Value};
+
+/// Read-only access to a JSON-like document.
+///
+/// Implement it for other in-memory representations (e.g. YAML values or arena-based JSON) to
+/// check them with `JSONSchema::is_valid_instance`.
+///
+/// This is not a generic validation: keyword validators work only with `serde_json::Value`.
+/// Instances are checked directly only against the subset of keywords supported by
+/// `JSONSchema::is_valid_from` (`type`, `enum` with string options, `properties`, `required`,
+/// `additionalProperties`, single-schema `items` and size / length / range keywords).
+///
+/// Numbers are checked with `as_u64`, `as_i64` and `as_f64` in this order.
+pub trait JsonLike {
+    /// Whether the value is `null`.
+    fn is_null(&self) -> bool;
+    /// The value as a boolean, if it is one.
+    fn as_bool(&self) -> Option<bool>;
+    /// The value as an unsigned integer, if it is representable as one.
+    fn as_u64(&self) -> Option<u64>;
+    /// The value as a signed integer, if it is representable as one.
+    fn as_i64(&self) -> Option<i64>;
+    /// The value as a floating point number, if it is a number.
+    fn as_f64(&self) -> Option<f64>;
+    /// The value as a string, if it is one.
+    fn as_str(&self) -> Option<&str>;
+    /// Array items, if the value is an array.
+    fn as_array(&self) -> Option<Box<dyn Iterator<Item = &Self> + '_>>;
+    /// Object properties, if the value is an object.
+    fn as_object(&self) -> Option<Box<dyn Iterator<Item = (&str, &Self)> + '_>>;
+
+    /// Convert the value into `serde_json::Value`, e.g. to validate it with `JSONSchema::validate`
+    /// when `JSONSchema::is_valid_instance` can't decide.
+    fn to_value(&self) -> Value {
+        if let Some(value) = self.as_bool() {
+            Value::Bool(value)
+        } else if let Some(value) = self.as_u64() {
+            Value::from(value)
+        } else if let Some(value) = self.as_i64() {
+            Value::from(value)
+        } else if let Some(value) = self.as_f64() {
+            Value::from(value)
+        } else if let Some(value) = self.as_str() {
+            Value::String(value.to_string())
+        } else if let Some(items) = self.as_array() {
+            Value::Array(items.map(JsonLike::to_value).collect())
+        } else if let Some(properties) = self.as_object() {
+            Value::Object(
+                properties
+                    .map(|(key, value)| (key.to_string(), value.to_value()))
+                    .collect::<Map<String, Value>>(),
+            )
+        } else {
+            Value::Null
+        }
+    }
+}
+
+impl JsonLike for Value {
+    #[inline]
+    fn is_null(&self) -> bool {
+        self.is_null()
+    }
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        self.as_bool()
+    }
+    #[inline]
+    fn as_u64(&self) -> Option<u64> {
+        self.as_u64()
+    }
+    #[inline]
+    fn as_i64(&self) -> Option<i64> {
+        self.as_i64()
+    }
+    #[inline]
+    fn as_f64(&self) -> Option<f64> {
+        self.as_f64()
+    }
+    #[inline]
+    fn as_str(&self) -> Option<&str> {
+        self.as_str()
+    }
+    #[inline]
+    fn as_array(&self) -> Option<Box<dyn Iterator<Item = &Self> + '_>> {
+        match self {
+            Value::Array(items) => Some(Box::new(items.iter())),
+            _ => None,
+        }
+    }
+    #[inline]
+    fn as_object(&self) -> Option<Box<dyn Iterator<Item = (&str, &Self)> + '_>> {
+        match self {
+            Value::Object(object) => Some(Box::new(
+                object.iter().map(|(key, value)| (key.as_str(), value)),
+            )),
+            _ => None,
+        }
+    }
+    #[inline]
+    fn to_value(&self) -> Value {
+        self.clone()
+    }
+}
+
+/// Returns `None` if `instance` reaches a sub-schema outside of the supported subset.
+fn check<I: JsonLike + ?Sized>(plan: &Plan, instance: &I) -> Option<bool> {
+    match plan {
+        Plan::Always(is_valid) => Some(*is_valid),
+        Plan::Stream(node) => check_node(node, instance),
+        Plan::Materialize(_) => None,
+    }
+}
+
+fn check_node<I: JsonLike + ?Sized>(node: &Node, instance: &I) -> Option<bool> {
+    let is_valid = if instance.is_null() {
+        node.is_valid_scalar(PrimitiveType::Null)
+    } else if instance.as_bool().is_some() {
+        node.is_valid_scalar(PrimitiveType::Boolean)
+    } else if let Some(value) = instance.as_u64() {
+        node.is_valid_number(Number::U64(value))
+    } else if let Some(value) = instance.as_i64() {
+        node.is_valid_number(Number::I64(value))
+    } else if let Some(value) = instance.as_f64() {
+        node.is_valid_number(Number::F64(value))
+    } else if let Some(value) = instance.as_str() {
+        node.is_valid_string(value)
+    } else if let Some(items) = instance.as_array() {
+        if !node.is_valid_scalar(PrimitiveType::Array) {
+            return Some(false);
+        }
+        let mut count = 0_u64;
+        for item in items {
+            count += 1;
+            if let Some(plan) = node.items() {
+                if !check(plan, item)? {
+                    return Some(false);
+                }
+            }
+        }
+        node.is_valid_items_count(count)
+    } else if let Some(properties) = instance.as_object() {
+        if !node.is_valid_scalar(PrimitiveType::Object) {
+            return Some(false);
+        }
+        // Values are checked even after a failure, since a duplicate key may replace them
+        let mut state = ObjectState::new(node);
+        for (key, value) in properties {
+            let is_valid = match state.plan(key) {
+                Some(plan) => check(plan, value)?,
+                None => true,
+            };
+            state.record(key, is_valid);
+        }
+        state.is_satisfied()
+    } else {
+        // Not a JSON value; only the most permissive schemas accept it
+        false
+    };
+    Some(is_valid)
+}
+
+impl<'a> JSONSchema<'a> {
+    /// Check a `JsonLike` instance without converting it into `Value`.
+    ///
+    /// Returns `None` if the result depends on keywords outside of the subset listed in
+    /// `JsonLike`, e.g. `pattern`, `oneOf` or `$ref`. Such instances could be converted via
+    /// `JsonLike::to_value` and validated as usual.
+    ///
+    /// ```rust
+    /// # use jsonschema::JSONSchema;
+    /// # use serde_json::json;
+    /// let schema = json!({"properties": {"name": {"maxLength": 3}, "id": {"pattern": "^a"}}});
+    /// let compiled = JSONSchema::compile(&schema).unwrap();
+    /// assert_eq!(compiled.is_valid_instance(&json!({"name": "foo"})), Some(true));
+    /// assert_eq!(compiled.is_valid_instance(&json!({"id": "abc"})), None);
+    /// ```
+    pub fn is_valid_instance<I: JsonLike + ?Sized>(&self, instance: &I) -> Option<bool> {
+        check(self.plan()?, instance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JsonLike;
+    use crate::JSONSchema;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    /// A minimal custom representation
+    enum Tree {
+        Null,
+        Integer(i64),
+        Text(String),
+        List(Vec<Tree>),
+        Dict(Vec<(String, Tree)>),
+    }
+
+    impl JsonLike for Tree {
+        fn is_null(&self) -> bool {
+            matches!(self, Tree::Null)
+        }
+        fn as_bool(&self) -> Option<bool> {
+            None
+        }
+        fn as_u64(&self) -> Option<u64> {
+            None
+        }
+        fn as_i64(&self) -> Option<i64> {
+            match self {
+                Tree::Integer(value) => Some(*value),
+                _ => None,
+            }
+        }
+        fn as_f64(&self) -> Option<f64> {
+            None
+        }
+        fn as_str(&self) -> Option<&str> {
+            match self {
+                Tree::Text(value) => Some(value),
+                _ => None,
+            }
+        }
+        fn as_array(&self) -> Option<Box<dyn Iterator<Item = &Self> + '_>> {
+            match self {
+                Tree::List(items) => Some(Box::new(items.iter())),
+                _ => None,
+            }
+        }
+        fn as_object(&self) -> Option<Box<dyn Iterator<Item = (&str, &Self)> + '_>> {
+            match self {
+                Tree::Dict(items) => Some(Box::new(
+                    items.iter().map(|(key, value)| (key.as_str(), value)),
+                )),
+                _ => None,
+            }
+        }
+    }
+
+    fn document(name: &str, tags: Vec<Tree>) -> Tree {
+        Tree::Dict(vec![
+            ("name".to_string(), Tree::Text(name.to_string())),
+            ("age".to_string(), Tree::Integer(-5)),
+            ("tags".to_string(), Tree::List(tags)),
+            ("extra".to_string(), Tree::Null),
+        ])
+    }
+
+    #[test_case(&json!({"properties": {"name": {"maxLength": 3}}, "required": ["name", "age"]}), true)]
+    #[test_case(&json!({"properties": {"age": {"minimum": 0}}}), true)]
+    #[test_case(&json!({"properties": {"tags": {"items": {"type": "integer"}, "minItems": 1}}}), true)]
+    #[test_case(&json!({"properties": {"name": {"pattern": "^f"}}, "additionalProperties": {"type": ["array", "integer"]}}), false)]
+    #[test_case(&json!({"additionalProperties": false}), true)]
+    #[test_case(&json!({"anyOf": [{"required": ["missing"]}, {"minProperties": 4}]}), false)]
+    fn consistent_with_is_valid(schema: &Value, is_supported: bool) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        let documents = [
+            document("foo", vec![Tree::Integer(1)]),
+            document("bar", vec![]),
+            document("foobar", vec![Tree::Text("a".to_string())]),
+        ];
+        for document in &documents {
+            let value = document.to_value();
+            let expected = Some(compiled.is_valid(&value)).filter(|_| is_supported);
+            assert_eq!(
+                compiled.is_valid_instance(document),
+                expected,
+                "{} against {}",
+                value,
+                schema
+            );
+        }
+    }
+
+    #[test]
+    fn serde_json_value() {
+        let schema = json!({"items": {"type": "string"}});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        assert_eq!(compiled.is_valid_instance(&json!(["a"])), Some(true));
+        assert_eq!(compiled.is_valid_instance(&json!([1])), Some(false));
+    }
+}
@@ -47,7 +47,7 @@ impl Validate for AdditionalItemsObjectValidator {
 
     #[inline]
     fn validate_array<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         _: &'a Value,
         instance_array: &'a [Value],
@@ -55,18 +55,19 @@ impl Validate for AdditionalItemsObjectValidator {
         Box::new(
             instance_array
                 .iter()
+                .enumerate()
                 .skip(self.items_count)
-                .flat_map(|item| {
-                    self.validators
-                        .iter()
-                        .flat_map(move |validator| validator.validate(schema, item))
-                })
-                .collect::<Vec<_>>()
-                .into_iter(),
+                .flat_map(move |(idx, item)| {
+                    self.validators.iter().flat_map(move |validator| {
+                        validator
+                            .validate(schema, item)
+                            .map(move |error| error.within(idx))
+                    })
+                }),
         )
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Array(instance_value) = instance {
             self.validate_array(schema, instance, instance_value)
         } else {
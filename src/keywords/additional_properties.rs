@@ -44,25 +44,21 @@ impl Validate for AdditionalPropertiesValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         _: &'a Value,
         instance_value: &'a Map<String, Value>,
     ) -> ErrorIterator<'a> {
-        Box::new(
-            self.validators
-                .iter()
-                .flat_map(move |validator| {
-                    instance_value
-                        .values()
-                        .flat_map(move |value| validator.validate(schema, value))
-                })
-                .collect::<Vec<_>>()
-                .into_iter(),
-        )
+        Box::new(self.validators.iter().flat_map(move |validator| {
+            instance_value.iter().flat_map(move |(property, value)| {
+                validator
+                    .validate(schema, value)
+                    .map(move |error| error.within(property))
+            })
+        }))
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
             self.validate_object(schema, instance, instance_value)
         } else {
@@ -154,7 +150,7 @@ impl Validate for AdditionalPropertiesNotEmptyFalseValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         _: &'a JSONSchema,
         _: &'a Value,
         instance_value: &'a Map<String, Value>,
@@ -176,7 +172,7 @@ impl Validate for AdditionalPropertiesNotEmptyFalseValidator {
             .unwrap_or_else(no_error)
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
             self.validate_object(schema, instance, instance_value)
         } else {
@@ -237,26 +233,24 @@ impl Validate for AdditionalPropertiesNotEmptyValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         _: &'a Value,
         instance_value: &'a Map<String, Value>,
     ) -> ErrorIterator<'a> {
-        Box::new(
-            self.validators
+        Box::new(self.validators.iter().flat_map(move |validator| {
+            instance_value
                 .iter()
-                .flat_map(move |validator| {
-                    instance_value
-                        .iter()
-                        .filter(move |(property, _)| !self.properties.contains(*property))
-                        .flat_map(move |(_, value)| validator.validate(schema, value))
+                .filter(move |(property, _)| !self.properties.contains(*property))
+                .flat_map(move |(property, value)| {
+                    validator
+                        .validate(schema, value)
+                        .map(move |error| error.within(property))
                 })
-                .collect::<Vec<_>>()
-                .into_iter(),
-        )
+        }))
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
             self.validate_object(schema, instance, instance_value)
         } else {
@@ -316,26 +310,24 @@ impl Validate for AdditionalPropertiesWithPatternsValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         _: &'a Value,
         instance_value: &'a Map<String, Value>,
     ) -> ErrorIterator<'a> {
-        Box::new(
-            self.validators
+        Box::new(self.validators.iter().flat_map(move |validator| {
+            instance_value
                 .iter()
-                .flat_map(move |validator| {
-                    instance_value
-                        .iter()
-                        .filter(move |(property, _)| !self.pattern.is_match(property))
-                        .flat_map(move |(_, value)| validator.validate(schema, value))
+                .filter(move |(property, _)| !self.pattern.is_match(property))
+                .flat_map(move |(property, value)| {
+                    validator
+                        .validate(schema, value)
+                        .map(move |error| error.within(property))
                 })
-                .collect::<Vec<_>>()
-                .into_iter(),
-        )
+        }))
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
             self.validate_object(schema, instance, instance_value)
         } else {
@@ -386,7 +378,7 @@ impl Validate for AdditionalPropertiesWithPatternsFalseValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         _: &'a JSONSchema,
         _: &'a Value,
         instance_value: &'a Map<String, Value>,
@@ -402,7 +394,7 @@ impl Validate for AdditionalPropertiesWithPatternsFalseValidator {
             })
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
             self.validate_object(schema, instance, instance_value)
         } else {
@@ -470,29 +462,26 @@ impl Validate for AdditionalPropertiesWithPatternsNotEmptyValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         _: &'a Value,
         instance_value: &'a Map<String, Value>,
     ) -> ErrorIterator<'a> {
-        Box::new(
-            self.validators
+        Box::new(self.validators.iter().flat_map(move |validator| {
+            instance_value
                 .iter()
-                .flat_map(move |validator| {
-                    instance_value
-                        .iter()
-                        .filter(move |(property, _)| {
-                            !(self.properties.contains(*property)
-                                || self.pattern.is_match(property))
-                        })
-                        .flat_map(move |(_, value)| validator.validate(schema, value))
+                .filter(move |(property, _)| {
+                    !(self.properties.contains(*property) || self.pattern.is_match(property))
                 })
-                .collect::<Vec<_>>()
-                .into_iter(),
-        )
+                .flat_map(move |(property, value)| {
+                    validator
+                        .validate(schema, value)
+                        .map(move |error| error.within(property))
+                })
+        }))
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
             self.validate_object(schema, instance, instance_value)
         } else {
@@ -551,7 +540,7 @@ impl Validate for AdditionalPropertiesWithPatternsNotEmptyFalseValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         _: &'a JSONSchema,
         _: &'a Value,
         instance_value: &'a Map<String, Value>,
@@ -569,7 +558,7 @@ impl Validate for AdditionalPropertiesWithPatternsNotEmptyFalseValidator {
             })
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
             self.validate_object(schema, instance, instance_value)
         } else {
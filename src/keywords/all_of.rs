@@ -15,8 +15,8 @@ impl AllOfValidator {
     pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         if let Value::Array(items) = schema {
             let mut schemas = Vec::with_capacity(items.len());
-            for item in items {
-                let validators = compile_validators(item, context)?;
+            for (idx, item) in items.iter().enumerate() {
+                let validators = compile_validators(item, &context.with_path(idx.to_string()))?;
                 schemas.push(validators)
             }
             Ok(Box::new(AllOfValidator { schemas }))
@@ -50,7 +50,7 @@ macro_rules! all_of_impl_validate {
         paste::item! {
             #[inline]
             fn [<validate_ $method_suffix>]<'a>(
-                &self,
+                &'a self,
                 schema: &'a JSONSchema,
                 instance: &'a Value,
                 instance_value: $instance_type,
@@ -62,9 +62,7 @@ macro_rules! all_of_impl_validate {
                             validators.iter().flat_map(move |validator| {
                                 validator.[<validate_ $method_suffix>](schema, instance, instance_value)
                             })
-                        })
-                        .collect::<Vec<_>>()
-                        .into_iter(),
+                        }),
                 )
             }
         }
@@ -15,8 +15,8 @@ impl AnyOfValidator {
     pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         if let Value::Array(items) = schema {
             let mut schemas = Vec::with_capacity(items.len());
-            for item in items {
-                let validators = compile_validators(item, context)?;
+            for (idx, item) in items.iter().enumerate() {
+                let validators = compile_validators(item, &context.with_path(idx.to_string()))?;
                 schemas.push(validators)
             }
             Ok(Box::new(AnyOfValidator { schemas }))
@@ -20,7 +20,7 @@ impl Validate for TrueValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, _: &'a JSONSchema, _: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, _: &'a Value) -> ErrorIterator<'a> {
         no_error()
     }
 }
@@ -81,7 +81,7 @@ impl Validate for FalseValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         error(self.build_validation_error(instance))
     }
 }
@@ -66,7 +66,7 @@ impl Validate for ConstArrayValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Array(instance_value) = instance {
             self.validate_array(schema, instance, instance_value)
         } else {
@@ -144,7 +144,7 @@ impl Validate for ConstBooleanValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Bool(instance_value) = instance {
             self.validate_boolean(schema, instance, *instance_value)
         } else {
@@ -213,7 +213,7 @@ impl Validate for ConstNullValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Null = instance {
             self.validate_null(schema, instance, ())
         } else {
@@ -303,7 +303,7 @@ impl Validate for ConstNumberValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Some(instance_value) = instance.as_f64() {
             self.validate_number(schema, instance, instance_value)
         } else {
@@ -381,7 +381,7 @@ impl Validate for ConstObjectValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
             self.validate_object(schema, instance, instance_value)
         } else {
@@ -461,7 +461,7 @@ impl Validate for ConstStringValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::String(instance_value) = instance {
             self.validate_string(schema, instance, instance_value)
         } else {
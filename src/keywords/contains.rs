@@ -43,7 +43,7 @@ impl Validate for ContainsValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Array(instance_value) = instance {
             self.validate_array(schema, instance, instance_value)
         } else {
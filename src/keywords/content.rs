@@ -42,7 +42,7 @@ impl Validate for ContentMediaTypeValidator {
 
     #[inline]
     fn validate_string<'a>(
-        &self,
+        &'a self,
         _: &'a JSONSchema,
         instance: &'a Value,
         instance_value: &'a str,
@@ -57,7 +57,7 @@ impl Validate for ContentMediaTypeValidator {
         }
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::String(instance_value) = instance {
             self.validate_string(schema, instance, instance_value)
         } else {
@@ -103,7 +103,7 @@ impl Validate for ContentEncodingValidator {
 
     #[inline]
     fn validate_string<'a>(
-        &self,
+        &'a self,
         _: &'a JSONSchema,
         instance: &'a Value,
         instance_value: &'a str,
@@ -115,7 +115,7 @@ impl Validate for ContentEncodingValidator {
         }
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::String(instance_value) = instance {
             self.validate_string(schema, instance, instance_value)
         } else {
@@ -174,7 +174,7 @@ impl Validate for ContentMediaTypeAndEncodingValidator {
 
     #[inline]
     fn validate_string<'a>(
-        &self,
+        &'a self,
         _: &'a JSONSchema,
         instance: &'a Value,
         instance_value: &'a str,
@@ -197,7 +197,7 @@ impl Validate for ContentMediaTypeAndEncodingValidator {
         }
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::String(instance_value) = instance {
             self.validate_string(schema, instance, instance_value)
         } else {
@@ -20,7 +20,7 @@ impl DependenciesValidator {
             for (key, subschema) in map {
                 let s = match subschema {
                     Value::Array(_) => vec![RequiredValidator::compile(subschema)?],
-                    _ => compile_validators(subschema, context)?,
+                    _ => compile_validators(subschema, &context.with_path(key.as_str()))?,
                 };
                 dependencies.push((key.clone(), s))
             }
@@ -59,7 +59,7 @@ impl Validate for DependenciesValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         instance: &'a Value,
         instance_value: &'a Map<String, Value>,
@@ -68,18 +68,16 @@ impl Validate for DependenciesValidator {
         Box::new(
             self.dependencies
                 .iter()
-                .filter(|(property, _)| instance_value.contains_key(property))
+                .filter(move |(property, _)| instance_value.contains_key(property))
                 .flat_map(move |(_, validators)| {
                     validators.iter().flat_map(move |validator| {
                         validator.validate_object(schema, instance, instance_value)
                     })
-                })
-                .collect::<Vec<_>>()
-                .into_iter(),
+                }),
         )
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
             self.validate_object(schema, instance, instance_value)
         } else {
@@ -1,7 +1,7 @@
 use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{CompilationError, ValidationError},
-    keywords::CompilationResult,
+    keywords::{const_, BoxedValidator, CompilationResult},
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -117,6 +117,82 @@ impl ToString for EnumValidator {
     }
 }
 
+/// `enum` with a single option. It is checked like `const`, but errors are reported as
+/// for `enum`.
+pub(crate) struct SingleValueEnumValidator {
+    options: Value,
+    value: BoxedValidator,
+}
+
+impl SingleValueEnumValidator {
+    #[inline]
+    pub(crate) fn compile(
+        parent: &Map<String, Value>,
+        schema: &Value,
+        context: &CompilationContext,
+    ) -> CompilationResult {
+        match schema {
+            Value::Array(items) if items.len() == 1 => Ok(Box::new(SingleValueEnumValidator {
+                options: schema.clone(),
+                value: const_::compile(parent, &items[0], context)
+                    .expect("Should always return Some")?,
+            })),
+            _ => Err(CompilationError::SchemaError),
+        }
+    }
+}
+
+impl Validate for SingleValueEnumValidator {
+    #[inline]
+    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
+        ValidationError::enumeration(instance, &self.options)
+    }
+
+    #[inline]
+    fn is_valid_array(&self, schema: &JSONSchema, instance: &Value, value: &[Value]) -> bool {
+        self.value.is_valid_array(schema, instance, value)
+    }
+    #[inline]
+    fn is_valid_boolean(&self, schema: &JSONSchema, instance: &Value, value: bool) -> bool {
+        self.value.is_valid_boolean(schema, instance, value)
+    }
+    #[inline]
+    fn is_valid_object(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        value: &Map<String, Value>,
+    ) -> bool {
+        self.value.is_valid_object(schema, instance, value)
+    }
+    #[inline]
+    fn is_valid_null(&self, schema: &JSONSchema, instance: &Value, value: ()) -> bool {
+        self.value.is_valid_null(schema, instance, value)
+    }
+    #[inline]
+    fn is_valid_number(&self, schema: &JSONSchema, instance: &Value, value: f64) -> bool {
+        self.value.is_valid_number(schema, instance, value)
+    }
+    #[inline]
+    fn is_valid_signed_integer(&self, schema: &JSONSchema, instance: &Value, value: i64) -> bool {
+        self.value.is_valid_signed_integer(schema, instance, value)
+    }
+    #[inline]
+    fn is_valid_string(&self, schema: &JSONSchema, instance: &Value, value: &str) -> bool {
+        self.value.is_valid_string(schema, instance, value)
+    }
+    #[inline]
+    fn is_valid_unsigned_integer(&self, schema: &JSONSchema, instance: &Value, value: u64) -> bool {
+        self.value
+            .is_valid_unsigned_integer(schema, instance, value)
+    }
+}
+impl ToString for SingleValueEnumValidator {
+    fn to_string(&self) -> String {
+        self.value.to_string()
+    }
+}
+
 #[inline]
 pub(crate) fn compile(
     _: &Map<String, Value>,
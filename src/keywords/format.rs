@@ -72,7 +72,7 @@ macro_rules! string_format_validator {
             }
 
             #[inline]
-            fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+            fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
                 if let Value::String(instance_value) = instance {
                     self.validate_string(schema, instance, instance_value)
                 } else {
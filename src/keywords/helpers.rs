@@ -0,0 +1,48 @@
+//! Checks that are shared by keyword validators and the single-pass validation plan, hence both
+//! always agree on the result.
+use crate::primitive_type::{PrimitiveType, PrimitiveTypesBitMap};
+use num_cmp::NumCmp;
+
+/// Whether an integer instance matches `types`. Every integer is a number as well.
+#[inline]
+pub(crate) fn is_integer_of(types: PrimitiveTypesBitMap) -> bool {
+    types.contains_type(PrimitiveType::Integer) || types.contains_type(PrimitiveType::Number)
+}
+
+/// Whether a floating point instance matches `types`.
+/// Draft 4 does not consider numbers like `1.0` as integers, which is set by `strict_integers`.
+#[inline]
+pub(crate) fn is_number_of(types: PrimitiveTypesBitMap, value: f64, strict_integers: bool) -> bool {
+    types.contains_type(PrimitiveType::Number)
+        || (!strict_integers && types.contains_type(PrimitiveType::Integer) && value.fract() == 0.)
+}
+
+/// Length of a string in Unicode code points, as `minLength` & `maxLength` define it.
+#[inline]
+pub(crate) fn string_length(value: &str) -> u64 {
+    value.chars().count() as u64
+}
+
+/// `minimum`
+#[inline]
+pub(crate) fn is_at_least<V: NumCmp<L>, L: Copy>(value: V, limit: L) -> bool {
+    NumCmp::num_ge(value, limit)
+}
+
+/// `maximum`
+#[inline]
+pub(crate) fn is_at_most<V: NumCmp<L>, L: Copy>(value: V, limit: L) -> bool {
+    NumCmp::num_le(value, limit)
+}
+
+/// `exclusiveMinimum`
+#[inline]
+pub(crate) fn is_above<V: NumCmp<L>, L: Copy>(value: V, limit: L) -> bool {
+    NumCmp::num_gt(value, limit)
+}
+
+/// `exclusiveMaximum`
+#[inline]
+pub(crate) fn is_below<V: NumCmp<L>, L: Copy>(value: V, limit: L) -> bool {
+    NumCmp::num_lt(value, limit)
+}
@@ -20,7 +20,7 @@ impl IfThenValidator {
     ) -> CompilationResult {
         Ok(Box::new(IfThenValidator {
             schema: compile_validators(schema, context)?,
-            then_schema: compile_validators(then_schema, context)?,
+            then_schema: compile_validators(then_schema, &context.with_sibling_path("then"))?,
         }))
     }
 }
@@ -55,7 +55,7 @@ macro_rules! if_then_impl_validate {
         paste::item! {
             #[inline]
             fn [<validate_ $method_suffix>]<'a>(
-                &self,
+                &'a self,
                 schema: &'a JSONSchema,
                 instance: &'a Value,
                 instance_value: $instance_type,
@@ -70,8 +70,6 @@ macro_rules! if_then_impl_validate {
                             .then_schema
                             .iter()
                             .flat_map(move |validator| validator.[<validate_ $method_suffix>](schema, instance, instance_value))
-                            .collect::<Vec<_>>()
-                            .into_iter()
                         )
                 } else {
                     no_error()
@@ -124,7 +122,7 @@ impl IfElseValidator {
     ) -> CompilationResult {
         Ok(Box::new(IfElseValidator {
             schema: compile_validators(schema, context)?,
-            else_schema: compile_validators(else_schema, context)?,
+            else_schema: compile_validators(else_schema, &context.with_sibling_path("else"))?,
         }))
     }
 }
@@ -159,7 +157,7 @@ macro_rules! if_else_impl_validate {
         paste::item! {
             #[inline]
             fn [<validate_ $method_suffix>]<'a>(
-                &self,
+                &'a self,
                 schema: &'a JSONSchema,
                 instance: &'a Value,
                 instance_value: $instance_type,
@@ -176,8 +174,6 @@ macro_rules! if_else_impl_validate {
                             .else_schema
                             .iter()
                             .flat_map(move |validator| validator.[<validate_ $method_suffix>](schema, instance, instance_value))
-                            .collect::<Vec<_>>()
-                            .into_iter()
                         )
                 }
             }
@@ -230,8 +226,8 @@ impl IfThenElseValidator {
     ) -> CompilationResult {
         Ok(Box::new(IfThenElseValidator {
             schema: compile_validators(schema, context)?,
-            then_schema: compile_validators(then_schema, context)?,
-            else_schema: compile_validators(else_schema, context)?,
+            then_schema: compile_validators(then_schema, &context.with_sibling_path("then"))?,
+            else_schema: compile_validators(else_schema, &context.with_sibling_path("else"))?,
         }))
     }
 }
@@ -268,7 +264,7 @@ macro_rules! if_then_else_impl_validate {
         paste::item! {
             #[inline]
             fn [<validate_ $method_suffix>]<'a>(
-                &self,
+                &'a self,
                 schema: &'a JSONSchema,
                 instance: &'a Value,
                 instance_value: $instance_type,
@@ -283,8 +279,6 @@ macro_rules! if_then_else_impl_validate {
                             .then_schema
                             .iter()
                             .flat_map(move |validator| validator.[<validate_ $method_suffix>](schema, instance, instance_value))
-                            .collect::<Vec<_>>()
-                            .into_iter()
                     )
                 } else {
                     Box::new(
@@ -292,8 +286,6 @@ macro_rules! if_then_else_impl_validate {
                             .else_schema
                             .iter()
                             .flat_map(move |validator| validator.[<validate_ $method_suffix>](schema, instance, instance_value))
-                            .collect::<Vec<_>>()
-                            .into_iter()
                         )
                 }
             }
@@ -17,8 +17,8 @@ impl ItemsArrayValidator {
     #[inline]
     pub(crate) fn compile(schemas: &[Value], context: &CompilationContext) -> CompilationResult {
         let mut items = Vec::with_capacity(schemas.len());
-        for item in schemas {
-            let validators = compile_validators(item, context)?;
+        for (idx, item) in schemas.iter().enumerate() {
+            let validators = compile_validators(item, &context.with_path(idx.to_string()))?;
             items.push(validators)
         }
         Ok(Box::new(ItemsArrayValidator { items }))
@@ -47,7 +47,7 @@ impl Validate for ItemsArrayValidator {
 
     #[inline]
     fn validate_array<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         _: &'a Value,
         instance_value: &'a [Value],
@@ -56,17 +56,18 @@ impl Validate for ItemsArrayValidator {
             instance_value
                 .iter()
                 .zip(self.items.iter())
-                .flat_map(move |(item, validators)| {
-                    validators
-                        .iter()
-                        .flat_map(move |validator| validator.validate(schema, item))
-                })
-                .collect::<Vec<_>>()
-                .into_iter(),
+                .enumerate()
+                .flat_map(move |(idx, (item, validators))| {
+                    validators.iter().flat_map(move |validator| {
+                        validator
+                            .validate(schema, item)
+                            .map(move |error| error.within(idx))
+                    })
+                }),
         )
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Array(instance_value) = instance {
             self.validate_array(schema, instance, instance_value)
         } else {
@@ -118,35 +119,42 @@ impl Validate for ItemsObjectValidator {
 
     #[inline]
     fn validate_array<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         _: &'a Value,
         instance_value: &'a [Value],
     ) -> ErrorIterator<'a> {
-        let errors: Vec<_> = if instance_value.len() > 8 {
-            instance_value
-                .par_iter()
-                .flat_map(|item| {
-                    self.validators
-                        .iter()
-                        .flat_map(|validator| validator.validate(schema, item))
-                        .collect::<Vec<_>>()
+        if instance_value.len() > 8 {
+            // Invalid items are found in parallel, but their errors are still produced lazily,
+            // one validator at a time
+            Box::new(self.validators.iter().flat_map(move |validator| {
+                let invalid: Vec<_> = instance_value
+                    .par_iter()
+                    .enumerate()
+                    .filter(|(_, item)| !validator.is_valid(schema, item))
+                    .map(|(idx, _)| idx)
+                    .collect();
+                invalid.into_iter().flat_map(move |idx| {
+                    validator
+                        .validate(schema, &instance_value[idx])
+                        .map(move |error| error.within(idx))
                 })
-                .collect()
+            }))
         } else {
-            self.validators
-                .iter()
-                .flat_map(move |validator| {
-                    instance_value
-                        .iter()
-                        .flat_map(move |item| validator.validate(schema, item))
-                })
-                .collect()
-        };
-        Box::new(errors.into_iter())
+            Box::new(self.validators.iter().flat_map(move |validator| {
+                instance_value
+                    .iter()
+                    .enumerate()
+                    .flat_map(move |(idx, item)| {
+                        validator
+                            .validate(schema, item)
+                            .map(move |error| error.within(idx))
+                    })
+            }))
+        }
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Array(instance_value) = instance {
             self.validate_array(schema, instance, instance_value)
         } else {
@@ -1,7 +1,10 @@
 use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
-    keywords::{type_, CompilationResult},
+    keywords::{
+        helpers::{is_integer_of, is_number_of},
+        type_, CompilationResult,
+    },
     primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
     validator::Validate,
 };
@@ -51,8 +54,8 @@ impl Validate for MultipleTypesValidator {
         self.types.contains_type(PrimitiveType::Null)
     }
     #[inline]
-    fn is_valid_number(&self, _: &JSONSchema, _: &Value, _: f64) -> bool {
-        self.types.contains_type(PrimitiveType::Number)
+    fn is_valid_number(&self, _: &JSONSchema, _: &Value, instance_value: f64) -> bool {
+        is_number_of(self.types, instance_value, true)
     }
     #[inline]
     fn is_valid_object(&self, _: &JSONSchema, _: &Value, _: &Map<String, Value>) -> bool {
@@ -60,7 +63,7 @@ impl Validate for MultipleTypesValidator {
     }
     #[inline]
     fn is_valid_signed_integer(&self, _: &JSONSchema, _: &Value, _: i64) -> bool {
-        self.types.contains_type(PrimitiveType::Integer)
+        is_integer_of(self.types)
     }
     #[inline]
     fn is_valid_string(&self, _: &JSONSchema, _: &Value, _: &str) -> bool {
@@ -68,7 +71,7 @@ impl Validate for MultipleTypesValidator {
     }
     #[inline]
     fn is_valid_unsigned_integer(&self, _: &JSONSchema, _: &Value, _: u64) -> bool {
-        self.types.contains_type(PrimitiveType::Integer)
+        is_integer_of(self.types)
     }
 }
 impl ToString for MultipleTypesValidator {
@@ -132,7 +135,7 @@ impl Validate for IntegerTypeValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Number(_) = instance {
             if self.is_valid(schema, instance) {
                 no_error()
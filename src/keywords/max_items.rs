@@ -41,7 +41,7 @@ impl Validate for MaxItemsValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Array(instance_value) = instance {
             self.validate_array(schema, instance, instance_value)
         } else {
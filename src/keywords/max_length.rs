@@ -1,7 +1,7 @@
 use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{no_error, CompilationError, ErrorIterator, ValidationError},
-    keywords::CompilationResult,
+    keywords::{helpers::string_length, CompilationResult},
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -29,7 +29,7 @@ impl Validate for MaxLengthValidator {
 
     #[inline]
     fn is_valid_string(&self, _: &JSONSchema, _: &Value, instance_value: &str) -> bool {
-        instance_value.chars().count() as u64 <= self.limit
+        string_length(instance_value) <= self.limit
     }
     #[inline]
     fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
@@ -41,7 +41,7 @@ impl Validate for MaxLengthValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::String(instance_value) = instance {
             self.validate_string(schema, instance, instance_value)
         } else {
@@ -1,10 +1,9 @@
 use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{no_error, CompilationError, ErrorIterator, ValidationError},
-    keywords::CompilationResult,
+    keywords::{helpers::is_at_most, CompilationResult},
     validator::Validate,
 };
-use num_cmp::NumCmp;
 use serde_json::{Map, Value};
 
 pub(crate) struct MaximumU64Validator {
@@ -28,7 +27,7 @@ macro_rules! validate {
 
             #[inline]
             fn is_valid_number(&self, _: &JSONSchema, _: &Value, instance_value: f64) -> bool {
-                NumCmp::num_le(instance_value, self.limit)
+                is_at_most(instance_value, self.limit)
             }
             #[inline]
             fn is_valid_signed_integer(
@@ -37,7 +36,7 @@ macro_rules! validate {
                 _: &Value,
                 instance_value: i64,
             ) -> bool {
-                NumCmp::num_le(instance_value, self.limit)
+                is_at_most(instance_value, self.limit)
             }
             #[inline]
             fn is_valid_unsigned_integer(
@@ -46,7 +45,7 @@ macro_rules! validate {
                 _: &Value,
                 instance_value: u64,
             ) -> bool {
-                NumCmp::num_le(instance_value, self.limit)
+                is_at_most(instance_value, self.limit)
             }
             #[inline]
             fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
@@ -63,7 +62,7 @@ macro_rules! validate {
 
             #[inline]
             fn validate<'a>(
-                &self,
+                &'a self,
                 schema: &'a JSONSchema,
                 instance: &'a Value,
             ) -> ErrorIterator<'a> {
@@ -46,7 +46,7 @@ impl Validate for MinPropertiesValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
             self.validate_object(schema, instance, instance_value)
         } else {
@@ -11,6 +11,7 @@ pub(crate) mod enum_;
 pub(crate) mod exclusive_maximum;
 pub(crate) mod exclusive_minimum;
 pub(crate) mod format;
+pub(crate) mod helpers;
 pub(crate) mod if_;
 pub(crate) mod items;
 pub(crate) mod legacy;
@@ -29,6 +30,7 @@ pub(crate) mod pattern;
 pub(crate) mod pattern_properties;
 pub(crate) mod properties;
 pub(crate) mod property_names;
+pub(crate) mod range;
 pub(crate) mod ref_;
 pub(crate) mod required;
 pub(crate) mod type_;
@@ -104,7 +106,7 @@ mod tests {
     #[test_case(&json!({"contentEncoding": "base64"}), "contentEncoding: base64")]
     #[test_case(&json!({"contentEncoding": "base64", "contentMediaType": "application/json"}), "{contentMediaType: application/json, contentEncoding: base64}")]
     #[test_case(&json!({"dependencies": {"bar": ["foo"]}}), "dependencies: {bar: {required: [foo]}}")]
-    #[test_case(&json!({"enum": [1]}), "enum: [1]")]
+    #[test_case(&json!({"enum": [1, 2]}), "enum: [1, 2]")]
     #[test_case(&json!({"exclusiveMaximum": 1}), "exclusiveMaximum: 1")]
     #[test_case(&json!({"exclusiveMinimum": 1}), "exclusiveMinimum: 1")]
     #[test_case(&json!({"format": "date"}), "format: date")]
@@ -129,7 +131,7 @@ mod tests {
     #[test_case(&json!({"if": {"exclusiveMaximum": 0}, "then": {"minimum": -10}, "else": {"multipleOf": 2}}), "if: {exclusiveMaximum: 0}, then: {minimum: -10}, else: {multipleOf: 2}")]
     #[test_case(&json!({"items": [{"type": "string"}]}), "items: [{type: string}]")]
     #[test_case(&json!({"items": {"type": "integer"}}), "items: {type: integer}")]
-    #[test_case(&json!({"items": {"type": "integer", "minimum": 4}}), "items: {minimum: 4, type: integer}")]
+    #[test_case(&json!({"items": {"type": "integer", "minimum": 4}}), "items: {type: integer, minimum: 4}")]
     #[test_case(&json!({"maxItems": 1}), "maxItems: 1")]
     #[test_case(&json!({"maxLength": 1}), "maxLength: 1")]
     #[test_case(&json!({"maxProperties": 1}), "maxProperties: 1")]
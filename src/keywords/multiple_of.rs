@@ -5,16 +5,108 @@ use crate::{
     validator::Validate,
 };
 use serde_json::{Map, Value};
-use std::f64::EPSILON;
+
+/// Split the shortest decimal representation of `value` into its digits and the number of
+/// digits after the decimal point, e.g. `12.05` -> `("1205", 2)`.
+///
+/// `f64` formatting in Rust never uses the exponent notation, therefore the output always
+/// contains plain digits that represent `value` exactly as it was written in the document.
+#[inline]
+fn decimal_digits(value: f64) -> (String, usize) {
+    let representation = value.abs().to_string();
+    match representation.find('.') {
+        Some(position) => {
+            let scale = representation.len() - position - 1;
+            let mut digits = representation;
+            digits.remove(position);
+            (digits, scale)
+        }
+        None => (representation, 0),
+    }
+}
+
+/// Remainder of dividing a non-negative decimal number (given as digits) by `divisor`.
+/// The digits are followed by `trailing_zeros` zeros.
+#[inline]
+fn decimal_remainder(digits: &str, trailing_zeros: usize, divisor: u64) -> u64 {
+    let divisor = u128::from(divisor);
+    let mut remainder: u128 = 0;
+    // `remainder < divisor <= u64::MAX`, therefore these operations never overflow `u128`
+    for digit in digits.bytes() {
+        remainder = (remainder * 10 + u128::from(digit - b'0')) % divisor;
+    }
+    for _ in 0..trailing_zeros {
+        remainder = remainder * 10 % divisor;
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    {
+        remainder as u64
+    }
+}
+
+/// `multiple_of` scaled to an integer and the number of its decimal places,
+/// e.g. `0.01` -> `(1, 2)`.
+#[inline]
+fn scale(multiple_of: f64) -> Option<(u64, usize)> {
+    let (digits, scale) = decimal_digits(multiple_of);
+    // Non-integer numbers have at most 17 significant digits, so they always fit into `u64`
+    let digits = digits.trim_start_matches('0').parse::<u64>().ok()?;
+    Some((digits, scale))
+}
+
+/// Check divisibility exactly by scaling both numbers to integers with the same number
+/// of decimal places. E.g. `0.3` and `0.1` become `3` and `1`.
+///
+/// The last decimal place of the shortest representation is never zero, hence a number with
+/// more decimal places than `multiple_of` is never its multiple.
+#[inline]
+fn is_decimal_multiple(
+    instance_value: f64,
+    multiple_of_digits: u64,
+    multiple_of_scale: usize,
+) -> bool {
+    let (digits, scale) = decimal_digits(instance_value);
+    scale <= multiple_of_scale
+        && decimal_remainder(&digits, multiple_of_scale - scale, multiple_of_digits) == 0
+}
+
+/// Divisibility by an integer `multiple_of`.
+#[inline]
+fn is_integer_multiple(instance_value: f64, multiple_of: f64) -> bool {
+    // Numbers with a fractional part are never multiples of an integer
+    instance_value.fract() == 0. && (instance_value % multiple_of) == 0.
+}
+
+/// Whether `value` is a multiple of `multiple_of` by the rules of the `multipleOf` keyword.
+#[inline]
+pub(crate) fn is_multiple_of(value: f64, multiple_of: f64) -> bool {
+    if multiple_of.fract() == 0. {
+        is_integer_multiple(value, multiple_of)
+    } else if let Some((digits, scale)) = scale(multiple_of) {
+        is_decimal_multiple(value, digits, scale)
+    } else {
+        false
+    }
+}
 
 pub(crate) struct MultipleOfFloatValidator {
     multiple_of: f64,
+    /// `multiple_of` scaled to an integer, e.g. `0.01` -> `1`
+    multiple_of_digits: u64,
+    /// How many decimal places `multiple_of` has, e.g. `0.01` -> `2`
+    multiple_of_scale: usize,
 }
 
 impl MultipleOfFloatValidator {
     #[inline]
     pub(crate) fn compile(multiple_of: f64) -> CompilationResult {
-        Ok(Box::new(MultipleOfFloatValidator { multiple_of }))
+        let (multiple_of_digits, multiple_of_scale) =
+            scale(multiple_of).ok_or(CompilationError::SchemaError)?;
+        Ok(Box::new(MultipleOfFloatValidator {
+            multiple_of,
+            multiple_of_digits,
+            multiple_of_scale,
+        }))
     }
 }
 
@@ -26,8 +118,11 @@ impl Validate for MultipleOfFloatValidator {
 
     #[inline]
     fn is_valid_number(&self, _: &JSONSchema, _: &Value, instance_value: f64) -> bool {
-        let remainder = (instance_value / self.multiple_of) % 1.;
-        remainder < EPSILON && remainder < (1. - EPSILON)
+        is_decimal_multiple(
+            instance_value,
+            self.multiple_of_digits,
+            self.multiple_of_scale,
+        )
     }
     #[inline]
     fn is_valid_signed_integer(
@@ -59,7 +154,7 @@ impl Validate for MultipleOfFloatValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Some(instance_value) = instance.as_f64() {
             self.validate_number(schema, instance, instance_value)
         } else {
@@ -92,12 +187,7 @@ impl Validate for MultipleOfIntegerValidator {
 
     #[inline]
     fn is_valid_number(&self, _: &JSONSchema, _: &Value, instance_value: f64) -> bool {
-        if instance_value.fract() == 0. {
-            (instance_value % self.multiple_of) == 0.
-        } else {
-            let remainder = (instance_value / self.multiple_of) % 1.;
-            remainder < EPSILON && remainder < (1. - EPSILON)
-        }
+        is_integer_multiple(instance_value, self.multiple_of)
     }
     #[inline]
     fn is_valid_signed_integer(
@@ -129,7 +219,7 @@ impl Validate for MultipleOfIntegerValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Some(instance_value) = instance.as_f64() {
             self.validate_number(schema, instance, instance_value)
         } else {
@@ -160,3 +250,39 @@ pub(crate) fn compile(
         Some(Err(CompilationError::SchemaError))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{tests_util, JSONSchema};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(&json!({"multipleOf": 0.1}), &json!(0.3))]
+    #[test_case(&json!({"multipleOf": 0.1}), &json!(-0.7))]
+    #[test_case(&json!({"multipleOf": 0.01}), &json!(19.99))]
+    #[test_case(&json!({"multipleOf": 0.01}), &json!(1234567.89))]
+    #[test_case(&json!({"multipleOf": 0.01}), &json!(4.1))]
+    #[test_case(&json!({"multipleOf": 0.01}), &json!(10_000_000))]
+    #[test_case(&json!({"multipleOf": 0.0001}), &json!(1e20))]
+    #[test_case(&json!({"multipleOf": 1.5}), &json!(4.5))]
+    #[test_case(&json!({"multipleOf": 2}), &json!(4.0))]
+    // The sum is rounded to `1e15`
+    #[test_case(&json!({"multipleOf": 0.01}), &json!(1e15 + 0.01))]
+    fn is_valid(schema: &Value, instance: &Value) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        assert!(compiled.is_valid(instance))
+    }
+
+    #[test_case(&json!({"multipleOf": 0.1}), &json!(0.35))]
+    #[test_case(&json!({"multipleOf": 0.01}), &json!(19.999))]
+    #[test_case(&json!({"multipleOf": 0.01}), &json!(0.0001))]
+    #[test_case(&json!({"multipleOf": 0.01}), &json!(0.001))]
+    #[test_case(&json!({"multipleOf": 0.01}), &json!(1e-30))]
+    #[test_case(&json!({"multipleOf": 0.01}), &json!(123456789.001))]
+    #[test_case(&json!({"multipleOf": 0.3}), &json!(1))]
+    #[test_case(&json!({"multipleOf": 1.5}), &json!(4))]
+    #[test_case(&json!({"multipleOf": 2}), &json!(4.5))]
+    fn is_not_valid(schema: &Value, instance: &Value) {
+        tests_util::is_not_valid(schema, instance)
+    }
+}
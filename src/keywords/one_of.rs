@@ -15,8 +15,11 @@ impl OneOfValidator {
     pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         if let Value::Array(items) = schema {
             let mut schemas = Vec::with_capacity(items.len());
-            for item in items {
-                schemas.push(compile_validators(item, context)?)
+            for (idx, item) in items.iter().enumerate() {
+                schemas.push(compile_validators(
+                    item,
+                    &context.with_path(idx.to_string()),
+                )?)
             }
             Ok(Box::new(OneOfValidator { schemas }))
         } else {
@@ -58,7 +61,7 @@ macro_rules! one_of_impl_validate {
         paste::item! {
             #[inline]
             fn [<validate_ $method_suffix>]<'a>(
-                &self,
+                &'a self,
                 schema: &'a JSONSchema,
                 instance: &'a Value,
                 instance_value: $instance_type,
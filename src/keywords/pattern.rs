@@ -54,7 +54,7 @@ impl Validate for PatternValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::String(instance_value) = instance {
             self.validate_string(schema, instance, instance_value)
         } else {
@@ -69,7 +69,7 @@ impl ToString for PatternValidator {
 }
 
 // ECMA 262 has differences
-fn convert_regex(pattern: &str) -> Result<Regex, regex::Error> {
+pub(crate) fn convert_regex(pattern: &str) -> Result<Regex, regex::Error> {
     // replace control chars
     let new_pattern = CONTROL_GROUPS_RE.replace_all(pattern, replace_control_group);
     Regex::new(
@@ -19,7 +19,7 @@ impl PatternPropertiesValidator {
             for (pattern, subschema) in map {
                 patterns.push((
                     Regex::new(pattern)?,
-                    compile_validators(subschema, context)?,
+                    compile_validators(subschema, &context.with_path(pattern.as_str()))?,
                 ));
             }
             Ok(Box::new(PatternPropertiesValidator { patterns }))
@@ -59,30 +59,26 @@ impl Validate for PatternPropertiesValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         _: &'a Value,
         instance_value: &'a Map<String, Value>,
     ) -> ErrorIterator<'a> {
-        Box::new(
-            self.patterns
+        Box::new(self.patterns.iter().flat_map(move |(re, validators)| {
+            instance_value
                 .iter()
-                .flat_map(|(re, validators)| {
-                    instance_value
-                        .iter()
-                        .filter(move |(key, _)| re.is_match(key))
-                        .flat_map(move |(_key, value)| {
-                            validators
-                                .iter()
-                                .flat_map(move |validator| validator.validate(schema, value))
-                        })
+                .filter(move |(key, _)| re.is_match(key))
+                .flat_map(move |(key, value)| {
+                    validators.iter().flat_map(move |validator| {
+                        validator
+                            .validate(schema, value)
+                            .map(move |error| error.within(key))
+                    })
                 })
-                .collect::<Vec<_>>()
-                .into_iter(),
-        )
+        }))
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
             self.validate_object(schema, instance, instance_value)
         } else {
@@ -17,7 +17,10 @@ impl PropertiesValidator {
             Value::Object(map) => {
                 let mut properties = Vec::with_capacity(map.len());
                 for (key, subschema) in map {
-                    properties.push((key.clone(), compile_validators(subschema, context)?));
+                    properties.push((
+                        key.clone(),
+                        compile_validators(subschema, &context.with_path(key.as_str()))?,
+                    ));
                 }
                 Ok(Box::new(PropertiesValidator { properties }))
             }
@@ -53,30 +56,26 @@ impl Validate for PropertiesValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         _: &'a Value,
         instance_value: &'a Map<String, Value>,
     ) -> ErrorIterator<'a> {
-        Box::new(
-            self.properties
-                .iter()
-                .flat_map(move |(name, validators)| {
-                    instance_value
-                        .get(name)
-                        .into_iter()
-                        .flat_map(move |sub_value| {
-                            validators
-                                .iter()
-                                .flat_map(move |validator| validator.validate(schema, sub_value))
-                        })
+        Box::new(self.properties.iter().flat_map(move |(name, validators)| {
+            instance_value
+                .get(name)
+                .into_iter()
+                .flat_map(move |sub_value| {
+                    validators.iter().flat_map(move |validator| {
+                        validator
+                            .validate(schema, sub_value)
+                            .map(move |error| error.within(name))
+                    })
                 })
-                .collect::<Vec<_>>()
-                .into_iter(),
-        )
+        }))
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
             self.validate_object(schema, instance, instance_value)
         } else {
@@ -44,30 +44,31 @@ impl Validate for PropertyNamesObjectValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         _: &'a Value,
-        instance_value: &Map<String, Value>,
+        instance_value: &'a Map<String, Value>,
     ) -> ErrorIterator<'a> {
-        Box::new(
-            self.validators
-                .iter()
-                .flat_map(|validator| {
-                    instance_value.keys().flat_map(move |key| {
-                        let wrapper = Value::String(key.to_string());
-                        let errors: Vec<_> = validator
-                            .validate_string(schema, &wrapper, key)
-                            .map(ValidationError::into_owned)
-                            .collect();
-                        errors.into_iter()
-                    })
+        Box::new(self.validators.iter().flat_map(move |validator| {
+            instance_value
+                .keys()
+                .filter(move |key| {
+                    !validator.is_valid_string(schema, &Value::String((*key).to_string()), key)
+                })
+                .flat_map(move |key| {
+                    // Errors borrow the wrapper, therefore they are owned before it is dropped.
+                    // Only invalid keys get here, so valid ones are never collected
+                    let wrapper = Value::String(key.to_string());
+                    let errors: Vec<_> = validator
+                        .validate_string(schema, &wrapper, key)
+                        .map(ValidationError::into_owned)
+                        .collect();
+                    errors.into_iter()
                 })
-                .collect::<Vec<_>>()
-                .into_iter(),
-        )
+        }))
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
             self.validate_object(schema, instance, instance_value)
         } else {
@@ -0,0 +1,136 @@
+use crate::{
+    compilation::JSONSchema,
+    error::ValidationError,
+    keywords::{
+        helpers::{is_at_least, is_at_most},
+        CompilationResult,
+    },
+    validator::Validate,
+};
+use serde_json::{Number, Value};
+
+/// `minimum` and `maximum` of the same schema checked by a single validator.
+pub(crate) struct RangeValidator<L, H> {
+    minimum: L,
+    maximum: H,
+}
+
+macro_rules! validate {
+    ($minimum: ty, $maximum: ty) => {
+        impl Validate for RangeValidator<$minimum, $maximum> {
+            #[inline]
+            #[allow(trivial_numeric_casts)]
+            fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
+                let is_below_minimum = if let Some(instance_value) = instance.as_u64() {
+                    !is_at_least(instance_value, self.minimum)
+                } else if let Some(instance_value) = instance.as_i64() {
+                    !is_at_least(instance_value, self.minimum)
+                } else {
+                    let instance_value = instance.as_f64().unwrap_or(f64::NAN);
+                    !is_at_least(instance_value, self.minimum)
+                };
+                if is_below_minimum {
+                    ValidationError::minimum(instance, self.minimum as f64)
+                } else {
+                    ValidationError::maximum(instance, self.maximum as f64)
+                }
+            }
+
+            #[inline]
+            fn is_valid_number(&self, _: &JSONSchema, _: &Value, instance_value: f64) -> bool {
+                is_at_least(instance_value, self.minimum)
+                    && is_at_most(instance_value, self.maximum)
+            }
+            #[inline]
+            fn is_valid_signed_integer(
+                &self,
+                _: &JSONSchema,
+                _: &Value,
+                instance_value: i64,
+            ) -> bool {
+                is_at_least(instance_value, self.minimum)
+                    && is_at_most(instance_value, self.maximum)
+            }
+            #[inline]
+            fn is_valid_unsigned_integer(
+                &self,
+                _: &JSONSchema,
+                _: &Value,
+                instance_value: u64,
+            ) -> bool {
+                is_at_least(instance_value, self.minimum)
+                    && is_at_most(instance_value, self.maximum)
+            }
+            #[inline]
+            fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
+                if let Some(instance_value) = instance.as_u64() {
+                    self.is_valid_unsigned_integer(schema, instance, instance_value)
+                } else if let Some(instance_value) = instance.as_i64() {
+                    self.is_valid_signed_integer(schema, instance, instance_value)
+                } else if let Some(instance_value) = instance.as_f64() {
+                    self.is_valid_number(schema, instance, instance_value)
+                } else {
+                    true
+                }
+            }
+        }
+        impl ToString for RangeValidator<$minimum, $maximum> {
+            fn to_string(&self) -> String {
+                format!("minimum: {}, maximum: {}", self.minimum, self.maximum)
+            }
+        }
+    };
+}
+
+validate!(u64, u64);
+validate!(u64, i64);
+validate!(u64, f64);
+validate!(i64, u64);
+validate!(i64, i64);
+validate!(i64, f64);
+validate!(f64, u64);
+validate!(f64, i64);
+validate!(f64, f64);
+
+macro_rules! with_limit {
+    ($number: expr, |$limit: ident| $body: expr) => {
+        if let Some($limit) = $number.as_u64() {
+            $body
+        } else if let Some($limit) = $number.as_i64() {
+            $body
+        } else {
+            let $limit = $number.as_f64().expect("Always valid");
+            $body
+        }
+    };
+}
+
+#[inline]
+pub(crate) fn compile(minimum: &Number, maximum: &Number) -> CompilationResult {
+    with_limit!(minimum, |minimum| with_limit!(maximum, |maximum| Ok(
+        Box::new(RangeValidator { minimum, maximum })
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests_util;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(&json!({"minimum": 1, "maximum": 3}), &json!(1))]
+    #[test_case(&json!({"minimum": -1, "maximum": 2.5}), &json!(2.5))]
+    #[test_case(&json!({"minimum": 1u64 << 54, "maximum": 1u64 << 55}), &json!(1u64 << 54))]
+    #[test_case(&json!({"minimum": 1, "maximum": 3}), &json!("4"))]
+    fn is_valid(schema: &Value, instance: &Value) {
+        tests_util::is_valid(schema, instance)
+    }
+
+    #[test_case(&json!({"minimum": 1, "maximum": 3}), &json!(0.5))]
+    #[test_case(&json!({"minimum": -1, "maximum": 2.5}), &json!(3))]
+    #[test_case(&json!({"minimum": 1u64 << 54, "maximum": 1u64 << 55}), &json!((1u64 << 54) - 1))]
+    #[test_case(&json!({"minimum": -2, "maximum": -1}), &json!(-3))]
+    fn is_not_valid(schema: &Value, instance: &Value) {
+        tests_util::is_not_valid(schema, instance)
+    }
+}
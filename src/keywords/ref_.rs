@@ -1,50 +1,93 @@
 use crate::{
-    compilation::{compile_validators, context::CompilationContext, JSONSchema},
-    error::{error, ErrorIterator, ValidationError},
+    compilation::{
+        compile_validators,
+        context::{CompilationContext, SchemaPath},
+        JSONSchema,
+    },
+    error::{error, ErrorIterator, ReferenceContext, ValidationError},
     keywords::{CompilationResult, Validators},
     validator::Validate,
 };
-use parking_lot::RwLock;
+use once_cell::sync::OnceCell;
 use serde_json::{Map, Value};
 use std::borrow::Cow;
 use url::Url;
 
 pub(crate) struct RefValidator {
     reference: Url,
+    /// Reference as it is written in the schema
+    original_reference: String,
+    /// Scope that was used to build `reference`
+    base_uri: Url,
+    /// Location of the `$ref` keyword in the schema
+    schema_path: String,
     /// Precomputed validators.
-    /// They are behind a RwLock as is not possible to compute them
+    /// They are behind a `OnceCell` as it is not possible to compute them
     /// at compile time without risking infinite loops of references
     /// and at the same time during validation we iterate over shared
     /// references (&self) and not owned references (&mut self).
-    validators: RwLock<Option<Validators>>,
+    /// Once built, they live as long as `self`, hence errors are produced lazily.
+    validators: OnceCell<Validators>,
 }
 
 impl RefValidator {
     #[inline]
     pub(crate) fn compile(reference: &str, context: &CompilationContext) -> CompilationResult {
-        let reference = context.build_url(reference)?;
         Ok(Box::new(RefValidator {
-            reference,
-            validators: RwLock::new(None),
+            original_reference: reference.to_string(),
+            reference: context.build_url(reference)?,
+            base_uri: context.scope.clone().into_owned(),
+            schema_path: context.schema_pointer(),
+            validators: OnceCell::new(),
         }))
     }
 
-    /// Ensure that validators are built and built once.
-    #[inline]
-    fn ensure_validators<'a>(&self, schema: &'a JSONSchema) -> Result<(), ValidationError<'a>> {
-        if self.validators.read().is_none() {
-            let (scope, resolved) = schema.resolver.resolve_fragment(
-                schema.context.config.draft(),
-                &self.reference,
-                schema.schema,
-            )?;
-            let context = CompilationContext::new(scope, Cow::Borrowed(&schema.context.config));
-            let validators = compile_validators(&resolved, &context)?;
+    fn reference_context(&self) -> ReferenceContext {
+        ReferenceContext {
+            reference: self.original_reference.clone(),
+            url: self.reference.to_string(),
+            base_uri: self.base_uri.to_string(),
+            schema_path: self.schema_path.clone(),
+        }
+    }
 
-            // Inject the validators into self.validators
-            *self.validators.write() = Some(validators);
+    /// JSON Pointer chunks from the reference fragment, if it is a JSON Pointer.
+    fn fragment_path(&self) -> Vec<String> {
+        let fragment =
+            percent_encoding::percent_decode_str(self.reference.fragment().unwrap_or(""))
+                .decode_utf8_lossy();
+        if fragment.starts_with('/') {
+            fragment
+                .split('/')
+                .skip(1)
+                .map(|chunk| chunk.replace("~1", "/").replace("~0", "~"))
+                .collect()
+        } else {
+            vec![]
         }
-        Ok(())
+    }
+
+    /// Validators of the referenced schema, built once.
+    #[inline]
+    fn validators<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+    ) -> Result<&'a Validators, ValidationError<'a>> {
+        self.validators.get_or_try_init(|| {
+            let (scope, resolved) = schema
+                .resolver
+                .resolve_fragment(
+                    schema.context.config.draft(),
+                    &self.reference,
+                    schema.schema,
+                )
+                .map_err(|error| error.with_reference_context(self.reference_context()))?;
+            let mut context = CompilationContext::new(scope, Cow::Borrowed(&schema.context.config));
+            context.schema_path = SchemaPath::Root(self.fragment_path());
+            compile_validators(&resolved, &context).map_err(|error| {
+                ValidationError::from(error).with_reference_context(self.reference_context())
+            })
+        })
     }
 }
 
@@ -58,17 +101,11 @@ macro_rules! ref_impl_is_valid {
                 instance: &Value,
                 instance_value: $instance_type,
             ) -> bool {
-                if self.ensure_validators(schema).is_err() {
-                    false
-                } else {
-                    self.validators
-                        .read()
-                        .as_ref()
-                        .expect("ensure_validators guarantees the presence of the validators")
-                        .iter()
-                        .all(move |validator| {
-                            validator.[<is_valid_ $method_suffix>](schema, instance, instance_value)
-                        })
+                match self.validators(schema) {
+                    Ok(validators) => validators.iter().all(move |validator| {
+                        validator.[<is_valid_ $method_suffix>](schema, instance, instance_value)
+                    }),
+                    Err(_) => false,
                 }
             }
         }
@@ -79,26 +116,16 @@ macro_rules! ref_impl_validate {
         paste::item! {
             #[inline]
             fn [<validate_ $method_suffix>]<'a>(
-                &self,
+                &'a self,
                 schema: &'a JSONSchema,
                 instance: &'a Value,
                 instance_value: $instance_type,
             ) -> ErrorIterator<'a> {
-                if let Err(err) = self.ensure_validators(schema) {
-                    error(err)
-                } else {
-                    Box::new(
-                        self.validators
-                            .read()
-                            .as_ref()
-                            .expect("ensure_validators guarantees the presence of the validators")
-                            .iter()
-                            .flat_map(move |validator| {
-                                validator.[<validate_ $method_suffix>](schema, instance, instance_value)
-                            })
-                            .collect::<Vec<_>>()
-                            .into_iter(),
-                    )
+                match self.validators(schema) {
+                    Ok(validators) => Box::new(validators.iter().flat_map(move |validator| {
+                        validator.[<validate_ $method_suffix>](schema, instance, instance_value)
+                    })),
+                    Err(err) => error(err),
                 }
             }
         }
@@ -138,3 +165,79 @@ pub(crate) fn compile(
 ) -> Option<CompilationResult> {
     Some(RefValidator::compile(reference, context))
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::JSONSchema;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(&json!({"if": {"type": "integer"}, "then": {"$ref": "#/definitions/missing"}}), &json!(1), "/then/$ref"; "sibling keyword")]
+    #[test_case(&json!({"$ref": "#/definitions/a", "definitions": {"a": {"items": {"$ref": "#/definitions/missing"}}}}), &json!([1]), "/definitions/a/items/$ref"; "inside a reference")]
+    fn schema_path(schema: &Value, instance: &Value, expected: &str) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        let errors: Vec<_> = compiled.validate(instance).unwrap_err().collect();
+        let context = errors[0].reference_context().unwrap();
+        assert_eq!(context.schema_path, expected);
+    }
+
+    #[test]
+    fn missing_local_reference() {
+        let schema = json!({"properties": {"foo": {"$ref": "#/definitions/missing"}}});
+        let instance = json!({"foo": 1});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].is_schema_error());
+        let context = errors[0].reference_context().unwrap();
+        assert_eq!(context.reference, "#/definitions/missing");
+        assert_eq!(context.url, "json-schema:///#/definitions/missing");
+        assert_eq!(context.base_uri, "json-schema:///");
+        assert_eq!(context.schema_path, "/properties/foo/$ref");
+        assert_eq!(
+            errors[0].to_string(),
+            "Failed to resolve '#/definitions/missing' (url: json-schema:///#/definitions/missing, \
+             base URI: json-schema:///, schema path: '/properties/foo/$ref'): \
+             Invalid reference: json-schema:///#/definitions/missing"
+        );
+    }
+
+    #[test]
+    fn unavailable_remote_reference() {
+        let mut server = mockito::Server::new();
+        let _missing = server
+            .mock("GET", "/missing.json")
+            .with_status(404)
+            .create();
+        let schema = json!({
+            "$id": format!("{}/root.json", server.url()),
+            "items": [{"$ref": "missing.json#/definitions/foo"}]
+        });
+        let instance = json!([1]);
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].is_schema_error());
+        let context = errors[0].reference_context().unwrap();
+        assert_eq!(context.reference, "missing.json#/definitions/foo");
+        assert_eq!(
+            context.url,
+            format!("{}/missing.json#/definitions/foo", server.url())
+        );
+        assert_eq!(context.base_uri, format!("{}/root.json", server.url()));
+        assert_eq!(context.schema_path, "/items/0/$ref");
+        assert!(errors[0].to_string().contains("404"));
+    }
+
+    #[test]
+    fn instance_errors_are_not_schema_errors() {
+        let schema =
+            json!({"definitions": {"foo": {"type": "string"}}, "$ref": "#/definitions/foo"});
+        let instance = json!(1);
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(errors.len(), 1);
+        assert!(!errors[0].is_schema_error());
+        assert!(errors[0].reference_context().is_none());
+    }
+}
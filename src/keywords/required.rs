@@ -52,7 +52,7 @@ impl Validate for RequiredValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         _: &'a JSONSchema,
         instance: &'a Value,
         instance_value: &'a Map<String, Value>,
@@ -73,7 +73,7 @@ impl Validate for RequiredValidator {
             .unwrap_or_else(no_error)
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
             self.validate_object(schema, instance, instance_value)
         } else {
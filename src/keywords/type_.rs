@@ -1,7 +1,10 @@
 use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
-    keywords::CompilationResult,
+    keywords::{
+        helpers::{is_integer_of, is_number_of},
+        CompilationResult,
+    },
     primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
     validator::Validate,
 };
@@ -52,8 +55,7 @@ impl Validate for MultipleTypesValidator {
     }
     #[inline]
     fn is_valid_number(&self, _: &JSONSchema, _: &Value, instance_value: f64) -> bool {
-        self.types.contains_type(PrimitiveType::Number)
-            || (self.types.contains_type(PrimitiveType::Integer) && instance_value.fract() == 0.)
+        is_number_of(self.types, instance_value, false)
     }
     #[inline]
     fn is_valid_object(&self, _: &JSONSchema, _: &Value, _: &Map<String, Value>) -> bool {
@@ -61,7 +63,7 @@ impl Validate for MultipleTypesValidator {
     }
     #[inline]
     fn is_valid_signed_integer(&self, _: &JSONSchema, _: &Value, _: i64) -> bool {
-        self.types.contains_type(PrimitiveType::Integer)
+        is_integer_of(self.types)
     }
     #[inline]
     fn is_valid_string(&self, _: &JSONSchema, _: &Value, _: &str) -> bool {
@@ -69,7 +71,7 @@ impl Validate for MultipleTypesValidator {
     }
     #[inline]
     fn is_valid_unsigned_integer(&self, _: &JSONSchema, _: &Value, _: u64) -> bool {
-        self.types.contains_type(PrimitiveType::Integer)
+        is_integer_of(self.types)
     }
 }
 impl ToString for MultipleTypesValidator {
@@ -138,7 +140,7 @@ impl Validate for NullTypeValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Null = instance {
             self.validate_null(schema, instance, ())
         } else {
@@ -205,7 +207,7 @@ impl Validate for BooleanTypeValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Bool(instance_value) = instance {
             self.validate_boolean(schema, instance, *instance_value)
         } else {
@@ -272,7 +274,7 @@ impl Validate for StringTypeValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::String(instance_value) = instance {
             self.validate_string(schema, instance, instance_value)
         } else {
@@ -339,7 +341,7 @@ impl Validate for ArrayTypeValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Array(instance_value) = instance {
             self.validate_array(schema, instance, instance_value)
         } else {
@@ -406,7 +408,7 @@ impl Validate for ObjectTypeValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
             self.validate_object(schema, instance, instance_value)
         } else {
@@ -465,7 +467,7 @@ impl Validate for NumberTypeValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Number(_) = instance {
             no_error()
         } else {
@@ -530,7 +532,7 @@ impl Validate for IntegerTypeValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Number(_) = instance {
             if self.is_valid(schema, instance) {
                 no_error()
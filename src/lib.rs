@@ -6,6 +6,18 @@
 //! Supports:
 //!   - JSON Schema drafts 6, 7 (all test cases);
 //!   - Loading remote documents via HTTP(S);
+//!   - Loading remote documents without blocking inside async runtimes (`async` feature);
+//!   - Validating large JSON Lines files and arrays in parallel without loading them at once;
+//!   - Source locations of invalid values in JSON text;
+//!   - YAML schemas and instances with error locations (`yaml` feature);
+//!   - Generating valid and invalid instances from a schema;
+//!   - `proptest` strategies built from a schema (`proptest` feature);
+//!   - Checking whether a new version of a schema is backwards compatible;
+//!   - Converting schemas between drafts 4, 6, 7 and 2019-09;
+//!   - Bundling a schema and all referenced documents into a single document;
+//!   - Expanding references for tools that can't follow `$ref`;
+//!   - Linting schemas against configurable conventions;
+//!   - Warnings about subschemas that no instance can satisfy;
 //!
 //! ## Usage Examples:
 //! A schema can be compiled with two main flavours:
@@ -46,7 +58,8 @@
 //!     let result = compiled.validate(&instance);
 //!     if let Err(errors) = result {
 //!         for error in errors {
-//!             println!("Validation error: {}", error)
+//!             println!("Validation error: {}", error);
+//!             println!("Instance path: {}", error.instance_path());
 //!         }
 //!     }
 //!     Ok(())
@@ -74,19 +87,54 @@
     variant_size_differences
 )]
 #![cfg_attr(not(test), allow(clippy::integer_arithmetic, clippy::unwrap_used))]
+mod analysis;
+mod bundle;
+mod cache;
+mod coercion;
+mod compatibility;
 mod compilation;
 mod content_encoding;
 mod content_media_type;
+mod conversion;
+mod defaults;
+mod dereference;
 mod error;
+mod generator;
+mod instance;
 mod keywords;
+mod lint;
+mod paths;
 mod primitive_type;
 mod resolver;
+mod sanitize;
 mod schemas;
+mod ser;
+mod single_pass;
+mod source;
+#[cfg(feature = "proptest")]
+mod strategy;
+mod stream;
+mod subschemas;
 mod validator;
+#[cfg(feature = "yaml")]
+mod yaml;
+pub use analysis::CompilationWarning;
+pub use cache::DocumentCache;
+pub use compatibility::{ChangeKind, SchemaChange};
 pub use compilation::{options::CompilationOptions, JSONSchema};
-pub use error::{CompilationError, ErrorIterator, ValidationError};
+pub use conversion::{convert, Conversion, ConversionIssue, SchemaVersion};
+pub use dereference::Dereferenced;
+pub use error::{CompilationError, ErrorIterator, ReferenceContext, ValidationError};
+pub use generator::{GenerationError, Generator};
+pub use instance::JsonLike;
+pub use lint::{Lint, LintRule, Linter, Severity};
+pub use paths::{JSONPointer, PathChunk};
 pub use schemas::Draft;
 use serde_json::Value;
+pub use source::{JSONDocument, Location, Span};
+pub use stream::{RecordResult, RecordStream, StreamError};
+#[cfg(feature = "yaml")]
+pub use yaml::YamlDocument;
 
 /// A shortcut for validating `instance` against `schema`. Draft version is detected automatically.
 /// ```rust
@@ -124,6 +172,16 @@ pub(crate) mod tests_util {
             instance
         );
     }
+
+    pub(crate) fn is_valid(schema: &Value, instance: &Value) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        assert!(compiled.is_valid(instance), "{} should be valid", instance);
+        assert!(
+            compiled.validate(instance).is_ok(),
+            "{} should be valid",
+            instance
+        );
+    }
 }
 
 #[cfg(test)]
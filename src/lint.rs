@@ -0,0 +1,590 @@
+//! Checking schemas against conventions that go beyond metaschema validity.
+use crate::{
+    compilation::DEFAULT_ROOT_URL,
+    keywords::pattern::convert_regex,
+    paths::{JSONPointer, PathChunk},
+    schemas::{draft_from_schema, id_of, subschemas, Draft},
+};
+use serde_json::{Map, Value};
+use std::{collections::HashMap, fmt};
+use url::Url;
+
+/// A convention that schemas should follow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintRule {
+    /// Object schemas (with `"type": "object"` or `properties`) without `additionalProperties`.
+    MissingAdditionalProperties,
+    /// Subschemas in `properties` without `description`.
+    MissingDescription,
+    /// Lower limits that are greater than upper ones, e.g. `minimum` and `maximum`,
+    /// hence no value satisfies both.
+    ContradictoryLimits,
+    /// `pattern` that does not start with `^` and end with `$`, i.e. matches substrings.
+    UnanchoredPattern,
+    /// `required` names that are neither in `properties` nor match `patternProperties`.
+    UndefinedRequired,
+    /// `definitions` that are not reachable via `$ref` from the root schema.
+    UnreachableDefinition,
+    /// Keywords that are not defined by the draft.
+    UnknownKeyword,
+}
+
+const RULES: [LintRule; 7] = [
+    LintRule::MissingAdditionalProperties,
+    LintRule::MissingDescription,
+    LintRule::ContradictoryLimits,
+    LintRule::UnanchoredPattern,
+    LintRule::UndefinedRequired,
+    LintRule::UnreachableDefinition,
+    LintRule::UnknownKeyword,
+];
+
+impl LintRule {
+    /// The name of the rule in kebab case, e.g. `missing-description`.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            LintRule::MissingAdditionalProperties => "missing-additional-properties",
+            LintRule::MissingDescription => "missing-description",
+            LintRule::ContradictoryLimits => "contradictory-limits",
+            LintRule::UnanchoredPattern => "unanchored-pattern",
+            LintRule::UndefinedRequired => "undefined-required",
+            LintRule::UnreachableDefinition => "unreachable-definition",
+            LintRule::UnknownKeyword => "unknown-keyword",
+        }
+    }
+
+    fn default_severity(self) -> Severity {
+        match self {
+            LintRule::ContradictoryLimits | LintRule::UndefinedRequired => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+/// How serious a rule violation is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The schema works, but does not follow a convention.
+    Warning,
+    /// The schema likely does not work as intended.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => f.write_str("warning"),
+            Severity::Error => f.write_str("error"),
+        }
+    }
+}
+
+/// A rule violation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lint {
+    /// The violated rule.
+    pub rule: LintRule,
+    /// Severity configured for the rule.
+    pub severity: Severity,
+    /// Location of the violation in the schema.
+    pub path: JSONPointer,
+    /// Human-readable description of the violation.
+    pub message: String,
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}] {}: {}",
+            self.severity,
+            self.rule.name(),
+            self.path,
+            self.message
+        )
+    }
+}
+
+/// Checks schemas against a configurable set of rules.
+///
+/// All rules are enabled by default. `ContradictoryLimits` and `UndefinedRequired` are errors,
+/// the other rules are warnings.
+///
+/// ```rust
+/// use jsonschema::{LintRule, Linter, Severity};
+/// use serde_json::json;
+///
+/// let schema = json!({
+///     "type": "object",
+///     "properties": {"name": {"pattern": "[a-z]+"}},
+///     "additionalProperties": false
+/// });
+/// let lints = Linter::default()
+///     .with_severity(LintRule::UnanchoredPattern, Severity::Error)
+///     .without_rule(LintRule::MissingDescription)
+///     .lint(&schema);
+/// assert_eq!(lints.len(), 1);
+/// assert_eq!(
+///     lints[0].to_string(),
+///     "error[unanchored-pattern] /properties/name/pattern: `[a-z]+` is not anchored with `^` and `$`"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Linter {
+    draft: Option<Draft>,
+    rules: HashMap<LintRule, Severity>,
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Linter {
+            draft: None,
+            rules: RULES
+                .iter()
+                .map(|rule| (*rule, rule.default_severity()))
+                .collect(),
+        }
+    }
+}
+
+impl Linter {
+    /// Interpret schemas according to the given draft. By default, the draft is detected from
+    /// `$schema` with a fallback to Draft 7.
+    #[inline]
+    pub fn with_draft(&mut self, draft: Draft) -> &mut Self {
+        self.draft = Some(draft);
+        self
+    }
+
+    /// Enable the rule with the given severity.
+    #[inline]
+    pub fn with_severity(&mut self, rule: LintRule, severity: Severity) -> &mut Self {
+        self.rules.insert(rule, severity);
+        self
+    }
+
+    /// Disable the rule.
+    #[inline]
+    pub fn without_rule(&mut self, rule: LintRule) -> &mut Self {
+        self.rules.remove(&rule);
+        self
+    }
+
+    /// Check the schema and return rule violations in the order of their locations.
+    #[must_use]
+    pub fn lint(&self, schema: &Value) -> Vec<Lint> {
+        let draft = self
+            .draft
+            .or_else(|| draft_from_schema(schema))
+            .unwrap_or_default();
+        let scope = match id_of(draft, schema).and_then(|id| Url::parse(id).ok()) {
+            Some(scope) => scope,
+            None => Url::parse(DEFAULT_ROOT_URL).expect("Is a valid URL"),
+        };
+        let mut nodes = vec![];
+        collect(
+            draft,
+            schema,
+            &Location {
+                path: vec![],
+                scope: scope.clone(),
+                resource: scope,
+                relative: vec![],
+                definition: None,
+            },
+            false,
+            &mut nodes,
+        );
+        let mut context = Context {
+            rules: &self.rules,
+            lints: vec![],
+        };
+        for node in &nodes {
+            check(draft, node, &mut context);
+        }
+        if self.rules.contains_key(&LintRule::UnreachableDefinition) {
+            unreachable_definitions(&nodes, &mut context);
+        }
+        let mut lints = context.lints;
+        lints.sort_by(|left, right| left.path.cmp(&right.path));
+        lints
+    }
+}
+
+/// Where a subschema is.
+#[derive(Clone)]
+struct Location {
+    /// Location in the schema document.
+    path: Vec<PathChunk>,
+    /// Base URL for references.
+    scope: Url,
+    /// The fragment-less base URL, i.e. the resource the subschema belongs to.
+    resource: Url,
+    /// Location within the resource.
+    relative: Vec<PathChunk>,
+    /// Index of the innermost definition that contains the subschema.
+    definition: Option<usize>,
+}
+
+impl Location {
+    fn child(&self, chunks: &[PathChunk]) -> Location {
+        let mut location = self.clone();
+        location.path.extend_from_slice(chunks);
+        location.relative.extend_from_slice(chunks);
+        location
+    }
+}
+
+struct Node<'s> {
+    schema: &'s Map<String, Value>,
+    location: Location,
+    /// Whether the subschema is directly in `definitions`.
+    is_definition: bool,
+}
+
+/// Collect all subschemas of `schema`, skipping values that are instances, e.g. `enum`.
+fn collect<'s>(
+    draft: Draft,
+    schema: &'s Value,
+    location: &Location,
+    is_definition: bool,
+    nodes: &mut Vec<Node<'s>>,
+) {
+    let object = match schema {
+        Value::Object(object) => object,
+        _ => return,
+    };
+    let mut location = location.clone();
+    if let Some(id) = id_of(draft, schema) {
+        if let Ok(scope) = Url::options().base_url(Some(&location.scope)).parse(id) {
+            let mut resource = scope.clone();
+            resource.set_fragment(None);
+            if resource != location.resource {
+                location.resource = resource;
+                location.relative = vec![];
+            }
+            location.scope = scope;
+        }
+    }
+    if is_definition {
+        location.definition = Some(nodes.len());
+    }
+    nodes.push(Node {
+        schema: object,
+        location: location.clone(),
+        is_definition,
+    });
+    for (keyword, position, subschema) in subschemas(object) {
+        let child = location.child(&position.path(keyword));
+        collect(draft, subschema, &child, keyword == "definitions", nodes);
+    }
+}
+
+struct Context<'r> {
+    rules: &'r HashMap<LintRule, Severity>,
+    lints: Vec<Lint>,
+}
+
+impl Context<'_> {
+    fn report(
+        &mut self,
+        rule: LintRule,
+        path: &[PathChunk],
+        chunks: &[PathChunk],
+        message: String,
+    ) {
+        if let Some(severity) = self.rules.get(&rule) {
+            let mut path = path.to_vec();
+            path.extend_from_slice(chunks);
+            self.lints.push(Lint {
+                rule,
+                severity: *severity,
+                path: JSONPointer::from(path),
+                message,
+            })
+        }
+    }
+}
+
+/// Keywords without a validator that are still defined by the draft.
+fn is_annotation(draft: Draft, keyword: &str) -> bool {
+    match keyword {
+        "$schema" | "$ref" | "definitions" | "title" | "description" | "default" => true,
+        "id" | "exclusiveMinimum" | "exclusiveMaximum" => draft == Draft::Draft4,
+        "$id" | "examples" => draft != Draft::Draft4,
+        "$comment" | "readOnly" | "writeOnly" | "then" | "else" => draft == Draft::Draft7,
+        _ => false,
+    }
+}
+
+fn check(draft: Draft, node: &Node<'_>, context: &mut Context<'_>) {
+    let schema = node.schema;
+    let path = &node.location.path;
+    let property = |keyword: &str| PathChunk::Property(keyword.to_string());
+    let is_object = match schema.get("type") {
+        Some(Value::String(name)) => name == "object",
+        Some(Value::Array(names)) => names.contains(&Value::String("object".to_string())),
+        _ => schema.contains_key("properties"),
+    };
+    // `$ref` siblings are ignored
+    if is_object && !schema.contains_key("additionalProperties") && !schema.contains_key("$ref") {
+        context.report(
+            LintRule::MissingAdditionalProperties,
+            path,
+            &[],
+            "`additionalProperties` is not specified".to_string(),
+        )
+    }
+    if let Some(Value::Object(properties)) = schema.get("properties") {
+        for (name, subschema) in properties {
+            // The description may be next to the referenced schema
+            if let Value::Object(subschema) = subschema {
+                if !subschema.contains_key("description") && !subschema.contains_key("$ref") {
+                    context.report(
+                        LintRule::MissingDescription,
+                        path,
+                        &[property("properties"), PathChunk::Property(name.clone())],
+                        format!("Property `{}` has no description", name),
+                    )
+                }
+            }
+        }
+    }
+    check_limits(draft, schema, path, context);
+    if let Some(Value::String(pattern)) = schema.get("pattern") {
+        if !is_anchored(pattern) {
+            context.report(
+                LintRule::UnanchoredPattern,
+                path,
+                &[property("pattern")],
+                format!("`{}` is not anchored with `^` and `$`", pattern),
+            )
+        }
+    }
+    if let Some(Value::Array(required)) = schema.get("required") {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let patterns: Vec<_> = schema
+            .get("patternProperties")
+            .and_then(Value::as_object)
+            .map(|patterns| {
+                patterns
+                    .keys()
+                    .filter_map(|pattern| convert_regex(pattern).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        // Other properties may come from `allOf` and similar keywords
+        if properties.is_some() || !patterns.is_empty() {
+            for (index, name) in required.iter().enumerate() {
+                if let Value::String(name) = name {
+                    let is_defined = matches!(properties, Some(properties) if properties.contains_key(name))
+                        || patterns.iter().any(|pattern| pattern.is_match(name));
+                    if !is_defined {
+                        context.report(
+                            LintRule::UndefinedRequired,
+                            path,
+                            &[property("required"), PathChunk::Index(index)],
+                            format!("Required property `{}` is not defined", name),
+                        )
+                    }
+                }
+            }
+        }
+    }
+    for keyword in schema.keys() {
+        if draft.get_validator(keyword).is_none() && !is_annotation(draft, keyword) {
+            context.report(
+                LintRule::UnknownKeyword,
+                path,
+                &[property(keyword)],
+                format!("`{}` is not a keyword", keyword),
+            )
+        }
+    }
+}
+
+/// Whether `pattern` starts with `^` and ends with an unescaped `$`.
+/// The `$` is escaped if an odd number of backslashes precedes it.
+fn is_anchored(pattern: &str) -> bool {
+    pattern.starts_with('^')
+        && pattern.ends_with('$')
+        && pattern[..pattern.len() - 1]
+            .chars()
+            .rev()
+            .take_while(|&c| c == '\\')
+            .count()
+            % 2
+            == 0
+}
+
+fn check_limits(
+    draft: Draft,
+    schema: &Map<String, Value>,
+    path: &[PathChunk],
+    context: &mut Context<'_>,
+) {
+    let number = |keyword: &str| schema.get(keyword).and_then(Value::as_f64);
+    let flag = |keyword: &str| schema.get(keyword) == Some(&Value::Bool(true));
+    let mut pairs = vec![
+        ("minLength", "maxLength", false),
+        ("minItems", "maxItems", false),
+        ("minProperties", "maxProperties", false),
+    ];
+    if draft == Draft::Draft4 {
+        let is_exclusive = flag("exclusiveMinimum") || flag("exclusiveMaximum");
+        pairs.push(("minimum", "maximum", is_exclusive));
+    } else {
+        pairs.extend_from_slice(&[
+            ("minimum", "maximum", false),
+            ("exclusiveMinimum", "maximum", true),
+            ("minimum", "exclusiveMaximum", true),
+            ("exclusiveMinimum", "exclusiveMaximum", true),
+        ]);
+    }
+    for (lower, upper, is_exclusive) in pairs {
+        if let (Some(low), Some(high)) = (number(lower), number(upper)) {
+            if low > high || (is_exclusive && low == high) {
+                context.report(
+                    LintRule::ContradictoryLimits,
+                    path,
+                    &[PathChunk::Property(lower.to_string())],
+                    format!(
+                        "`{}` ({}) excludes all values allowed by `{}` ({})",
+                        lower, schema[lower], upper, schema[upper]
+                    ),
+                )
+            }
+        }
+    }
+}
+
+/// Report definitions that can't be reached from the root schema via `$ref`.
+fn unreachable_definitions(nodes: &[Node<'_>], context: &mut Context<'_>) {
+    // References and the definitions they are in
+    let mut references = vec![];
+    for node in nodes {
+        if let Some(Value::String(reference)) = node.schema.get("$ref") {
+            if let Ok(url) = Url::options()
+                .base_url(Some(&node.location.scope))
+                .parse(reference)
+            {
+                references.push((node.location.definition, url));
+            }
+        }
+    }
+    let mut reached = vec![false; nodes.len()];
+    let mut pending: Vec<&Url> = references
+        .iter()
+        .filter(|(definition, _)| definition.is_none())
+        .map(|(_, url)| url)
+        .collect();
+    while let Some(url) = pending.pop() {
+        let mut resource = url.clone();
+        resource.set_fragment(None);
+        let fragment = percent_encoding::percent_decode_str(url.fragment().unwrap_or(""))
+            .decode_utf8_lossy()
+            .into_owned();
+        for (index, node) in nodes.iter().enumerate() {
+            if !node.is_definition || reached[index] {
+                continue;
+            }
+            let location = &node.location;
+            let pointer = JSONPointer::from(location.relative.clone()).to_string();
+            let is_target = location.scope == *url
+                || (location.resource == resource
+                    && (fragment == pointer || fragment.starts_with(&format!("{}/", pointer))));
+            if is_target {
+                reached[index] = true;
+                pending.extend(
+                    references
+                        .iter()
+                        .filter(|(definition, _)| *definition == Some(index))
+                        .map(|(_, url)| url),
+                );
+            }
+        }
+    }
+    for (index, node) in nodes.iter().enumerate() {
+        // Nested definitions are reported only if their parent definition is reachable
+        let is_parent_reached = match parent_definition(nodes, index) {
+            Some(parent) => reached[parent],
+            None => true,
+        };
+        if node.is_definition && !reached[index] && is_parent_reached {
+            context.report(
+                LintRule::UnreachableDefinition,
+                &node.location.path,
+                &[],
+                "The definition is not referenced".to_string(),
+            )
+        }
+    }
+}
+
+/// The innermost definition that contains the definition at `index`.
+fn parent_definition(nodes: &[Node<'_>], index: usize) -> Option<usize> {
+    let path = &nodes[index].location.path;
+    nodes[..index]
+        .iter()
+        .enumerate()
+        .rev()
+        .find(|(_, node)| {
+            node.is_definition
+                && node.location.path.len() < path.len()
+                && path.starts_with(&node.location.path)
+        })
+        .map(|(parent, _)| parent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LintRule, Linter, Severity};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(&json!({"type": "object", "properties": {"a": {"description": "A"}}}), LintRule::MissingAdditionalProperties, &[""]; "missing additional properties")]
+    #[test_case(&json!({"properties": {"a": {"description": "A"}}, "additionalProperties": false}), LintRule::MissingAdditionalProperties, &[]; "additional properties")]
+    #[test_case(&json!({"properties": {"a": {}, "b": {"description": "B"}, "c": {"$ref": "#/properties/b"}}}), LintRule::MissingDescription, &["/properties/a"]; "missing description")]
+    #[test_case(&json!({"minimum": 5, "maximum": 3, "minLength": 1, "maxLength": 1}), LintRule::ContradictoryLimits, &["/minimum"]; "minimum greater than maximum")]
+    #[test_case(&json!({"exclusiveMinimum": 3, "maximum": 3}), LintRule::ContradictoryLimits, &["/exclusiveMinimum"]; "exclusive limits")]
+    #[test_case(&json!({"$schema": "http://json-schema.org/draft-04/schema#", "minimum": 3, "maximum": 3, "exclusiveMaximum": true}), LintRule::ContradictoryLimits, &["/minimum"]; "draft 4 exclusive limits")]
+    #[test_case(&json!({"items": [{"pattern": "^a$"}, {"pattern": "a"}, {"pattern": "^a\\$"}, {"pattern": "^a\\\\$"}, {"pattern": "^a\\\\\\$"}]}), LintRule::UnanchoredPattern, &["/items/1/pattern", "/items/2/pattern", "/items/4/pattern"]; "unanchored pattern")]
+    #[test_case(&json!({"properties": {"a": {}}, "patternProperties": {"^x-": {}}, "required": ["a", "x-b", "c"]}), LintRule::UndefinedRequired, &["/required/2"]; "undefined required")]
+    #[test_case(&json!({"allOf": [{"properties": {"a": {}}}, {"required": ["a"]}]}), LintRule::UndefinedRequired, &[]; "required without properties")]
+    #[test_case(&json!({"items": [{"pattern": "a"}, {"pattern": "a"}, {"pattern": "a"}, {"pattern": "a"}, {"pattern": "a"}, {"pattern": "a"}, {"pattern": "a"}, {"pattern": "a"}, {"pattern": "a"}, {"pattern": "a"}, {"pattern": "a"}]}), LintRule::UnanchoredPattern, &["/items/0/pattern", "/items/1/pattern", "/items/2/pattern", "/items/3/pattern", "/items/4/pattern", "/items/5/pattern", "/items/6/pattern", "/items/7/pattern", "/items/8/pattern", "/items/9/pattern", "/items/10/pattern"]; "sorted by indices")]
+    #[test_case(&json!({"patternProperties": {"^\\d$": {}}, "required": ["1", "\u{661}"]}), LintRule::UndefinedRequired, &["/required/1"]; "ecma 262 patterns")]
+    #[test_case(&json!({"$ref": "#/definitions/a", "definitions": {"a": {"items": {"$ref": "#/definitions/b"}}, "b": {}, "c": {"$ref": "#/definitions/d"}, "d": {}}}), LintRule::UnreachableDefinition, &["/definitions/c", "/definitions/d"]; "unreachable definitions")]
+    #[test_case(&json!({"properties": {"a": {"$ref": "#foo"}, "b": {"$ref": "item.json#/definitions/x"}}, "definitions": {"a": {"$id": "#foo"}, "b": {"$id": "item.json", "definitions": {"x": {}, "y": {}}}}}), LintRule::UnreachableDefinition, &["/definitions/b/definitions/y"]; "identifiers")]
+    #[test_case(&json!({"typo": 1, "enum": [{"typo": 1}], "properties": {"typo": {"$comment": "", "then": {}}}}), LintRule::UnknownKeyword, &["/typo"]; "unknown keywords")]
+    #[test_case(&json!({"$schema": "http://json-schema.org/draft-04/schema#", "$comment": "", "id": "#a", "exclusiveMinimum": true, "minimum": 1}), LintRule::UnknownKeyword, &["/$comment"]; "draft 4 keywords")]
+    fn lint(schema: &Value, rule: LintRule, expected: &[&str]) {
+        let paths: Vec<_> = Linter::default()
+            .lint(schema)
+            .iter()
+            .filter(|lint| lint.rule == rule)
+            .map(|lint| lint.path.to_string())
+            .collect();
+        assert_eq!(paths, expected);
+    }
+
+    #[test]
+    fn configuration() {
+        let schema = json!({"properties": {"a": {"pattern": "a"}}});
+        let lints = Linter::default()
+            .without_rule(LintRule::MissingAdditionalProperties)
+            .with_severity(LintRule::MissingDescription, Severity::Error)
+            .lint(&schema);
+        let rules: Vec<_> = lints
+            .iter()
+            .map(|lint| (lint.rule, lint.severity))
+            .collect();
+        assert_eq!(
+            rules,
+            vec![
+                (LintRule::MissingDescription, Severity::Error),
+                (LintRule::UnanchoredPattern, Severity::Warning)
+            ]
+        );
+    }
+}
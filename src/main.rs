@@ -0,0 +1,204 @@
+#[cfg(feature = "yaml")]
+use jsonschema::YamlDocument;
+use jsonschema::{Draft, JSONDocument, JSONSchema, Location, StreamError, ValidationError};
+use serde_json::Value;
+use std::{
+    error::Error,
+    ffi::OsStr,
+    fs::{self, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+    process,
+};
+use structopt::StructOpt;
+
+type BoxedError = Box<dyn Error>;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "jsonschema",
+    about = "Validate JSON documents against a JSON Schema"
+)]
+struct Cli {
+    /// A path to a JSON Schema.
+    #[structopt(parse(from_os_str))]
+    schema: PathBuf,
+
+    /// A path to a JSON instance to validate. Files with `.jsonl` or `.ndjson` extension are
+    /// validated line by line, `.yaml` and `.yml` files are parsed as YAML if the `yaml`
+    /// feature is enabled. May be repeated.
+    #[structopt(short = "i", long = "instance", parse(from_os_str))]
+    instances: Vec<PathBuf>,
+
+    /// JSON Schema draft to use instead of the one declared via `$schema`.
+    #[structopt(short = "d", long = "draft", parse(try_from_str = parse_draft))]
+    #[structopt(possible_values = &["4", "6", "7"])]
+    draft: Option<Draft>,
+
+    /// Validate every instance file as JSON Lines regardless of its extension.
+    #[structopt(long = "jsonl")]
+    jsonl: bool,
+}
+
+fn parse_draft(value: &str) -> Result<Draft, String> {
+    match value {
+        "4" => Ok(Draft::Draft4),
+        "6" => Ok(Draft::Draft6),
+        "7" => Ok(Draft::Draft7),
+        _ => Err(format!("Unsupported draft: {}", value)),
+    }
+}
+
+#[cfg(feature = "yaml")]
+fn read_document(path: &Path) -> Result<Value, BoxedError> {
+    let content = fs::read_to_string(path)?;
+    if is_yaml(path) {
+        Ok(serde_yaml::from_str(&content)?)
+    } else {
+        Ok(serde_json::from_str(&content)?)
+    }
+}
+
+#[cfg(not(feature = "yaml"))]
+fn read_document(path: &Path) -> Result<Value, BoxedError> {
+    let content = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn is_json_lines(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(OsStr::to_str),
+        Some("jsonl") | Some("ndjson")
+    )
+}
+
+#[cfg(feature = "yaml")]
+fn is_yaml(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(OsStr::to_str),
+        Some("yaml") | Some("yml")
+    )
+}
+
+/// Print the validation outcome for a document. Returns `true` if the document is valid.
+/// `locate` gives the location of an invalid value in the file, if known.
+fn report<'a>(
+    label: &str,
+    errors: impl IntoIterator<Item = ValidationError<'a>>,
+    locate: impl Fn(&ValidationError<'a>) -> Option<Location>,
+) -> bool {
+    let mut errors = errors.into_iter().peekable();
+    if errors.peek().is_none() {
+        println!("{} - VALID", label);
+        return true;
+    }
+    println!("{} - INVALID. Errors:", label);
+    for error in errors {
+        match locate(&error) {
+            Some(location) => {
+                println!("  at '{}' ({}): {}", error.instance_path(), location, error)
+            }
+            None => println!("  at '{}': {}", error.instance_path(), error),
+        }
+    }
+    false
+}
+
+/// Validate a YAML document and report errors together with their locations.
+#[cfg(feature = "yaml")]
+fn validate_yaml(compiled: &JSONSchema, path: &Path) -> bool {
+    let name = path.display().to_string();
+    let document = match fs::read_to_string(path)
+        .map_err(BoxedError::from)
+        .and_then(|content| Ok(YamlDocument::parse(&content)?))
+    {
+        Ok(document) => document,
+        Err(error) => {
+            eprintln!("{} - ERROR: {}", name, error);
+            return false;
+        }
+    };
+    let locate = |error: &ValidationError| document.location(&error.instance_path());
+    let errors = compiled.validate(document.value()).err();
+    report(&name, errors.into_iter().flatten(), locate)
+}
+
+/// Validate every document in the given file. Returns `true` if all of them are valid.
+fn validate_file(compiled: &JSONSchema, path: &Path, json_lines: bool) -> bool {
+    let name = path.display();
+    if json_lines {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(error) => {
+                eprintln!("{} - ERROR: {}", name, error);
+                return false;
+            }
+        };
+        let mut success = true;
+        for result in compiled.validate_lines(BufReader::new(file)) {
+            success &= match result {
+                Ok(record) => report(
+                    &format!("{}:{}", name, record.index + 1),
+                    record.errors,
+                    |_| None,
+                ),
+                Err(StreamError::Parse { index, error }) => {
+                    eprintln!("{}:{} - ERROR: {}", name, index + 1, error);
+                    false
+                }
+                Err(error) => {
+                    eprintln!("{} - ERROR: {}", name, error);
+                    false
+                }
+            };
+        }
+        success
+    } else {
+        #[cfg(feature = "yaml")]
+        {
+            if is_yaml(path) {
+                return validate_yaml(compiled, path);
+            }
+        }
+        let document = match fs::read_to_string(path)
+            .map_err(BoxedError::from)
+            .and_then(|content| Ok(JSONDocument::parse(&content)?))
+        {
+            Ok(document) => document,
+            Err(error) => {
+                eprintln!("{} - ERROR: {}", name, error);
+                return false;
+            }
+        };
+        let locate = |error: &ValidationError| error.span().map(|span| span.location);
+        let errors = compiled.validate_document(&document).err();
+        report(&name.to_string(), errors.into_iter().flatten(), locate)
+    }
+}
+
+fn run(cli: &Cli) -> Result<bool, BoxedError> {
+    let schema = read_document(&cli.schema)
+        .map_err(|error| format!("Failed to read '{}': {}", cli.schema.display(), error))?;
+    let mut options = JSONSchema::options();
+    if let Some(draft) = cli.draft {
+        options.with_draft(draft);
+    }
+    let compiled = options.compile(&schema)?;
+    let mut success = true;
+    for path in &cli.instances {
+        success &= validate_file(&compiled, path, cli.jsonl || is_json_lines(path));
+    }
+    Ok(success)
+}
+
+fn main() {
+    let cli = Cli::from_args();
+    match run(&cli) {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            process::exit(1)
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Locations inside validated instances.
+use std::{fmt, slice};
+
+/// JSON Pointer to a value inside a validated instance.
+///
+/// It is displayed according to RFC 6901, e.g. `/foo/0/bar`. The empty pointer refers to
+/// the whole instance.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JSONPointer(Vec<PathChunk>);
+
+/// A single step in `JSONPointer`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PathChunk {
+    /// Property name within an object.
+    Property(String),
+    /// Index within an array.
+    Index(usize),
+}
+
+impl JSONPointer {
+    /// Iterate over the pointer components, starting from the root.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, PathChunk> {
+        self.0.iter()
+    }
+    /// Pointer components, starting from the root.
+    #[must_use]
+    #[inline]
+    pub fn into_vec(self) -> Vec<PathChunk> {
+        self.0
+    }
+    /// Whether the pointer refers to the whole instance.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for &'a JSONPointer {
+    type Item = &'a PathChunk;
+    type IntoIter = slice::Iter<'a, PathChunk>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl fmt::Display for JSONPointer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chunk in &self.0 {
+            f.write_str("/")?;
+            match chunk {
+                PathChunk::Property(name) => {
+                    f.write_str(&name.replace('~', "~0").replace('/', "~1"))?
+                }
+                PathChunk::Index(index) => write!(f, "{}", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<&str> for PathChunk {
+    #[inline]
+    fn from(value: &str) -> Self {
+        PathChunk::Property(value.to_string())
+    }
+}
+
+impl From<&String> for PathChunk {
+    #[inline]
+    fn from(value: &String) -> Self {
+        PathChunk::Property(value.clone())
+    }
+}
+
+impl From<usize> for PathChunk {
+    #[inline]
+    fn from(value: usize) -> Self {
+        PathChunk::Index(value)
+    }
+}
+
+impl From<Vec<PathChunk>> for JSONPointer {
+    #[inline]
+    fn from(chunks: Vec<PathChunk>) -> Self {
+        JSONPointer(chunks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JSONPointer, PathChunk};
+
+    #[test]
+    fn display() {
+        let pointer = JSONPointer::from(vec![
+            PathChunk::from("a/b"),
+            PathChunk::from(0),
+            PathChunk::from("c~d"),
+        ]);
+        assert_eq!(pointer.to_string(), "/a~1b/0/c~0d");
+        assert_eq!(JSONPointer::default().to_string(), "");
+    }
+}
@@ -2,7 +2,7 @@ use std::{convert::TryFrom, fmt, ops::BitOrAssign};
 
 /// For faster error handling in "type" keyword validator we have this enum, to match
 /// with it instead of a string.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub(crate) enum PrimitiveType {
     Array,
     Boolean,
@@ -91,6 +91,31 @@ impl PrimitiveTypesBitMap {
     pub(crate) fn contains_type(self, primitive_type: PrimitiveType) -> bool {
         primitive_type_to_bit_map_representation(primitive_type) & self.inner != 0
     }
+
+    #[inline]
+    pub(crate) fn remove_type(mut self, primitive_type: PrimitiveType) -> Self {
+        self.inner &= !primitive_type_to_bit_map_representation(primitive_type);
+        self
+    }
+
+    #[inline]
+    pub(crate) const fn intersection(self, other: Self) -> Self {
+        Self {
+            inner: self.inner & other.inner,
+        }
+    }
+
+    #[inline]
+    pub(crate) const fn union(self, other: Self) -> Self {
+        Self {
+            inner: self.inner | other.inner,
+        }
+    }
+
+    #[inline]
+    pub(crate) const fn is_empty(self) -> bool {
+        self.inner == 0
+    }
 }
 impl BitOrAssign<PrimitiveType> for PrimitiveTypesBitMap {
     #[inline]
@@ -1,20 +1,75 @@
 //! Reference resolver. Implements logic, required by `$ref` keyword.
-//! Is able to load documents from remote locations via HTTP(S).
+//! Is able to load documents from remote locations via HTTP(S) and from local files.
 use crate::{
-    compilation::{DEFAULT_ROOT_URL, DEFAULT_SCOPE},
+    cache::DocumentCache,
+    compilation::DEFAULT_ROOT_URL,
     error::{CompilationError, ValidationError},
-    schemas::{id_of, Draft},
+    schemas::{id_of, subschemas, Draft},
 };
+use parking_lot::RwLock;
+use reqwest::header::{HeaderMap, CONTENT_TYPE};
 use serde_json::Value;
-use std::{borrow::Cow, collections::HashMap};
+use std::{borrow::Cow, collections::HashMap, fs, ops::Deref, sync::Arc};
 use url::Url;
 
+/// Subschemas with `$id` in a loaded document.
+#[derive(Debug)]
+struct Embedded {
+    document: Arc<Value>,
+    /// Subschemas by their canonical ids
+    schemas: HashMap<String, Value>,
+}
+
+/// A document resolved by its URL.
+#[derive(Debug)]
+pub(crate) enum Document<'a> {
+    /// The root document or its subschema.
+    Borrowed(&'a Value),
+    /// A loaded document, shared with the resolver and the document cache.
+    Shared(Arc<Value>),
+}
+
+impl Document<'_> {
+    /// Take the document out, cloning it only if it is still shared.
+    pub(crate) fn into_owned(self) -> Value {
+        match self {
+            Document::Borrowed(document) => document.clone(),
+            Document::Shared(document) => {
+                Arc::try_unwrap(document).unwrap_or_else(|document| (*document).clone())
+            }
+        }
+    }
+}
+
+impl Deref for Document<'_> {
+    type Target = Value;
+
+    fn deref(&self) -> &Value {
+        match self {
+            Document::Borrowed(document) => document,
+            Document::Shared(document) => document,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct Resolver<'a> {
     // canonical_id: sub-schema mapping to resolve documents by their ID
     // canonical_id is composed with the root document id
     // (if not specified, then `DEFAULT_ROOT_URL` is used for this purpose)
+    // It contains embedded resources (e.g. `{"$id": "http://example.com/item.json"}`) and
+    // location-independent identifiers (e.g. `{"$id": "#foo"}`) and built once during compilation
     schemas: HashMap<String, &'a Value>,
+    // Remote documents that were loaded upfront (e.g. during async compilation)
+    // and should not be requested again during validation
+    documents: HashMap<String, Arc<Value>>,
+    // Remote documents loaded on demand, possibly shared with other resolvers
+    cache: DocumentCache,
+    // Subschemas with `$id` in loaded documents by their canonical ids, indexed once per
+    // document on the first lookup. A document reloaded after its expiration is indexed again
+    embedded: RwLock<HashMap<String, Arc<Embedded>>>,
+    // Whether references may point to local files via the `file` scheme
+    allow_files: bool,
 }
 
 impl<'a> Resolver<'a> {
@@ -22,6 +77,8 @@ impl<'a> Resolver<'a> {
         draft: Draft,
         scope: &Url,
         schema: &'a Value,
+        cache: DocumentCache,
+        allow_files: bool,
     ) -> Result<Resolver<'a>, CompilationError> {
         let mut schemas = HashMap::new();
         // traverse the schema and store all named ones under their canonical ids
@@ -29,24 +86,124 @@ impl<'a> Resolver<'a> {
             schemas.insert(id, schema);
             None
         })?;
-        Ok(Resolver { schemas })
+        Ok(Resolver {
+            schemas,
+            documents: HashMap::new(),
+            cache,
+            embedded: RwLock::new(HashMap::new()),
+            allow_files,
+        })
+    }
+
+    /// Load all remote documents referenced (directly or transitively) by `schema`
+    /// without blocking the current thread.
+    ///
+    /// Loaded documents are stored in the resolver, hence the validation does not issue
+    /// any further network requests for them.
+    #[cfg(feature = "async")]
+    pub(crate) async fn prefetch(
+        &mut self,
+        draft: Draft,
+        scope: &Url,
+        schema: &Value,
+    ) -> Result<(), CompilationError> {
+        let mut pending = vec![];
+        find_external_references(draft, schema, scope, &mut pending)?;
+        while let Some(url) = pending.pop() {
+            if !self.is_known(&url) {
+                let document = match self.cache.get(url.as_str()) {
+                    Some(document) => document,
+                    None => {
+                        let remote_document_error =
+                            |error: reqwest::Error| CompilationError::RemoteDocument {
+                                url: url.to_string(),
+                                reason: error.to_string(),
+                            };
+                        let response = reqwest::get(url.as_str())
+                            .await
+                            .and_then(reqwest::Response::error_for_status)
+                            .map_err(remote_document_error)?;
+                        let media_type = media_type_of(response.headers());
+                        let content = response.bytes().await.map_err(remote_document_error)?;
+                        let document = parse_document(&url, media_type.as_deref(), &content)
+                            .map_err(|error| CompilationError::RemoteDocument {
+                                url: url.to_string(),
+                                reason: error.to_string(),
+                            })?;
+                        let document = Arc::new(document);
+                        self.cache.insert(url.to_string(), Arc::clone(&document));
+                        document
+                    }
+                };
+                let base_url = match id_of(draft, &document) {
+                    Some(id) => url.join(id)?,
+                    None => url.clone(),
+                };
+                find_external_references(draft, &document, &base_url, &mut pending)?;
+                self.documents.insert(url.to_string(), document);
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the document under `url` can be loaded without a network request.
+    #[cfg(feature = "async")]
+    fn is_known(&self, url: &Url) -> bool {
+        let url = url.as_str();
+        url == DEFAULT_ROOT_URL
+            || self.schemas.contains_key(url)
+            || self.documents.contains_key(url)
+    }
+
+    /// Whether `url` is the canonical id of a subschema of the root document.
+    pub(crate) fn is_embedded(&self, url: &Url) -> bool {
+        self.schemas.contains_key(url.as_str())
     }
 
     /// Load a document for the given `url`.
     /// It may be:
     ///   - the root document (`DEFAULT_ROOT_URL`) case;
     ///   - named subschema that is stored in `self.schemas`;
-    ///   - document from a remote location;
-    fn resolve_url(&self, url: &Url, schema: &'a Value) -> Result<Cow<'a, Value>, ValidationError> {
+    ///   - document from a remote location or a local file (stored in the document cache).
+    ///     Local files are loaded only if they are explicitly allowed;
+    pub(crate) fn resolve_url(
+        &self,
+        url: &Url,
+        schema: &'a Value,
+    ) -> Result<Document<'a>, ValidationError> {
         match url.as_str() {
-            DEFAULT_ROOT_URL => Ok(Cow::Borrowed(schema)),
+            DEFAULT_ROOT_URL => Ok(Document::Borrowed(schema)),
             url_str => match self.schemas.get(url_str) {
-                Some(value) => Ok(Cow::Borrowed(value)),
+                Some(value) => Ok(Document::Borrowed(value)),
+                None if self.documents.contains_key(url_str) => {
+                    Ok(Document::Shared(Arc::clone(&self.documents[url_str])))
+                }
                 None => match url.scheme() {
                     "http" | "https" => {
-                        let response = reqwest::blocking::get(url.as_str())?;
-                        let document: Value = response.json()?;
-                        Ok(Cow::Owned(document))
+                        if let Some(document) = self.cache.get(url_str) {
+                            return Ok(Document::Shared(document));
+                        }
+                        let response = reqwest::blocking::get(url.as_str())?.error_for_status()?;
+                        let media_type = media_type_of(response.headers());
+                        let document =
+                            parse_document(url, media_type.as_deref(), &response.bytes()?)?;
+                        let document = Arc::new(document);
+                        self.cache
+                            .insert(url_str.to_string(), Arc::clone(&document));
+                        Ok(Document::Shared(document))
+                    }
+                    "file" if self.allow_files => {
+                        if let Some(document) = self.cache.get(url_str) {
+                            return Ok(Document::Shared(document));
+                        }
+                        let path = url
+                            .to_file_path()
+                            .map_err(|_| ValidationError::invalid_reference(url_str.to_string()))?;
+                        let document = parse_document(url, None, &fs::read(path)?)?;
+                        let document = Arc::new(document);
+                        self.cache
+                            .insert(url_str.to_string(), Arc::clone(&document));
+                        Ok(Document::Shared(document))
                     }
                     http_scheme => Err(ValidationError::unknown_reference_scheme(
                         http_scheme.to_owned(),
@@ -55,6 +212,34 @@ impl<'a> Resolver<'a> {
             },
         }
     }
+
+    /// Subschemas with `$id` in the loaded `document`, indexed once per document.
+    fn embedded(
+        &self,
+        draft: Draft,
+        resource: &Url,
+        document: &Arc<Value>,
+    ) -> Result<Arc<Embedded>, url::ParseError> {
+        if let Some(embedded) = self.embedded.read().get(resource.as_str()) {
+            if Arc::ptr_eq(&embedded.document, document) {
+                return Ok(Arc::clone(embedded));
+            }
+        }
+        let mut schemas = HashMap::new();
+        find_schemas(draft, document, resource, &mut |id, schema| {
+            schemas.insert(id, schema.clone());
+            None
+        })?;
+        let embedded = Arc::new(Embedded {
+            document: Arc::clone(document),
+            schemas,
+        });
+        self.embedded
+            .write()
+            .insert(resource.to_string(), Arc::clone(&embedded));
+        Ok(embedded)
+    }
+
     pub(crate) fn resolve_fragment(
         &self,
         draft: Draft,
@@ -68,35 +253,68 @@ impl<'a> Resolver<'a> {
 
         // Location-independent identifiers are searched before trying to resolve by
         // fragment-less url
-        if let Some(x) = find_schemas(draft, schema, &DEFAULT_SCOPE, &mut |id, x| {
-            if id == url.as_str() {
-                Some(x)
-            } else {
-                None
-            }
-        })? {
+        if let Some(x) = self.schemas.get(url.as_str()) {
             return Ok((resource, Cow::Borrowed(x)));
         }
 
         // Each resolved document may be in a changed subfolder
         // They are tracked when JSON pointer is resolved and added to the resource
         match self.resolve_url(&resource, schema)? {
-            Cow::Borrowed(document) => match pointer(draft, document, fragment.as_ref()) {
+            Document::Borrowed(document) => match pointer(draft, document, fragment.as_ref()) {
                 Some((folders, resolved)) => {
                     Ok((join_folders(resource, &folders)?, Cow::Borrowed(resolved)))
                 }
                 None => Err(ValidationError::invalid_reference(url.as_str().to_string())),
             },
-            Cow::Owned(document) => match pointer(draft, &document, fragment.as_ref()) {
+            Document::Shared(document) => match pointer(draft, &document, fragment.as_ref()) {
                 Some((folders, x)) => {
                     Ok((join_folders(resource, &folders)?, Cow::Owned(x.clone())))
                 }
-                None => Err(ValidationError::invalid_reference(url.as_str().to_string())),
+                // Location-independent identifier inside a remote document
+                None => match self
+                    .embedded(draft, &resource, &document)?
+                    .schemas
+                    .get(url.as_str())
+                {
+                    Some(x) => Ok((resource, Cow::Owned(x.clone()))),
+                    None => Err(ValidationError::invalid_reference(url.as_str().to_string())),
+                },
             },
         }
     }
 }
 
+fn media_type_of(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned)
+}
+
+/// Parse a loaded document. YAML documents are detected by their media type or extension.
+#[cfg(feature = "yaml")]
+fn parse_document(
+    url: &Url,
+    media_type: Option<&str>,
+    content: &[u8],
+) -> Result<Value, ValidationError<'static>> {
+    if crate::yaml::is_yaml(url, media_type) {
+        Ok(serde_yaml::from_slice(content)?)
+    } else {
+        Ok(serde_json::from_slice(content)?)
+    }
+}
+
+/// Parse a loaded document.
+#[cfg(not(feature = "yaml"))]
+fn parse_document(
+    _: &Url,
+    _: Option<&str>,
+    content: &[u8],
+) -> Result<Value, ValidationError<'static>> {
+    Ok(serde_json::from_slice(content)?)
+}
+
 fn join_folders(mut resource: Url, folders: &[&str]) -> Result<Url, url::ParseError> {
     if folders.len() > 1 {
         for i in folders.iter().skip(1) {
@@ -106,7 +324,40 @@ fn join_folders(mut resource: Url, folders: &[&str]) -> Result<Url, url::ParseEr
     Ok(resource)
 }
 
-/// Find all sub-schemas in the document and execute callback on each of them.
+/// Traverse `schema` and execute `callback` on it and on each of its sub-schemas together
+/// with their base URLs (i.e. with `$id` applied). Traversal stops as soon as `callback`
+/// returns `Some`.
+///
+/// Only keywords with subschemas are traversed, see `schemas::subschemas`.
+pub(crate) fn walk_schemas<'a, F, T>(
+    draft: Draft,
+    schema: &'a Value,
+    base_url: &Url,
+    callback: &mut F,
+) -> Result<Option<T>, url::ParseError>
+where
+    F: FnMut(&Url, &'a Value) -> Option<T>,
+{
+    if let Value::Object(item) = schema {
+        let base_url = match id_of(draft, schema) {
+            Some(id) => Cow::Owned(base_url.join(id)?),
+            None => Cow::Borrowed(base_url),
+        };
+        if let Some(result) = callback(&base_url, schema) {
+            return Ok(Some(result));
+        }
+        for (_, _, subschema) in subschemas(item) {
+            let result = walk_schemas(draft, subschema, &base_url, callback)?;
+            if result.is_some() {
+                return Ok(result);
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Find all sub-schemas with `$id` (`id` in Draft 4) in the document and execute callback on
+/// each of them together with their canonical ids.
 #[inline]
 pub(crate) fn find_schemas<'a, F>(
     draft: Draft,
@@ -117,39 +368,47 @@ pub(crate) fn find_schemas<'a, F>(
 where
     F: FnMut(String, &'a Value) -> Option<&'a Value>,
 {
-    match schema {
-        Value::Object(item) => {
-            if let Some(url) = id_of(draft, schema) {
-                let new_url = base_url.join(url)?;
-                if let Some(x) = callback(new_url.to_string(), schema) {
-                    return Ok(Some(x));
-                }
-                for (_, subschema) in item {
-                    let result = find_schemas(draft, subschema, &new_url, callback)?;
-                    if result.is_some() {
-                        return Ok(result);
-                    }
-                }
-            } else {
-                for (_, subschema) in item {
-                    let result = find_schemas(draft, subschema, base_url, callback)?;
-                    if result.is_some() {
-                        return Ok(result);
+    walk_schemas(draft, schema, base_url, &mut |url, subschema| {
+        if id_of(draft, subschema).is_some() {
+            callback(url.to_string(), subschema)
+        } else {
+            None
+        }
+    })
+}
+
+/// Collect fragment-less URLs of all remote documents referenced via `$ref` in `schema`.
+#[cfg(feature = "async")]
+pub(crate) fn find_external_references(
+    draft: Draft,
+    schema: &Value,
+    base_url: &Url,
+    references: &mut Vec<Url>,
+) -> Result<(), url::ParseError> {
+    let mut error = None;
+    walk_schemas(draft, schema, base_url, &mut |base_url, subschema| {
+        if let Some(reference) = subschema.get("$ref").and_then(Value::as_str) {
+            match base_url.join(reference) {
+                Ok(mut url) => {
+                    url.set_fragment(None);
+                    if (url.scheme() == "http" || url.scheme() == "https")
+                        && !references.contains(&url)
+                    {
+                        references.push(url);
                     }
                 }
-            }
-        }
-        Value::Array(items) => {
-            for item in items {
-                let result = find_schemas(draft, item, base_url, callback)?;
-                if result.is_some() {
-                    return Ok(result);
+                Err(err) => {
+                    error = Some(err);
+                    return Some(());
                 }
             }
         }
-        _ => {}
+        None
+    })?;
+    match error {
+        Some(error) => Err(error),
+        None => Ok(()),
     }
-    Ok(None)
 }
 
 /// Based on `serde_json`, but tracks folders in the traversed documents.
@@ -202,8 +461,10 @@ fn parse_index(s: &str) -> Option<usize> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::JSONSchema;
     use serde_json::*;
     use std::borrow::Cow;
+    use tempfile::TempDir;
     use url::Url;
 
     fn make_resolver(schema: &Value) -> Resolver {
@@ -211,6 +472,8 @@ mod tests {
             Draft::Draft7,
             &Url::parse("json-schema:///").unwrap(),
             schema,
+            DocumentCache::default(),
+            true,
         )
         .unwrap()
     }
@@ -409,6 +672,104 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sub_schemas_in_items_array() {
+        let schema = json!({
+            "items": [
+                {"$id": "#foo", "type": "integer"},
+                {"items": {"$id": "#bar", "type": "string"}}
+            ]
+        });
+        let resolver = make_resolver(&schema);
+        assert_eq!(resolver.schemas.len(), 2);
+        assert_eq!(
+            resolver.schemas.get("json-schema:///#foo"),
+            schema.pointer("/items/0").as_ref()
+        );
+        assert_eq!(
+            resolver.schemas.get("json-schema:///#bar"),
+            schema.pointer("/items/1/items").as_ref()
+        );
+    }
+
+    #[test]
+    fn ids_in_instances_are_ignored() {
+        // When `$id` is a part of data rather than a schema
+        let schema = json!({
+            "definitions": {
+                "in_enum": {"enum": [{"$id": "http://localhost:1234/id.json"}]},
+                "in_const": {"const": {"$id": "http://localhost:1234/id.json"}},
+                "in_default": {"default": {"$id": "http://localhost:1234/id.json"}},
+                "real": {"$id": "http://localhost:1234/id.json", "type": "string"}
+            }
+        });
+        let resolver = make_resolver(&schema);
+        // Then only the real identifier is stored
+        assert_eq!(resolver.schemas.len(), 1);
+        assert_eq!(
+            resolver.schemas.get("http://localhost:1234/id.json"),
+            schema.pointer("/definitions/real").as_ref()
+        );
+    }
+
+    #[test]
+    fn properties_named_as_keywords() {
+        // When property names match keywords that contain instances
+        let schema = json!({
+            "properties": {
+                "enum": {"$id": "#enum"},
+                "const": {"$id": "#const"}
+            }
+        });
+        let resolver = make_resolver(&schema);
+        // Then they are still treated as schemas
+        assert_eq!(resolver.schemas.len(), 2);
+        assert_eq!(
+            resolver.schemas.get("json-schema:///#enum"),
+            schema.pointer("/properties/enum").as_ref()
+        );
+        assert_eq!(
+            resolver.schemas.get("json-schema:///#const"),
+            schema.pointer("/properties/const").as_ref()
+        );
+    }
+
+    #[test]
+    fn resolve_plain_name_fragment() {
+        let schema = json!({
+            "$id": "http://localhost:1234/root.json",
+            "definitions": {
+                "A": {"$id": "#foo", "type": "integer"}
+            }
+        });
+        let resolver = make_resolver(&schema);
+        let url = Url::parse("http://localhost:1234/root.json#foo").unwrap();
+        let (resource, resolved) = resolver
+            .resolve_fragment(Draft::Draft7, &url, &schema)
+            .unwrap();
+        assert_eq!(
+            resource,
+            Url::parse("http://localhost:1234/root.json").unwrap()
+        );
+        assert_eq!(&*resolved, schema.pointer("/definitions/A").unwrap());
+    }
+
+    #[test]
+    fn resolve_plain_name_fragment_in_remote_document() {
+        let mut server = mockito::Server::new();
+        let _remote = server
+            .mock("GET", "/remote.json")
+            .with_body(r##"{"definitions": {"A": {"$id": "#foo", "type": "integer"}}}"##)
+            .create();
+        let schema = json!({});
+        let resolver = make_resolver(&schema);
+        let url = Url::parse(&format!("{}/remote.json#foo", server.url())).unwrap();
+        let (_, resolved) = resolver
+            .resolve_fragment(Draft::Draft7, &url, &schema)
+            .unwrap();
+        assert_eq!(*resolved, json!({"$id": "#foo", "type": "integer"}));
+    }
+
     #[test]
     fn resolve_ref() {
         let schema = json!({
@@ -429,4 +790,118 @@ mod tests {
             assert_eq!(resolved, schema.pointer("/definitions/a").unwrap());
         }
     }
+
+    /// The file is removed together with `dir`.
+    fn write_file(dir: &TempDir, name: &str, content: &str) -> Url {
+        let path = dir.path().join(name);
+        fs::write(&path, content).unwrap();
+        Url::from_file_path(path).unwrap()
+    }
+
+    #[test]
+    fn resolve_local_file() {
+        let dir = TempDir::new().unwrap();
+        let mut url = write_file(
+            &dir,
+            "local.json",
+            r#"{"definitions": {"A": {"type": "integer"}}}"#,
+        );
+        url.set_fragment(Some("/definitions/A"));
+        let schema = json!({});
+        let resolver = make_resolver(&schema);
+        let (_, resolved) = resolver
+            .resolve_fragment(Draft::Draft7, &url, &schema)
+            .unwrap();
+        assert_eq!(*resolved, json!({"type": "integer"}));
+    }
+
+    #[test]
+    fn cached_documents_are_shared() {
+        let dir = TempDir::new().unwrap();
+        let url = write_file(&dir, "shared.json", r#"{"type": "integer"}"#);
+        let schema = json!({});
+        let resolver = make_resolver(&schema);
+        let resolve = || match resolver.resolve_url(&url, &schema).unwrap() {
+            Document::Shared(document) => document,
+            Document::Borrowed(_) => panic!("Should be loaded"),
+        };
+        assert!(Arc::ptr_eq(&resolve(), &resolve()));
+    }
+
+    #[test]
+    fn plain_name_fragments_are_indexed_once() {
+        let dir = TempDir::new().unwrap();
+        let mut url = write_file(
+            &dir,
+            "indexed.json",
+            r##"{"definitions": {"A": {"$id": "#foo", "type": "integer"}}}"##,
+        );
+        url.set_fragment(Some("foo"));
+        let schema = json!({});
+        let resolver = make_resolver(&schema);
+        let mut indexes = vec![];
+        for _ in 0..2 {
+            let (_, resolved) = resolver
+                .resolve_fragment(Draft::Draft7, &url, &schema)
+                .unwrap();
+            assert_eq!(*resolved, json!({"$id": "#foo", "type": "integer"}));
+            let embedded = resolver.embedded.read();
+            assert_eq!(embedded.len(), 1);
+            indexes.extend(embedded.values().map(Arc::clone));
+        }
+        assert!(Arc::ptr_eq(&indexes[0], &indexes[1]));
+    }
+
+    #[test]
+    fn local_files_are_not_allowed_by_default() {
+        let dir = TempDir::new().unwrap();
+        let url = write_file(&dir, "disallowed.json", r#"{"type": "integer"}"#);
+        let schema = json!({"$ref": url.as_str()});
+        let instance = json!("a");
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let error = compiled.validate(&instance).unwrap_err().next().unwrap();
+        assert!(error.is_schema_error());
+        let compiled = JSONSchema::options()
+            .with_file_references()
+            .compile(&schema)
+            .unwrap();
+        assert!(!compiled.is_valid(&json!("a")));
+        assert!(compiled.is_valid(&json!(1)));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn resolve_local_yaml_file() {
+        let dir = TempDir::new().unwrap();
+        let mut url = write_file(
+            &dir,
+            "local.yaml",
+            "definitions:\n  A:\n    type: integer\n",
+        );
+        url.set_fragment(Some("/definitions/A"));
+        let schema = json!({});
+        let resolver = make_resolver(&schema);
+        let (_, resolved) = resolver
+            .resolve_fragment(Draft::Draft7, &url, &schema)
+            .unwrap();
+        assert_eq!(*resolved, json!({"type": "integer"}));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn resolve_remote_yaml_by_media_type() {
+        let mut server = mockito::Server::new();
+        let _remote = server
+            .mock("GET", "/remote")
+            .with_header("content-type", "application/yaml")
+            .with_body("definitions: {A: {type: integer}}")
+            .create();
+        let schema = json!({});
+        let resolver = make_resolver(&schema);
+        let url = Url::parse(&format!("{}/remote#/definitions/A", server.url())).unwrap();
+        let (_, resolved) = resolver
+            .resolve_fragment(Draft::Draft7, &url, &schema)
+            .unwrap();
+        assert_eq!(*resolved, json!({"type": "integer"}));
+    }
 }
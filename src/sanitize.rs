@@ -0,0 +1,169 @@
+//! Removing properties that are not allowed by the schema.
+use crate::{
+    compilation::JSONSchema,
+    error::ValidationError,
+    paths::{JSONPointer, PathChunk},
+    subschemas::{self, Result, Source},
+};
+use serde_json::Value;
+
+fn remove(
+    compiled: &JSONSchema,
+    sources: &[Source<'_>],
+    instance: &mut Value,
+    path: &mut Vec<PathChunk>,
+    removed: &mut Vec<JSONPointer>,
+) -> Result<()> {
+    match instance {
+        Value::Object(object) => {
+            let mut disallowed = vec![];
+            let mut allowed = vec![];
+            for name in object.keys() {
+                let children = subschemas::property(compiled, sources, name)?;
+                // E.g. `additionalProperties: false` if the name doesn't match any of
+                // `properties` and `patternProperties`
+                if children
+                    .iter()
+                    .any(|child| *child.schema == Value::Bool(false))
+                {
+                    disallowed.push(name.clone());
+                } else {
+                    allowed.push((name.clone(), children));
+                }
+            }
+            for name in disallowed {
+                object.remove(&name);
+                let mut pointer = path.clone();
+                pointer.push(PathChunk::Property(name));
+                removed.push(JSONPointer::from(pointer));
+            }
+            for (name, children) in allowed {
+                if let Some(value) = object.get_mut(&name) {
+                    path.push(PathChunk::Property(name));
+                    remove(compiled, &children, value, path, removed)?;
+                    path.pop();
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (index, value) in items.iter_mut().enumerate() {
+                let children = subschemas::item(compiled, sources, index)?;
+                path.push(PathChunk::Index(index));
+                remove(compiled, &children, value, path, removed)?;
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+impl<'a> JSONSchema<'a> {
+    /// Remove object properties that are not allowed by the schema and return their locations.
+    ///
+    /// A property is removed if it is rejected by `additionalProperties: false` (i.e. not listed in
+    /// `properties` and not matching `patternProperties`) or if its own schema is `false`.
+    /// Nested objects and arrays are processed recursively, following `properties`,
+    /// `patternProperties`, `additionalProperties`, `items`, `$ref` and `allOf`.
+    /// The instance is not validated, use `JSONSchema::validate` afterwards.
+    ///
+    /// ```rust
+    /// use jsonschema::JSONSchema;
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({"properties": {"name": {"type": "string"}}, "additionalProperties": false});
+    /// let compiled = JSONSchema::compile(&schema).unwrap();
+    /// let mut payload = json!({"name": "foo", "tracking": "abc"});
+    /// let removed = compiled.remove_additional_properties(&mut payload).unwrap();
+    /// assert_eq!(payload, json!({"name": "foo"}));
+    /// assert_eq!(removed[0].to_string(), "/tracking");
+    /// ```
+    pub fn remove_additional_properties(
+        &self,
+        instance: &mut Value,
+    ) -> std::result::Result<Vec<JSONPointer>, ValidationError<'static>> {
+        let sources = subschemas::root(self)?;
+        let mut removed = vec![];
+        remove(self, &sources, instance, &mut vec![], &mut removed)?;
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::JSONSchema;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(
+        &json!({"properties": {"a": {}}, "additionalProperties": false}),
+        json!({"a": 1, "b": 2}),
+        &json!({"a": 1}),
+        &["/b"];
+        "additional properties"
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {}}, "patternProperties": {"^x-": {}}, "additionalProperties": false}),
+        json!({"a": 1, "x-b": 2, "c": 3}),
+        &json!({"a": 1, "x-b": 2}),
+        &["/c"];
+        "pattern properties"
+    )]
+    #[test_case(
+        &json!({"patternProperties": {"^\\d+$": {}}, "additionalProperties": false}),
+        json!({"1": 1, "\u{661}": 2}),
+        &json!({"1": 1}),
+        &["/\u{661}"];
+        "ecma 262 patterns"
+    )]
+    #[test_case(
+        &json!({"properties": {"a": false}}),
+        json!({"a": 1, "b": 2}),
+        &json!({"b": 2}),
+        &["/a"];
+        "false schema"
+    )]
+    #[test_case(
+        &json!({"additionalProperties": {"type": "integer"}}),
+        json!({"a": 1, "b": "2"}),
+        &json!({"a": 1, "b": "2"}),
+        &[];
+        "other additional properties are kept"
+    )]
+    #[test_case(
+        &json!({
+            "$ref": "#/definitions/list",
+            "definitions": {
+                "list": {"items": {"$ref": "#/definitions/item"}},
+                "item": {"properties": {"id": {}, "children": {"$ref": "#/definitions/list"}}, "additionalProperties": false}
+            }
+        }),
+        json!([{"id": 1, "extra": 1, "children": [{"id": 2, "extra": 2}]}]),
+        &json!([{"id": 1, "children": [{"id": 2}]}]),
+        &["/0/extra", "/0/children/0/extra"];
+        "nested"
+    )]
+    #[test_case(
+        &json!({"allOf": [{"properties": {"a": {}, "b": {}}}, {"properties": {"a": {}}, "additionalProperties": false}]}),
+        json!({"a": 1, "b": 2}),
+        &json!({"a": 1}),
+        &["/b"];
+        "all of"
+    )]
+    fn remove_additional_properties(
+        schema: &Value,
+        mut instance: Value,
+        expected: &Value,
+        removed: &[&str],
+    ) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        let paths: Vec<_> = compiled
+            .remove_additional_properties(&mut instance)
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(&instance, expected);
+        assert_eq!(paths, removed);
+    }
+}
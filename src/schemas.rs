@@ -1,4 +1,4 @@
-use crate::{compilation::context::CompilationContext, keywords};
+use crate::{compilation::context::CompilationContext, keywords, paths::PathChunk};
 use serde_json::{Map, Value};
 
 /// JSON Schema Draft version
@@ -129,9 +129,184 @@ pub(crate) fn id_of(draft: Draft, schema: &Value) -> Option<&str> {
     }
 }
 
+/// Keywords with a single subschema, in any of the supported drafts or the ones schemas are
+/// converted to.
+pub(crate) const SINGLE: [&str; 12] = [
+    "additionalItems",
+    "additionalProperties",
+    "contains",
+    "contentSchema",
+    "else",
+    "if",
+    "items",
+    "not",
+    "propertyNames",
+    "then",
+    "unevaluatedItems",
+    "unevaluatedProperties",
+];
+/// Keywords with an array of subschemas.
+pub(crate) const ARRAY: [&str; 4] = ["allOf", "anyOf", "items", "oneOf"];
+/// Keywords with an object of subschemas.
+pub(crate) const MAP: [&str; 6] = [
+    "$defs",
+    "definitions",
+    "dependencies",
+    "dependentSchemas",
+    "patternProperties",
+    "properties",
+];
+
+/// How subschemas are nested in a keyword value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Nesting {
+    /// The value is a subschema, e.g. `not`.
+    Single,
+    /// The value is an array of subschemas, e.g. `allOf`.
+    Array,
+    /// The value is an object of subschemas, e.g. `properties`.
+    Map,
+}
+
+/// How subschemas are nested in the value of `keyword`. Values of other keywords, e.g. `enum`,
+/// `default` or unknown keywords, are instances or annotations and contain no subschemas.
+#[inline]
+pub(crate) fn nesting(keyword: &str, value: &Value) -> Option<Nesting> {
+    match value {
+        Value::Array(_) if ARRAY.contains(&keyword) => Some(Nesting::Array),
+        Value::Object(_) if MAP.contains(&keyword) => Some(Nesting::Map),
+        Value::Object(_) | Value::Bool(_) if SINGLE.contains(&keyword) => Some(Nesting::Single),
+        _ => None,
+    }
+}
+
+/// Whether a value inside `Nesting::Map` is a subschema. Property dependencies are arrays of
+/// names instead, and schemas are sometimes grouped into arrays, e.g. in `definitions`.
+#[inline]
+pub(crate) fn is_schema(value: &Value) -> bool {
+    value.is_object() || value.is_boolean()
+}
+
+/// Position of a subschema inside the value of its keyword.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Position<'a> {
+    /// The keyword value is the subschema itself.
+    Whole,
+    /// An item of an array of subschemas.
+    Index(usize),
+    /// A value of an object of subschemas.
+    Name(&'a str),
+    /// An item of an array inside an object of subschemas.
+    Entry(&'a str, usize),
+}
+
+impl Position<'_> {
+    /// Path chunks from the parent schema to the subschema inside `keyword`.
+    pub(crate) fn path(self, keyword: &str) -> Vec<PathChunk> {
+        let keyword = PathChunk::Property(keyword.to_string());
+        match self {
+            Position::Whole => vec![keyword],
+            Position::Index(index) => vec![keyword, PathChunk::Index(index)],
+            Position::Name(name) => vec![keyword, PathChunk::Property(name.to_string())],
+            Position::Entry(name, index) => vec![
+                keyword,
+                PathChunk::Property(name.to_string()),
+                PathChunk::Index(index),
+            ],
+        }
+    }
+}
+
+/// Subschemas nested directly in `object` in the document order, with their keywords.
+pub(crate) fn subschemas(object: &Map<String, Value>) -> Vec<(&str, Position<'_>, &Value)> {
+    let mut subschemas = vec![];
+    for (keyword, value) in object {
+        match (nesting(keyword, value), value) {
+            (Some(Nesting::Single), _) => {
+                subschemas.push((keyword.as_str(), Position::Whole, value))
+            }
+            (Some(Nesting::Array), Value::Array(items)) => {
+                for (index, item) in items.iter().enumerate() {
+                    subschemas.push((keyword.as_str(), Position::Index(index), item))
+                }
+            }
+            (Some(Nesting::Map), Value::Object(map)) => {
+                for (name, item) in map {
+                    match item {
+                        Value::Array(items) => {
+                            for (index, item) in
+                                items.iter().enumerate().filter(|(_, item)| is_schema(item))
+                            {
+                                subschemas.push((
+                                    keyword.as_str(),
+                                    Position::Entry(name, index),
+                                    item,
+                                ))
+                            }
+                        }
+                        item if is_schema(item) => {
+                            subschemas.push((keyword.as_str(), Position::Name(name), item))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    subschemas
+}
+
+/// Same as `subschemas`, but allows modifying them in place.
+pub(crate) fn subschemas_mut(
+    object: &mut Map<String, Value>,
+) -> Vec<(&str, Position<'_>, &mut Value)> {
+    let mut subschemas = vec![];
+    for (keyword, value) in object.iter_mut() {
+        match nesting(keyword, value) {
+            Some(Nesting::Single) => subschemas.push((keyword.as_str(), Position::Whole, value)),
+            Some(Nesting::Array) => {
+                if let Value::Array(items) = value {
+                    for (index, item) in items.iter_mut().enumerate() {
+                        subschemas.push((keyword.as_str(), Position::Index(index), item))
+                    }
+                }
+            }
+            Some(Nesting::Map) => {
+                if let Value::Object(map) = value {
+                    for (name, item) in map.iter_mut() {
+                        match item {
+                            Value::Array(items) => {
+                                for (index, item) in items
+                                    .iter_mut()
+                                    .enumerate()
+                                    .filter(|(_, item)| is_schema(item))
+                                {
+                                    subschemas.push((
+                                        keyword.as_str(),
+                                        Position::Entry(name, index),
+                                        item,
+                                    ))
+                                }
+                            }
+                            item if is_schema(item) => {
+                                subschemas.push((keyword.as_str(), Position::Name(name), item))
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            None => {}
+        }
+    }
+    subschemas
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::paths::JSONPointer;
     use serde_json::{json, Value};
     use test_case::test_case;
 
@@ -143,6 +318,18 @@ mod tests {
         assert_eq!(draft_from_schema(schema), draft)
     }
 
+    #[test_case(&json!({"not": {}, "enum": [{}], "unknown": {}}), &["/not"]; "single")]
+    #[test_case(&json!({"allOf": [{}, true], "items": [{}]}), &["/allOf/0", "/allOf/1", "/items/0"]; "arrays")]
+    #[test_case(&json!({"properties": {"a": {}}, "dependencies": {"b": ["a"], "c": {}}}), &["/dependencies/c", "/properties/a"]; "maps")]
+    #[test_case(&json!({"definitions": {"a": [{}, 1]}}), &["/definitions/a/0"]; "arrays in maps")]
+    fn test_subschemas(schema: &Value, expected: &[&str]) {
+        let paths: Vec<_> = subschemas(schema.as_object().expect("An object"))
+            .into_iter()
+            .map(|(keyword, position, _)| JSONPointer::from(position.path(keyword)).to_string())
+            .collect();
+        assert_eq!(paths, expected)
+    }
+
     #[test]
     fn test_default() {
         assert_eq!(Draft::default(), Draft::Draft7)
@@ -0,0 +1,545 @@
+//! Validation of `serde::Serialize` types without converting them into `serde_json::Value`.
+//!
+//! The instance is passed through a custom `Serializer` that evaluates the same plan that is used
+//! for single-pass validation of deserialized documents.
+use crate::{
+    compilation::JSONSchema,
+    error::{ErrorIterator, ValidationError},
+    primitive_type::PrimitiveType,
+    single_pass::{Node, Number, ObjectState, Plan},
+};
+use serde::ser::{
+    self, Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant,
+    SerializeTuple, SerializeTupleStruct, SerializeTupleVariant, Serializer,
+};
+use serde_json::{value::Serializer as ValueSerializer, Value};
+use std::convert::TryFrom;
+
+type Error = serde_json::Error;
+
+/// Check `value` against `plan`.
+fn check<T: Serialize + ?Sized>(
+    plan: &Plan,
+    schema: &JSONSchema,
+    value: &T,
+) -> Result<bool, Error> {
+    match plan {
+        Plan::Always(is_valid) => Ok(*is_valid),
+        Plan::Stream(node) => value.serialize(NodeSerializer { node, schema }),
+        Plan::Materialize(validators) => {
+            let instance = serde_json::to_value(value)?;
+            Ok(validators
+                .iter()
+                .all(|validator| validator.is_valid(schema, &instance)))
+        }
+    }
+}
+
+/// Serialize a map key the same way as `serde_json` does, e.g. integers become strings.
+fn map_key<T: Serialize + ?Sized>(key: &T) -> Result<String, Error> {
+    let mut map = ValueSerializer.serialize_map(Some(1))?;
+    map.serialize_key(key)?;
+    map.serialize_value(&())?;
+    match SerializeMap::end(map)? {
+        Value::Object(object) => Ok(object
+            .into_iter()
+            .next()
+            .map(|(key, _)| key)
+            .unwrap_or_default()),
+        _ => unreachable!("A map is always serialized as an object"),
+    }
+}
+
+struct NodeSerializer<'p, 'a> {
+    node: &'p Node,
+    schema: &'p JSONSchema<'a>,
+}
+
+impl<'p, 'a> NodeSerializer<'p, 'a> {
+    fn check_number(self, value: Number) -> Result<bool, Error> {
+        Ok(self.node.is_valid_number(value))
+    }
+
+    fn object(self) -> ObjectChecker<'p, 'a> {
+        ObjectChecker {
+            is_valid: self.node.is_valid_scalar(PrimitiveType::Object),
+            state: ObjectState::new(self.node),
+            pending: None,
+            schema: self.schema,
+        }
+    }
+
+    fn array(self) -> ArrayChecker<'p, 'a> {
+        ArrayChecker {
+            node: self.node,
+            schema: self.schema,
+            is_valid: self.node.is_valid_scalar(PrimitiveType::Array),
+            count: 0,
+        }
+    }
+}
+
+impl<'p, 'a> Serializer for NodeSerializer<'p, 'a> {
+    type Ok = bool;
+    type Error = Error;
+    type SerializeSeq = ArrayChecker<'p, 'a>;
+    type SerializeTuple = ArrayChecker<'p, 'a>;
+    type SerializeTupleStruct = ArrayChecker<'p, 'a>;
+    type SerializeTupleVariant =
+        Materialized<'p, 'a, <ValueSerializer as Serializer>::SerializeTupleVariant>;
+    type SerializeMap = ObjectChecker<'p, 'a>;
+    type SerializeStruct = ObjectChecker<'p, 'a>;
+    type SerializeStructVariant =
+        Materialized<'p, 'a, <ValueSerializer as Serializer>::SerializeStructVariant>;
+
+    fn serialize_bool(self, _: bool) -> Result<bool, Error> {
+        Ok(self.node.is_valid_scalar(PrimitiveType::Boolean))
+    }
+    fn serialize_i8(self, value: i8) -> Result<bool, Error> {
+        self.serialize_i64(i64::from(value))
+    }
+    fn serialize_i16(self, value: i16) -> Result<bool, Error> {
+        self.serialize_i64(i64::from(value))
+    }
+    fn serialize_i32(self, value: i32) -> Result<bool, Error> {
+        self.serialize_i64(i64::from(value))
+    }
+    fn serialize_i64(self, value: i64) -> Result<bool, Error> {
+        self.check_number(Number::I64(value))
+    }
+    fn serialize_i128(self, value: i128) -> Result<bool, Error> {
+        if let Ok(value) = u64::try_from(value) {
+            self.serialize_u64(value)
+        } else if let Ok(value) = i64::try_from(value) {
+            self.serialize_i64(value)
+        } else {
+            Err(ser::Error::custom("number out of range"))
+        }
+    }
+    fn serialize_u8(self, value: u8) -> Result<bool, Error> {
+        self.serialize_u64(u64::from(value))
+    }
+    fn serialize_u16(self, value: u16) -> Result<bool, Error> {
+        self.serialize_u64(u64::from(value))
+    }
+    fn serialize_u32(self, value: u32) -> Result<bool, Error> {
+        self.serialize_u64(u64::from(value))
+    }
+    fn serialize_u64(self, value: u64) -> Result<bool, Error> {
+        self.check_number(Number::U64(value))
+    }
+    fn serialize_u128(self, value: u128) -> Result<bool, Error> {
+        match u64::try_from(value) {
+            Ok(value) => self.serialize_u64(value),
+            Err(_) => Err(ser::Error::custom("number out of range")),
+        }
+    }
+    fn serialize_f32(self, value: f32) -> Result<bool, Error> {
+        self.serialize_f64(f64::from(value))
+    }
+    fn serialize_f64(self, value: f64) -> Result<bool, Error> {
+        if value.is_finite() {
+            self.check_number(Number::F64(value))
+        } else {
+            // Same as in `serde_json`, which has no representation for NaN and infinities
+            self.serialize_unit()
+        }
+    }
+    fn serialize_char(self, value: char) -> Result<bool, Error> {
+        self.serialize_str(value.encode_utf8(&mut [0; 4]))
+    }
+    fn serialize_str(self, value: &str) -> Result<bool, Error> {
+        Ok(self.node.is_valid_string(value))
+    }
+    fn serialize_bytes(self, value: &[u8]) -> Result<bool, Error> {
+        let mut array = self.array();
+        for byte in value {
+            SerializeSeq::serialize_element(&mut array, byte)?;
+        }
+        SerializeSeq::end(array)
+    }
+    fn serialize_none(self) -> Result<bool, Error> {
+        self.serialize_unit()
+    }
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<bool, Error> {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<bool, Error> {
+        Ok(self.node.is_valid_scalar(PrimitiveType::Null))
+    }
+    fn serialize_unit_struct(self, _: &'static str) -> Result<bool, Error> {
+        self.serialize_unit()
+    }
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<bool, Error> {
+        self.serialize_str(variant)
+    }
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<bool, Error> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<bool, Error> {
+        let mut object = self.object();
+        SerializeStruct::serialize_field(&mut object, variant, value)?;
+        SerializeStruct::end(object)
+    }
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Ok(self.array())
+    }
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, Error> {
+        Ok(self.array())
+    }
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Ok(self.array())
+    }
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Ok(Materialized {
+            inner: ValueSerializer.serialize_tuple_variant(name, variant_index, variant, len)?,
+            node: self.node,
+            schema: self.schema,
+        })
+    }
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Ok(self.object())
+    }
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeStruct, Error> {
+        Ok(self.object())
+    }
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Ok(Materialized {
+            inner: ValueSerializer.serialize_struct_variant(name, variant_index, variant, len)?,
+            node: self.node,
+            schema: self.schema,
+        })
+    }
+}
+
+struct ArrayChecker<'p, 'a> {
+    node: &'p Node,
+    schema: &'p JSONSchema<'a>,
+    is_valid: bool,
+    count: u64,
+}
+
+impl SerializeSeq for ArrayChecker<'_, '_> {
+    type Ok = bool;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.count += 1;
+        if self.is_valid {
+            if let Some(plan) = self.node.items() {
+                self.is_valid = check(plan, self.schema, value)?;
+            }
+        }
+        Ok(())
+    }
+    fn end(self) -> Result<bool, Error> {
+        Ok(self.is_valid && self.node.is_valid_items_count(self.count))
+    }
+}
+
+impl SerializeTuple for ArrayChecker<'_, '_> {
+    type Ok = bool;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        SerializeSeq::serialize_element(self, value)
+    }
+    fn end(self) -> Result<bool, Error> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl SerializeTupleStruct for ArrayChecker<'_, '_> {
+    type Ok = bool;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        SerializeSeq::serialize_element(self, value)
+    }
+    fn end(self) -> Result<bool, Error> {
+        SerializeSeq::end(self)
+    }
+}
+
+struct ObjectChecker<'p, 'a> {
+    is_valid: bool,
+    state: ObjectState<'p>,
+    /// The last serialized key
+    pending: Option<String>,
+    schema: &'p JSONSchema<'a>,
+}
+
+impl SerializeMap for ObjectChecker<'_, '_> {
+    type Ok = bool;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.pending = Some(map_key(key)?);
+        Ok(())
+    }
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        // Values are checked even after a failure, since a duplicate key may replace them
+        if let (Some(key), true) = (self.pending.take(), self.is_valid) {
+            let is_valid = match self.state.plan(&key) {
+                Some(plan) => check(plan, self.schema, value)?,
+                None => true,
+            };
+            self.state.record(&key, is_valid);
+        }
+        Ok(())
+    }
+    fn end(self) -> Result<bool, Error> {
+        Ok(self.is_valid && self.state.is_satisfied())
+    }
+}
+
+impl SerializeStruct for ObjectChecker<'_, '_> {
+    type Ok = bool;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.pending = Some(key.to_string());
+        SerializeMap::serialize_value(self, value)
+    }
+    fn end(self) -> Result<bool, Error> {
+        SerializeMap::end(self)
+    }
+}
+
+/// Tuple and struct variants are built as `Value` first and then checked.
+struct Materialized<'p, 'a, S> {
+    inner: S,
+    node: &'p Node,
+    schema: &'p JSONSchema<'a>,
+}
+
+impl<S> Materialized<'_, '_, S> {
+    fn check(self, end: impl FnOnce(S) -> Result<Value, Error>) -> Result<bool, Error> {
+        let value = end(self.inner)?;
+        value.serialize(NodeSerializer {
+            node: self.node,
+            schema: self.schema,
+        })
+    }
+}
+
+impl<S: SerializeTupleVariant<Ok = Value, Error = Error>> SerializeTupleVariant
+    for Materialized<'_, '_, S>
+{
+    type Ok = bool;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.inner.serialize_field(value)
+    }
+    fn end(self) -> Result<bool, Error> {
+        self.check(SerializeTupleVariant::end)
+    }
+}
+
+impl<S: SerializeStructVariant<Ok = Value, Error = Error>> SerializeStructVariant
+    for Materialized<'_, '_, S>
+{
+    type Ok = bool;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.inner.serialize_field(key, value)
+    }
+    fn end(self) -> Result<bool, Error> {
+        self.check(SerializeStructVariant::end)
+    }
+}
+
+impl<'a> JSONSchema<'a> {
+    /// Validate any `Serialize` type without converting it into `Value`.
+    ///
+    /// It uses the same single-pass approach as `JSONSchema::is_valid_from`. Serialization
+    /// errors (e.g. maps with non-string keys) are returned as is.
+    pub fn is_valid_serialize<T: Serialize + ?Sized>(&self, instance: &T) -> Result<bool, Error> {
+        match self.plan() {
+            Some(plan) => check(plan, self, instance),
+            None => Ok(self.is_valid(&serde_json::to_value(instance)?)),
+        }
+    }
+
+    /// Validate any `Serialize` type and return an iterator over `ValidationError` in the error case.
+    ///
+    /// Valid instances are checked without converting them into `Value`; it is built only to
+    /// report errors. Serialization failures are reported as validation errors.
+    ///
+    /// ```rust
+    /// # use jsonschema::JSONSchema;
+    /// # use serde_json::json;
+    /// # use std::collections::BTreeMap;
+    /// let schema = json!({"additionalProperties": {"type": "integer", "minimum": 0}});
+    /// let compiled = JSONSchema::compile(&schema).unwrap();
+    /// let mut stock = BTreeMap::new();
+    /// stock.insert("apples", 3);
+    /// assert!(compiled.validate_serialize(&stock).is_ok());
+    /// stock.insert("pears", -1);
+    /// assert!(compiled.validate_serialize(&stock).is_err());
+    /// ```
+    pub fn validate_serialize<T: Serialize + ?Sized>(
+        &self,
+        instance: &T,
+    ) -> Result<(), ErrorIterator<'static>> {
+        let result = self.is_valid_serialize(instance).and_then(|is_valid| {
+            if is_valid {
+                Ok(None)
+            } else {
+                serde_json::to_value(instance).map(Some)
+            }
+        });
+        let errors: Vec<_> = match result {
+            Ok(None) => return Ok(()),
+            Ok(Some(instance)) => match self.validate(&instance) {
+                Ok(()) => return Ok(()),
+                Err(errors) => errors.map(ValidationError::into_owned).collect(),
+            },
+            Err(error) => vec![ValidationError::serialization(error)],
+        };
+        Err(Box::new(errors.into_iter()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{schemas::Draft, JSONSchema};
+    use serde::Serialize;
+    use serde_json::{json, Value};
+    use std::collections::BTreeMap;
+    use test_case::test_case;
+
+    #[derive(Serialize)]
+    enum Kind {
+        Unit,
+        Newtype(i32),
+        Tuple(i32, String),
+        Struct { value: f64 },
+    }
+
+    #[derive(Serialize)]
+    struct Item {
+        name: String,
+        count: u8,
+        tags: Vec<char>,
+        nickname: Option<String>,
+        kind: Kind,
+        extra: BTreeMap<u32, bool>,
+    }
+
+    fn item(name: &str, count: u8, kind: Kind) -> Item {
+        let mut extra = BTreeMap::new();
+        extra.insert(1, true);
+        Item {
+            name: name.to_string(),
+            count,
+            tags: vec!['a', 'b'],
+            nickname: None,
+            kind,
+            extra,
+        }
+    }
+
+    #[test_case(&json!({"properties": {"name": {"maxLength": 3}, "count": {"maximum": 10}}, "required": ["name"]}))]
+    #[test_case(&json!({"properties": {"tags": {"items": {"enum": ["a"]}, "maxItems": 2}}}))]
+    #[test_case(&json!({"properties": {"nickname": {"type": "null"}, "extra": {"properties": {"1": {"const": true}}}}}))]
+    #[test_case(&json!({"properties": {"kind": {"type": "string"}}}))]
+    #[test_case(&json!({"properties": {"kind": {"properties": {"Newtype": {"minimum": 0}}, "additionalProperties": {"type": "array", "minItems": 2}}}}))]
+    #[test_case(&json!({"properties": {"kind": {"properties": {"Struct": {"properties": {"value": {"type": "integer"}}}}}}}))]
+    #[test_case(&json!({"required": ["missing"]}))]
+    #[test_case(&json!({"not": {"type": "object"}}))]
+    fn consistent_with_is_valid(schema: &Value) {
+        let items = [
+            item("foo", 1, Kind::Unit),
+            item("foobar", 20, Kind::Newtype(-1)),
+            item("foo", 5, Kind::Newtype(5)),
+            item("foo", 5, Kind::Tuple(1, "a".to_string())),
+            item("foo", 5, Kind::Struct { value: 1.0 }),
+            item("foo", 5, Kind::Struct { value: 1.5 }),
+        ];
+        for draft in &[Draft::Draft4, Draft::Draft7] {
+            let compiled = JSONSchema::options()
+                .with_draft(*draft)
+                .compile(schema)
+                .unwrap();
+            for item in &items {
+                let value = serde_json::to_value(item).unwrap();
+                assert_eq!(
+                    compiled.is_valid_serialize(item).unwrap(),
+                    compiled.is_valid(&value),
+                    "{} against {}",
+                    value,
+                    schema
+                );
+                assert_eq!(
+                    compiled.validate_serialize(item).is_ok(),
+                    compiled.is_valid(&value)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let schema = json!({"properties": {"count": {"maximum": 10}}});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let errors: Vec<_> = compiled
+            .validate_serialize(&item("foo", 20, Kind::Unit))
+            .unwrap_err()
+            .collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].instance_path().to_string(), "/count");
+    }
+
+    #[test]
+    fn serialization_error() {
+        let schema = json!({"type": "object"});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let mut map = BTreeMap::new();
+        map.insert(vec![1], 1);
+        assert!(compiled.is_valid_serialize(&map).is_err());
+        let errors: Vec<_> = compiled.validate_serialize(&map).unwrap_err().collect();
+        assert!(!errors[0].is_schema_error());
+    }
+}
@@ -0,0 +1,619 @@
+//! Validation of documents while they are being deserialized.
+//!
+//! Simple schemas are converted into a plan that is evaluated directly from `serde` visitor
+//! callbacks, hence the validated document is never built as `serde_json::Value`.
+//! Sub-schemas that use other keywords are compiled into regular validators and only the
+//! corresponding parts of the document are materialized.
+use crate::{
+    compilation::{compile_validators, context::CompilationContext, JSONSchema},
+    error::CompilationError,
+    keywords::{
+        helpers::{
+            is_above, is_at_least, is_at_most, is_below, is_integer_of, is_number_of, string_length,
+        },
+        Validators,
+    },
+    primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
+    schemas::Draft,
+};
+use serde::de::{
+    Deserialize, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor,
+};
+use serde_json::{Map, Value};
+use std::{borrow::Cow, collections::HashMap, convert::TryFrom, fmt};
+
+/// How to validate a sub-document.
+#[derive(Debug)]
+pub(crate) enum Plan {
+    /// Boolean schema.
+    Always(bool),
+    /// Validate while deserializing.
+    Stream(Box<Node>),
+    /// Deserialize into `Value` and run regular validators.
+    Materialize(Validators),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Number {
+    U64(u64),
+    I64(i64),
+    F64(f64),
+}
+
+impl Number {
+    fn from_value(value: &Value) -> Option<Self> {
+        if let Some(value) = value.as_u64() {
+            Some(Number::U64(value))
+        } else if let Some(value) = value.as_i64() {
+            Some(Number::I64(value))
+        } else {
+            value.as_f64().map(Number::F64)
+        }
+    }
+}
+
+macro_rules! compare {
+    ($function:ident, $left:expr, $right:expr) => {
+        match ($left, $right) {
+            (Number::U64(left), Number::U64(right)) => $function(left, right),
+            (Number::U64(left), Number::I64(right)) => $function(left, right),
+            (Number::U64(left), Number::F64(right)) => $function(left, right),
+            (Number::I64(left), Number::U64(right)) => $function(left, right),
+            (Number::I64(left), Number::I64(right)) => $function(left, right),
+            (Number::I64(left), Number::F64(right)) => $function(left, right),
+            (Number::F64(left), Number::U64(right)) => $function(left, right),
+            (Number::F64(left), Number::I64(right)) => $function(left, right),
+            (Number::F64(left), Number::F64(right)) => $function(left, right),
+        }
+    };
+}
+
+/// Keywords of a single schema that can be checked without materializing the instance.
+#[derive(Debug, Default)]
+pub(crate) struct Node {
+    types: Option<PrimitiveTypesBitMap>,
+    /// Draft 4 does not consider numbers like `1.0` as integers.
+    strict_integers: bool,
+    options: Option<Vec<String>>,
+    min_length: Option<u64>,
+    max_length: Option<u64>,
+    minimum: Option<Number>,
+    maximum: Option<Number>,
+    exclusive_minimum: Option<Number>,
+    exclusive_maximum: Option<Number>,
+    min_items: Option<u64>,
+    max_items: Option<u64>,
+    items: Option<Plan>,
+    min_properties: Option<u64>,
+    max_properties: Option<u64>,
+    properties: Vec<(String, Plan)>,
+    additional_properties: Option<Plan>,
+    required: Vec<String>,
+}
+
+fn as_limit(value: &Value) -> Result<u64, CompilationError> {
+    value.as_u64().ok_or(CompilationError::SchemaError)
+}
+
+fn as_number(value: &Value) -> Result<Number, CompilationError> {
+    Number::from_value(value).ok_or(CompilationError::SchemaError)
+}
+
+fn as_types(value: &Value) -> Result<PrimitiveTypesBitMap, CompilationError> {
+    let mut types = PrimitiveTypesBitMap::new();
+    let mut add = |item: &Value| match item.as_str().map(PrimitiveType::try_from) {
+        Some(Ok(primitive_type)) => {
+            types |= primitive_type;
+            Ok(())
+        }
+        _ => Err(CompilationError::SchemaError),
+    };
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                add(item)?
+            }
+        }
+        _ => add(value)?,
+    }
+    Ok(types)
+}
+
+/// Whether `limit` is not defined or the `predicate` holds for it.
+fn satisfies<T>(limit: Option<T>, predicate: impl FnOnce(T) -> bool) -> bool {
+    match limit {
+        Some(limit) => predicate(limit),
+        None => true,
+    }
+}
+
+impl Plan {
+    /// Plan for the root schema. Returns `None` if the root itself has to be materialized,
+    /// since its regular validators are already compiled.
+    pub(crate) fn compile_root(
+        schema: &Value,
+        context: &CompilationContext,
+    ) -> Result<Option<Plan>, CompilationError> {
+        match schema {
+            Value::Bool(value) => Ok(Some(Plan::Always(*value))),
+            Value::Object(object) => Ok(
+                Node::compile(schema, object, context)?.map(|node| Plan::Stream(Box::new(node)))
+            ),
+            _ => Err(CompilationError::SchemaError),
+        }
+    }
+
+    fn compile(schema: &Value, context: &CompilationContext) -> Result<Plan, CompilationError> {
+        match schema {
+            Value::Bool(value) => Ok(Plan::Always(*value)),
+            Value::Object(object) => match Node::compile(schema, object, context)? {
+                Some(node) => Ok(Plan::Stream(Box::new(node))),
+                None => Ok(Plan::Materialize(compile_validators(schema, context)?)),
+            },
+            _ => Err(CompilationError::SchemaError),
+        }
+    }
+}
+
+impl Node {
+    /// Returns `None` if the schema contains keywords that require the whole instance.
+    fn compile(
+        schema: &Value,
+        object: &Map<String, Value>,
+        context: &CompilationContext,
+    ) -> Result<Option<Node>, CompilationError> {
+        let context = context.push(schema)?;
+        let draft = context.config.draft();
+        let mut node = Node {
+            strict_integers: draft == Draft::Draft4,
+            ..Node::default()
+        };
+        for (keyword, value) in object {
+            match keyword.as_str() {
+                "type" => node.types = Some(as_types(value)?),
+                "enum" => match value.as_array() {
+                    Some(items) if items.iter().all(Value::is_string) => {
+                        node.options = Some(
+                            items
+                                .iter()
+                                .filter_map(Value::as_str)
+                                .map(str::to_string)
+                                .collect(),
+                        )
+                    }
+                    _ => return Ok(None),
+                },
+                "minLength" => node.min_length = Some(as_limit(value)?),
+                "maxLength" => node.max_length = Some(as_limit(value)?),
+                // Draft 4 defines exclusive bounds as modifiers of `minimum` and `maximum`
+                "minimum" if draft != Draft::Draft4 => node.minimum = Some(as_number(value)?),
+                "maximum" if draft != Draft::Draft4 => node.maximum = Some(as_number(value)?),
+                "exclusiveMinimum" if draft != Draft::Draft4 => {
+                    node.exclusive_minimum = Some(as_number(value)?)
+                }
+                "exclusiveMaximum" if draft != Draft::Draft4 => {
+                    node.exclusive_maximum = Some(as_number(value)?)
+                }
+                "minItems" => node.min_items = Some(as_limit(value)?),
+                "maxItems" => node.max_items = Some(as_limit(value)?),
+                "items" if value.is_object() || value.is_boolean() => {
+                    node.items = Some(Plan::compile(value, &context.with_path("items"))?)
+                }
+                "minProperties" => node.min_properties = Some(as_limit(value)?),
+                "maxProperties" => node.max_properties = Some(as_limit(value)?),
+                "properties" => {
+                    let properties = value.as_object().ok_or(CompilationError::SchemaError)?;
+                    let context = context.with_path("properties");
+                    for (name, subschema) in properties {
+                        let plan = Plan::compile(subschema, &context.with_path(name.as_str()))?;
+                        node.properties.push((name.clone(), plan))
+                    }
+                }
+                "additionalProperties" if !object.contains_key("patternProperties") => {
+                    node.additional_properties = Some(Plan::compile(
+                        value,
+                        &context.with_path("additionalProperties"),
+                    )?)
+                }
+                "required" => {
+                    let items = value.as_array().ok_or(CompilationError::SchemaError)?;
+                    for item in items {
+                        let name = item.as_str().ok_or(CompilationError::SchemaError)?;
+                        node.required.push(name.to_string())
+                    }
+                }
+                // Keywords without validators (annotations, unknown keywords, keywords from
+                // other drafts) do not affect validation
+                "$ref" => return Ok(None),
+                _ if draft.get_validator(keyword).is_none() => {}
+                _ => return Ok(None),
+            }
+        }
+        Ok(Some(node))
+    }
+
+    pub(crate) fn items(&self) -> Option<&Plan> {
+        self.items.as_ref()
+    }
+
+    fn has_type(&self, primitive_type: PrimitiveType) -> bool {
+        satisfies(self.types, |types| types.contains_type(primitive_type))
+    }
+
+    pub(crate) fn is_valid_scalar(&self, primitive_type: PrimitiveType) -> bool {
+        self.options.is_none() && self.has_type(primitive_type)
+    }
+
+    pub(crate) fn is_valid_number(&self, value: Number) -> bool {
+        if self.options.is_some() {
+            return false;
+        }
+        let type_matches = satisfies(self.types, |types| match value {
+            Number::U64(_) | Number::I64(_) => is_integer_of(types),
+            Number::F64(value) => is_number_of(types, value, self.strict_integers),
+        });
+        type_matches
+            && satisfies(self.minimum, |limit| compare!(is_at_least, value, limit))
+            && satisfies(self.maximum, |limit| compare!(is_at_most, value, limit))
+            && satisfies(self.exclusive_minimum, |limit| {
+                compare!(is_above, value, limit)
+            })
+            && satisfies(self.exclusive_maximum, |limit| {
+                compare!(is_below, value, limit)
+            })
+    }
+
+    pub(crate) fn is_valid_string(&self, value: &str) -> bool {
+        if !self.has_type(PrimitiveType::String) {
+            return false;
+        }
+        if let Some(options) = &self.options {
+            if !options.iter().any(|option| option == value) {
+                return false;
+            }
+        }
+        if self.min_length.is_some() || self.max_length.is_some() {
+            let length = string_length(value);
+            satisfies(self.min_length, |limit| length >= limit)
+                && satisfies(self.max_length, |limit| length <= limit)
+        } else {
+            true
+        }
+    }
+
+    pub(crate) fn is_valid_items_count(&self, count: u64) -> bool {
+        satisfies(self.min_items, |limit| count >= limit)
+            && satisfies(self.max_items, |limit| count <= limit)
+    }
+}
+
+/// Tracks keywords that depend on the whole set of object properties.
+///
+/// Duplicate keys are handled as in `serde_json::Map`: the last value wins, hence the result
+/// is known only after all properties are seen.
+pub(crate) struct ObjectState<'n> {
+    node: &'n Node,
+    /// Whether the last value of every seen property is valid
+    properties: HashMap<String, bool>,
+}
+
+impl<'n> ObjectState<'n> {
+    pub(crate) fn new(node: &'n Node) -> Self {
+        ObjectState {
+            node,
+            properties: HashMap::new(),
+        }
+    }
+
+    /// The plan for the value of the given property, if any.
+    pub(crate) fn plan(&self, key: &str) -> Option<&'n Plan> {
+        self.node
+            .properties
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, plan)| plan)
+            .or(self.node.additional_properties.as_ref())
+    }
+
+    /// Register a property together with the validity of its value.
+    pub(crate) fn record(&mut self, key: &str, is_valid: bool) {
+        match self.properties.get_mut(key) {
+            Some(previous) => *previous = is_valid,
+            None => {
+                self.properties.insert(key.to_string(), is_valid);
+            }
+        }
+    }
+
+    /// Whether property values are valid, required properties are present and the number of
+    /// properties is within limits.
+    pub(crate) fn is_satisfied(&self) -> bool {
+        let count = self.properties.len() as u64;
+        self.properties.values().all(|is_valid| *is_valid)
+            && self
+                .node
+                .required
+                .iter()
+                .all(|name| self.properties.contains_key(name))
+            && satisfies(self.node.min_properties, |limit| count >= limit)
+            && satisfies(self.node.max_properties, |limit| count <= limit)
+    }
+}
+
+/// Validates a sub-document according to `plan`.
+struct PlanSeed<'p, 'a> {
+    plan: &'p Plan,
+    schema: &'p JSONSchema<'a>,
+}
+
+impl<'de> DeserializeSeed<'de> for PlanSeed<'_, '_> {
+    type Value = bool;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<bool, D::Error> {
+        match self.plan {
+            Plan::Always(valid) => {
+                IgnoredAny::deserialize(deserializer)?;
+                Ok(*valid)
+            }
+            Plan::Stream(node) => deserializer.deserialize_any(NodeVisitor {
+                node,
+                schema: self.schema,
+            }),
+            Plan::Materialize(validators) => {
+                let instance = Value::deserialize(deserializer)?;
+                Ok(validators
+                    .iter()
+                    .all(|validator| validator.is_valid(self.schema, &instance)))
+            }
+        }
+    }
+}
+
+/// Object key that borrows from the input whenever possible.
+struct Key<'de>(Cow<'de, str>);
+
+impl<'de> Deserialize<'de> for Key<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct KeyVisitor;
+
+        impl<'de> Visitor<'de> for KeyVisitor {
+            type Value = Key<'de>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a string")
+            }
+            fn visit_borrowed_str<E>(self, value: &'de str) -> Result<Self::Value, E> {
+                Ok(Key(Cow::Borrowed(value)))
+            }
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+                Ok(Key(Cow::Owned(value.to_string())))
+            }
+        }
+
+        deserializer.deserialize_str(KeyVisitor)
+    }
+}
+
+struct NodeVisitor<'p, 'a> {
+    node: &'p Node,
+    schema: &'p JSONSchema<'a>,
+}
+
+impl<'a> NodeVisitor<'_, 'a> {
+    fn seed<'p>(&'p self, plan: &'p Plan) -> PlanSeed<'p, 'a> {
+        PlanSeed {
+            plan,
+            schema: self.schema,
+        }
+    }
+}
+
+impl<'de> Visitor<'de> for NodeVisitor<'_, '_> {
+    type Value = bool;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a JSON value")
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<bool, E> {
+        Ok(self.node.is_valid_scalar(PrimitiveType::Boolean))
+    }
+    fn visit_i64<E>(self, value: i64) -> Result<bool, E> {
+        Ok(self.node.is_valid_number(Number::I64(value)))
+    }
+    fn visit_u64<E>(self, value: u64) -> Result<bool, E> {
+        Ok(self.node.is_valid_number(Number::U64(value)))
+    }
+    fn visit_f64<E>(self, value: f64) -> Result<bool, E> {
+        Ok(self.node.is_valid_number(Number::F64(value)))
+    }
+    fn visit_str<E>(self, value: &str) -> Result<bool, E> {
+        Ok(self.node.is_valid_string(value))
+    }
+    fn visit_unit<E>(self) -> Result<bool, E> {
+        Ok(self.node.is_valid_scalar(PrimitiveType::Null))
+    }
+    fn visit_none<E>(self) -> Result<bool, E> {
+        Ok(self.node.is_valid_scalar(PrimitiveType::Null))
+    }
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<bool, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<bool, A::Error> {
+        // The remaining items are skipped, but still consumed after the first failure
+        let mut is_valid = self.node.is_valid_scalar(PrimitiveType::Array);
+        let mut count = 0_u64;
+        loop {
+            let item = match (self.node.items(), is_valid) {
+                (Some(plan), true) => seq.next_element_seed(self.seed(plan))?,
+                _ => seq.next_element::<IgnoredAny>()?.map(|_| true),
+            };
+            match item {
+                Some(item_is_valid) => {
+                    is_valid &= item_is_valid;
+                    count += 1;
+                }
+                None => break,
+            }
+        }
+        Ok(is_valid && self.node.is_valid_items_count(count))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<bool, A::Error> {
+        if !self.node.is_valid_scalar(PrimitiveType::Object) {
+            while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+            return Ok(false);
+        }
+        // Values are validated even after a failure, since a duplicate key may replace them
+        let mut state = ObjectState::new(self.node);
+        while let Some(Key(key)) = map.next_key::<Key>()? {
+            let is_valid = match state.plan(&key) {
+                Some(plan) => map.next_value_seed(self.seed(plan))?,
+                None => map.next_value::<IgnoredAny>().map(|_| true)?,
+            };
+            state.record(&key, is_valid);
+        }
+        Ok(state.is_satisfied())
+    }
+}
+
+impl<'a> JSONSchema<'a> {
+    /// Validate a document produced by `deserializer` without building it as `Value`.
+    ///
+    /// Schemas that use only `type`, `enum` (with string options), `properties`, `required`,
+    /// `additionalProperties`, `items` (single schema) and size / length / range keywords are
+    /// checked in a single pass. Other sub-schemas are validated as usual, after materializing
+    /// only the parts of the document they apply to.
+    ///
+    /// Deserialization errors are returned as is.
+    pub fn is_valid_from<'de, D: Deserializer<'de>>(
+        &self,
+        deserializer: D,
+    ) -> Result<bool, D::Error> {
+        match self.plan() {
+            Some(plan) => PlanSeed { plan, schema: self }.deserialize(deserializer),
+            None => {
+                let instance = Value::deserialize(deserializer)?;
+                Ok(self.is_valid(&instance))
+            }
+        }
+    }
+
+    /// Validate a JSON document from raw bytes without building it as `Value`.
+    ///
+    /// ```rust
+    /// # use jsonschema::JSONSchema;
+    /// # use serde_json::json;
+    /// let schema = json!({"properties": {"name": {"maxLength": 3}}, "required": ["name"]});
+    /// let compiled = JSONSchema::compile(&schema).unwrap();
+    /// assert!(compiled.is_valid_slice(br#"{"name": "foo"}"#).unwrap());
+    /// assert!(!compiled.is_valid_slice(br#"{"name": "foobar"}"#).unwrap());
+    /// ```
+    pub fn is_valid_slice(&self, data: &[u8]) -> Result<bool, serde_json::Error> {
+        let mut deserializer = serde_json::Deserializer::from_slice(data);
+        let is_valid = self.is_valid_from(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(is_valid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Plan;
+    use crate::{schemas::Draft, JSONSchema};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn assert_consistent(schema: &Value, instance: &Value, draft: Draft) {
+        let compiled = JSONSchema::options()
+            .with_draft(draft)
+            .compile(schema)
+            .unwrap();
+        let data = serde_json::to_vec(instance).unwrap();
+        assert_eq!(
+            compiled.is_valid_slice(&data).unwrap(),
+            compiled.is_valid(instance),
+            "{} against {}",
+            instance,
+            schema
+        );
+    }
+
+    #[test_case(&json!({"type": "string", "maxLength": 3}))]
+    #[test_case(&json!({"type": ["integer", "null"], "minimum": 0, "exclusiveMaximum": 10}))]
+    #[test_case(&json!({"type": "integer"}))]
+    #[test_case(&json!({"type": ["number", "string"]}))]
+    #[test_case(&json!({"enum": ["a", "b"]}))]
+    #[test_case(&json!({"enum": [1, {"a": 1}]}))]
+    #[test_case(&json!({"minItems": 1, "maxItems": 2, "items": {"type": "number"}}))]
+    #[test_case(&json!({"items": [{"type": "string"}]}))]
+    #[test_case(&json!({"minProperties": 1, "maxProperties": 2}))]
+    #[test_case(&json!({"properties": {"a": {"type": "string"}}, "required": ["a"], "additionalProperties": false}))]
+    #[test_case(&json!({"properties": {"a": {"pattern": "^x"}}, "additionalProperties": {"type": "integer"}}))]
+    #[test_case(&json!({"patternProperties": {"^a": {"type": "string"}}, "additionalProperties": false}))]
+    #[test_case(&json!({"properties": {"a": {"$ref": "#/definitions/a"}}, "definitions": {"a": {"type": "string"}}}))]
+    #[test_case(&json!({"title": "x", "x-custom": 1, "maximum": 5, "minimum": 1.5}))]
+    #[test_case(&json!(false))]
+    fn consistent_with_is_valid(schema: &Value) {
+        let instances = [
+            json!(null),
+            json!(true),
+            json!(0),
+            json!(-3),
+            json!(1.0),
+            json!(2.5),
+            json!(42),
+            json!("a"),
+            json!("xyzw"),
+            json!([]),
+            json!([1, 2]),
+            json!([1, "a", 3]),
+            json!(["a"]),
+            json!({}),
+            json!({"a": "x"}),
+            json!({"a": 1}),
+            json!({"a": "x", "b": 2}),
+            json!({"ab": "x", "c": 1}),
+            json!({"a": 1}),
+        ];
+        for draft in &[Draft::Draft4, Draft::Draft6, Draft::Draft7] {
+            for instance in &instances {
+                assert_consistent(schema, instance, *draft)
+            }
+        }
+    }
+
+    #[test_case(&json!({"maxProperties": 1}), r#"{"a": 1, "a": 2}"#)]
+    #[test_case(&json!({"minProperties": 2}), r#"{"a": 1, "a": 2}"#)]
+    #[test_case(&json!({"properties": {"a": {"type": "integer"}}}), r#"{"a": "x", "a": 1}"#)]
+    #[test_case(&json!({"properties": {"a": {"type": "integer"}}}), r#"{"a": 1, "a": "x"}"#)]
+    #[test_case(&json!({"additionalProperties": {"type": "string"}}), r#"{"b": 1, "c": "x", "b": "y"}"#)]
+    #[test_case(&json!({"items": {"maxProperties": 1}}), r#"[{"a": 1, "a": 2}, {"b": 1}]"#)]
+    fn same_input_as_is_valid(schema: &Value, data: &str) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        let instance: Value = serde_json::from_str(data).unwrap();
+        assert_eq!(
+            compiled.is_valid_slice(data.as_bytes()).unwrap(),
+            compiled.is_valid(&instance),
+            "{} against {}",
+            data,
+            schema
+        );
+    }
+
+    #[test]
+    fn simple_schema_is_not_materialized() {
+        let schema = json!({"properties": {"a": {"type": "string"}}, "required": ["a"]});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        assert!(matches!(compiled.plan(), Some(Plan::Stream(_))));
+    }
+
+    #[test]
+    fn invalid_json() {
+        let schema = json!({});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        assert!(compiled.is_valid_slice(b"{\"a\": ").is_err());
+        assert!(compiled.is_valid_slice(b"{} 1").is_err());
+    }
+}
@@ -0,0 +1,305 @@
+//! Locations of values inside the source text of validated documents.
+use crate::{
+    compilation::JSONSchema,
+    error::ErrorIterator,
+    paths::{JSONPointer, PathChunk},
+};
+use serde_json::Value;
+use std::{collections::HashMap, fmt};
+
+/// Position inside a text document. Both line and column numbers start from 1.
+/// Columns are counted in characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Location {
+    /// Line number.
+    pub line: usize,
+    /// Column number.
+    pub column: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// Part of the source text occupied by a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    /// Byte offset of the first character of the value.
+    pub start: usize,
+    /// Byte offset right after the last character of the value.
+    pub end: usize,
+    /// Line and column of the first character of the value.
+    pub location: Location,
+}
+
+/// A parsed JSON document that remembers where each of its values is located.
+///
+/// ```rust
+/// use jsonschema::{JSONDocument, JSONSchema};
+/// use serde_json::json;
+///
+/// let schema = json!({"items": {"type": "integer"}});
+/// let document = JSONDocument::parse("[\n  1,\n  \"two\"\n]").unwrap();
+/// let compiled = JSONSchema::compile(&schema).unwrap();
+/// let result = compiled.validate_document(&document);
+/// if let Err(errors) = result {
+///     for error in errors {
+///         let span = error.span().unwrap();
+///         assert_eq!((span.start, span.end), (9, 14));
+///         assert_eq!(span.location.to_string(), "line 3, column 3");
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct JSONDocument {
+    value: Value,
+    spans: HashMap<JSONPointer, Span>,
+}
+
+impl JSONDocument {
+    /// Parse the given JSON text.
+    pub fn parse(text: &str) -> Result<JSONDocument, serde_json::Error> {
+        let value = serde_json::from_str(text)?;
+        // The text is valid JSON at this point
+        let mut scanner = Scanner::new(text.as_bytes());
+        scanner.value();
+        Ok(JSONDocument {
+            value,
+            spans: scanner.spans,
+        })
+    }
+    /// The document content.
+    #[must_use]
+    #[inline]
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+    /// The document content.
+    #[must_use]
+    #[inline]
+    pub fn into_value(self) -> Value {
+        self.value
+    }
+    /// Where the value under `path` is located in the source text.
+    #[must_use]
+    #[inline]
+    pub fn span(&self, path: &JSONPointer) -> Option<Span> {
+        self.spans.get(path).copied()
+    }
+}
+
+/// Records spans of all values by their JSON Pointers.
+struct Scanner<'t> {
+    text: &'t [u8],
+    position: usize,
+    location: Location,
+    path: Vec<PathChunk>,
+    spans: HashMap<JSONPointer, Span>,
+}
+
+impl<'t> Scanner<'t> {
+    fn new(text: &'t [u8]) -> Self {
+        Scanner {
+            text,
+            position: 0,
+            location: Location { line: 1, column: 1 },
+            path: Vec::new(),
+            spans: HashMap::new(),
+        }
+    }
+
+    #[inline]
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.position).copied()
+    }
+
+    #[inline]
+    fn bump(&mut self) {
+        if let Some(byte) = self.peek() {
+            if byte == b'\n' {
+                self.location.line += 1;
+                self.location.column = 1;
+            } else if byte & 0xC0 != 0x80 {
+                // Continuation bytes of multi-byte UTF-8 characters are not counted
+                self.location.column += 1;
+            }
+            self.position += 1;
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = self.peek() {
+            self.bump()
+        }
+    }
+
+    fn value(&mut self) {
+        self.skip_whitespace();
+        let (start, location) = (self.position, self.location);
+        match self.peek() {
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            Some(b'"') => self.string(),
+            Some(_) => self.scalar(),
+            None => return,
+        }
+        self.spans.insert(
+            JSONPointer::from(self.path.clone()),
+            Span {
+                start,
+                end: self.position,
+                location,
+            },
+        );
+    }
+
+    fn object(&mut self) {
+        self.bump();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'"') => {
+                    let start = self.position;
+                    self.string();
+                    let raw = &self.text[start..self.position];
+                    let name = if raw.contains(&b'\\') {
+                        serde_json::from_slice(raw).unwrap_or_default()
+                    } else {
+                        String::from_utf8_lossy(&raw[1..raw.len() - 1]).into_owned()
+                    };
+                    self.skip_whitespace();
+                    // `:`
+                    self.bump();
+                    self.path.push(PathChunk::Property(name));
+                    self.value();
+                    self.path.pop();
+                    self.skip_whitespace();
+                    if self.peek() == Some(b',') {
+                        self.bump()
+                    }
+                }
+                Some(b'}') => return self.bump(),
+                _ => return,
+            }
+        }
+    }
+
+    fn array(&mut self) {
+        self.bump();
+        let mut index = 0;
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b']') => return self.bump(),
+                None => return,
+                Some(_) => {
+                    let position = self.position;
+                    self.path.push(PathChunk::Index(index));
+                    self.value();
+                    self.path.pop();
+                    if self.position == position {
+                        return;
+                    }
+                    index += 1;
+                    self.skip_whitespace();
+                    if self.peek() == Some(b',') {
+                        self.bump()
+                    }
+                }
+            }
+        }
+    }
+
+    fn string(&mut self) {
+        self.bump();
+        while let Some(byte) = self.peek() {
+            self.bump();
+            match byte {
+                b'\\' => self.bump(),
+                b'"' => return,
+                _ => {}
+            }
+        }
+    }
+
+    fn scalar(&mut self) {
+        while let Some(byte) = self.peek() {
+            if matches!(byte, b',' | b']' | b'}' | b' ' | b'\t' | b'\n' | b'\r') {
+                return;
+            }
+            self.bump()
+        }
+    }
+}
+
+impl<'a> JSONSchema<'a> {
+    /// Run validation against a parsed `JSONDocument` and return an iterator over
+    /// `ValidationError` in the error case. Each error carries the span of the invalid value.
+    pub fn validate_document(
+        &'a self,
+        document: &'a JSONDocument,
+    ) -> Result<(), ErrorIterator<'a>> {
+        self.validate(&document.value)
+            .map_err(|errors| -> ErrorIterator<'a> {
+                Box::new(errors.map(move |error| {
+                    let span = document.span(&error.instance_path());
+                    error.with_span(span)
+                }))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JSONDocument, Location, Span};
+    use crate::{paths::PathChunk, JSONPointer, JSONSchema};
+    use serde_json::json;
+    use test_case::test_case;
+
+    const DOCUMENT: &str = "{\n  \"name\": \"app\",\n  \"ports\": [80, {\"port\": 443}],\n  \"a\\\"b\": \"ключ\", \"c\": null\n}";
+
+    #[test_case(vec![], 0, 84, 1, 1)]
+    #[test_case(vec!["name".into()], 12, 17, 2, 11)]
+    #[test_case(vec!["ports".into()], 30, 49, 3, 12)]
+    #[test_case(vec!["ports".into(), 1.into(), "port".into()], 44, 47, 3, 26)]
+    #[test_case(vec!["a\"b".into()], 61, 71, 4, 11)]
+    #[test_case(vec!["c".into()], 78, 82, 4, 24)]
+    fn spans(path: Vec<PathChunk>, start: usize, end: usize, line: usize, column: usize) {
+        let document = JSONDocument::parse(DOCUMENT).unwrap();
+        let span = document.span(&JSONPointer::from(path)).unwrap();
+        assert_eq!(&DOCUMENT[span.start..span.end], &DOCUMENT[start..end]);
+        assert_eq!(
+            span,
+            Span {
+                start,
+                end,
+                location: Location { line, column }
+            }
+        );
+    }
+
+    #[test]
+    fn errors_carry_spans() {
+        let schema = json!({"properties": {"ports": {"items": {"type": "integer"}}}});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let document = JSONDocument::parse(DOCUMENT).unwrap();
+        let result = compiled.validate_document(&document);
+        let spans: Vec<_> = result
+            .expect_err("The document is invalid")
+            .map(|error| error.span().map(|span| span.location))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![Some(Location {
+                line: 3,
+                column: 17
+            })]
+        );
+        assert!(compiled
+            .validate(document.value())
+            .expect_err("The document is invalid")
+            .all(|error| error.span().is_none()));
+    }
+}
@@ -0,0 +1,553 @@
+//! `proptest` strategies producing instances that are valid against a schema.
+//!
+//! Strategies are composed from `proptest` combinators following the keywords that apply to
+//! each location, so shrinking works on the structure of instances: optional properties are
+//! removed, arrays get shorter and numbers get closer to their lower limits.
+use crate::{
+    compilation::JSONSchema,
+    error::ValidationError,
+    generator::{
+        candidate_types, contains, format_sample, get, integers, limits, multiple_of,
+        parse_pattern, property_names, size_limits, subschema, with_defaults, ALPHABET, MAX_DEPTH,
+    },
+    primitive_type::PrimitiveType,
+    schemas::Draft,
+    subschemas::{self, collect, Result, Source},
+};
+use proptest::{
+    collection, option,
+    prelude::*,
+    sample,
+    strategy::{BoxedStrategy, Just, Union},
+};
+use regex_syntax::hir::{Class, Hir, HirKind};
+use serde_json::{Map, Value};
+use std::{borrow::Cow, convert::TryFrom, fmt};
+
+/// How many combinations of `anyOf`, `oneOf` and `if` branches are considered at one location.
+const MAX_ALTERNATIVES: usize = 16;
+/// Recursive schemas are not expanded further once they include that many subschemas.
+const MAX_SOURCES: usize = 64;
+
+fn union<T: fmt::Debug + 'static>(strategies: Vec<BoxedStrategy<T>>) -> Option<BoxedStrategy<T>> {
+    if strategies.len() > 1 {
+        Some(Union::new(strategies).boxed())
+    } else {
+        strategies.into_iter().next()
+    }
+}
+
+/// Sets of subschemas to add for every combination of `anyOf`, `oneOf` and `if` branches.
+fn choices<'s>(draft: Draft, schema: &Cow<'s, Value>) -> Vec<Vec<Cow<'s, Value>>> {
+    let mut choices = vec![vec![]];
+    for keyword in &["anyOf", "oneOf"] {
+        if let Some(Value::Array(branches)) = get(draft, schema, keyword) {
+            let mut next = vec![];
+            for choice in &choices {
+                for index in 0..branches.len() {
+                    let mut extended = choice.clone();
+                    extended.extend(subschema(schema, keyword, Some(index)));
+                    next.push(extended)
+                }
+            }
+            choices = next;
+        }
+    }
+    if get(draft, schema, "if").is_some() {
+        let mut next = vec![];
+        for choice in &choices {
+            let mut then = choice.clone();
+            then.extend(subschema(schema, "if", None));
+            then.extend(subschema(schema, "then", None));
+            next.push(then);
+            let mut otherwise = choice.clone();
+            otherwise.extend(subschema(schema, "else", None));
+            next.push(otherwise);
+        }
+        choices = next;
+    }
+    choices.truncate(MAX_ALTERNATIVES);
+    choices
+}
+
+/// Expand `sources`, starting from `start`, into sets of subschemas without branching keywords.
+fn alternatives<'s>(
+    compiled: &'s JSONSchema,
+    sources: Vec<Source<'s>>,
+    start: usize,
+    output: &mut Vec<Vec<Source<'s>>>,
+) -> Result<()> {
+    let draft = compiled.context.config.draft();
+    if sources.len() <= MAX_SOURCES {
+        for index in start..sources.len() {
+            let choices = choices(draft, &sources[index].schema);
+            if choices.len() == 1 && choices[0].is_empty() {
+                continue;
+            }
+            for choice in choices {
+                if output.len() >= MAX_ALTERNATIVES {
+                    break;
+                }
+                let mut extended = sources.clone();
+                let scope = sources[index].scope.clone();
+                for schema in choice {
+                    collect(compiled, schema, &scope, &mut extended, &mut vec![])?;
+                }
+                alternatives(compiled, extended, index + 1, output)?;
+            }
+            return Ok(());
+        }
+    }
+    output.push(sources);
+    Ok(())
+}
+
+fn build(
+    compiled: &JSONSchema,
+    sources: Vec<Source<'_>>,
+    depth: usize,
+) -> Result<Option<BoxedStrategy<Value>>> {
+    if depth > MAX_DEPTH * 2 {
+        return Ok(None);
+    }
+    let mut sets = vec![];
+    alternatives(compiled, sources, 0, &mut sets)?;
+    let mut strategies = vec![];
+    for sources in &sets {
+        strategies.extend(single(compiled, sources, depth)?)
+    }
+    Ok(union(strategies))
+}
+
+/// A strategy for a set of subschemas without branching keywords.
+fn single(
+    compiled: &JSONSchema,
+    sources: &[Source<'_>],
+    depth: usize,
+) -> Result<Option<BoxedStrategy<Value>>> {
+    let draft = compiled.context.config.draft();
+    if sources
+        .iter()
+        .any(|source| *source.schema == Value::Bool(false))
+    {
+        return Ok(None);
+    }
+    for source in sources {
+        if let Some(value) = get(draft, &source.schema, "const") {
+            return Ok(Some(Just(value.clone()).boxed()));
+        }
+    }
+    for source in sources {
+        if let Some(Value::Array(options)) = get(draft, &source.schema, "enum") {
+            if options.is_empty() {
+                return Ok(None);
+            }
+            return Ok(Some(sample::select(options.clone()).boxed()));
+        }
+    }
+    let mut strategies = vec![];
+    for primitive_type in candidate_types(sources) {
+        strategies.extend(match primitive_type {
+            PrimitiveType::Null => Some(Just(Value::Null).boxed()),
+            PrimitiveType::Boolean => Some(any::<bool>().prop_map(Value::Bool).boxed()),
+            PrimitiveType::Integer => integer(draft, sources),
+            PrimitiveType::Number => number(draft, sources),
+            PrimitiveType::String => string(draft, sources),
+            PrimitiveType::Array => array(compiled, sources, depth)?,
+            PrimitiveType::Object => object(compiled, sources, depth)?,
+        })
+    }
+    Ok(union(strategies))
+}
+
+fn integer(draft: Draft, sources: &[Source<'_>]) -> Option<BoxedStrategy<Value>> {
+    let integers = integers(draft, sources)?;
+    Some(
+        (integers.low..=integers.high)
+            .prop_map(move |factor| integers.value(factor))
+            .boxed(),
+    )
+}
+
+fn number(draft: Draft, sources: &[Source<'_>]) -> Option<BoxedStrategy<Value>> {
+    let integer = integer(draft, sources);
+    if multiple_of(draft, sources).is_some() {
+        return integer;
+    }
+    let (lower, upper) = limits(draft, sources);
+    let (low, high) = with_defaults(lower.map(|(value, _)| value), upper.map(|(value, _)| value));
+    if low > high || !low.is_finite() || !high.is_finite() {
+        return integer;
+    }
+    let number = (low..=high).prop_map(Value::from).boxed();
+    union(integer.into_iter().chain(Some(number)).collect())
+}
+
+/// Characters of a class, preferring printable ASCII ones.
+fn class_chars(ranges: impl Iterator<Item = (u32, u32)> + Clone) -> Vec<char> {
+    let printable: Vec<char> = ranges
+        .clone()
+        .flat_map(|(start, end)| start.max(0x21)..=end.min(0x7E))
+        .filter_map(char::from_u32)
+        .collect();
+    if printable.is_empty() {
+        ranges
+            .flat_map(|(start, end)| start..=end)
+            .filter_map(char::from_u32)
+            .take(32)
+            .collect()
+    } else {
+        printable
+    }
+}
+
+/// A strategy for strings matching the regular expression. Unbounded repetitions are repeated
+/// at most `extra` times more than required.
+fn from_hir(hir: &Hir, extra: u32) -> BoxedStrategy<String> {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => Just(String::new()).boxed(),
+        HirKind::Literal(literal) => Just(String::from_utf8_lossy(&literal.0).into_owned()).boxed(),
+        HirKind::Class(class) => {
+            let chars = match class {
+                Class::Unicode(class) => class_chars(
+                    class
+                        .ranges()
+                        .iter()
+                        .map(|range| (u32::from(range.start()), u32::from(range.end()))),
+                ),
+                Class::Bytes(class) => class_chars(
+                    class
+                        .ranges()
+                        .iter()
+                        .map(|range| (u32::from(range.start()), u32::from(range.end()))),
+                ),
+            };
+            if chars.is_empty() {
+                Just(String::new()).boxed()
+            } else {
+                sample::select(chars).prop_map(String::from).boxed()
+            }
+        }
+        HirKind::Repetition(repetition) => {
+            let max = repetition
+                .max
+                .unwrap_or_else(|| repetition.min.saturating_add(extra));
+            collection::vec(
+                from_hir(&repetition.sub, extra),
+                repetition.min as usize..=max as usize,
+            )
+            .prop_map(|parts| parts.concat())
+            .boxed()
+        }
+        HirKind::Capture(capture) => from_hir(&capture.sub, extra),
+        HirKind::Concat(items) => items
+            .iter()
+            .map(|item| from_hir(item, extra))
+            .collect::<Vec<_>>()
+            .prop_map(|parts| parts.concat())
+            .boxed(),
+        HirKind::Alternation(items) => {
+            Union::new(items.iter().map(|item| from_hir(item, extra))).boxed()
+        }
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn string(draft: Draft, sources: &[Source<'_>]) -> Option<BoxedStrategy<Value>> {
+    let (min_length, max_length) = size_limits(draft, sources, "minLength", "maxLength");
+    for source in sources {
+        if let Some(Value::String(format)) = get(draft, &source.schema, "format") {
+            let samples: Vec<_> = (1..=5)
+                .filter_map(|number| format_sample(format, number))
+                .map(Value::String)
+                .collect();
+            if !samples.is_empty() {
+                return Some(sample::select(samples).boxed());
+            }
+        }
+    }
+    for source in sources {
+        if let Some(hir) = get(draft, &source.schema, "pattern")
+            .and_then(Value::as_str)
+            .and_then(parse_pattern)
+        {
+            let extra = u32::try_from(min_length).unwrap_or(u32::MAX).max(3);
+            return Some(from_hir(&hir, extra).prop_map(Value::String).boxed());
+        }
+    }
+    let high = max_length.map_or(min_length + 8, |max| max.min(min_length + 8));
+    if min_length > high {
+        return None;
+    }
+    let alphabet: Vec<char> = ALPHABET.iter().map(|byte| char::from(*byte)).collect();
+    Some(
+        collection::vec(
+            sample::select(alphabet),
+            min_length as usize..=high as usize,
+        )
+        .prop_map(|chars| Value::String(chars.into_iter().collect()))
+        .boxed(),
+    )
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn array(
+    compiled: &JSONSchema,
+    sources: &[Source<'_>],
+    depth: usize,
+) -> Result<Option<BoxedStrategy<Value>>> {
+    let draft = compiled.context.config.draft();
+    let (min_items, max_items) = size_limits(draft, sources, "minItems", "maxItems");
+    let contains = contains(draft, sources);
+    let low = if contains.is_empty() {
+        min_items
+    } else {
+        min_items.max(1)
+    } as usize;
+    let mut high = if depth >= MAX_DEPTH { low } else { low + 3 }
+        .min(max_items.map_or(usize::MAX, |max| max as usize));
+    // Items that may have different schemas: from `items` arrays and the first item, which
+    // satisfies `contains`
+    let fixed = sources
+        .iter()
+        .filter_map(|source| match get(draft, &source.schema, "items") {
+            Some(Value::Array(items)) => Some(items.len()),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0)
+        .max(if contains.is_empty() { 0 } else { 1 });
+    let mut head = vec![];
+    for index in 0..fixed.min(high) {
+        let mut children = subschemas::item(compiled, sources, index)?;
+        if index == 0 {
+            for (schema, scope) in &contains {
+                collect(
+                    compiled,
+                    Cow::Borrowed(*schema),
+                    scope,
+                    &mut children,
+                    &mut vec![],
+                )?;
+            }
+        }
+        match build(compiled, children, depth + 1)? {
+            Some(strategy) => head.push(strategy),
+            None => {
+                high = index;
+                break;
+            }
+        }
+    }
+    let rest = if high > fixed {
+        let children = subschemas::item(compiled, sources, fixed)?;
+        build(compiled, children, depth + 1)?
+    } else {
+        None
+    };
+    let rest = match rest {
+        Some(rest) => rest,
+        None => {
+            high = high.min(fixed);
+            Just(Value::Null).boxed()
+        }
+    };
+    if low > high {
+        return Ok(None);
+    }
+    Ok(Some(
+        (low..=high)
+            .prop_flat_map(move |length| {
+                let head = head[..length.min(head.len())].to_vec();
+                let tail = collection::vec(rest.clone(), length.saturating_sub(head.len()));
+                (head, tail)
+            })
+            .prop_map(|(mut head, tail)| {
+                head.extend(tail);
+                Value::Array(head)
+            })
+            .boxed(),
+    ))
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn object(
+    compiled: &JSONSchema,
+    sources: &[Source<'_>],
+    depth: usize,
+) -> Result<Option<BoxedStrategy<Value>>> {
+    let draft = compiled.context.config.draft();
+    let (required, optional) = property_names(draft, sources);
+    let (min_properties, max_properties) =
+        size_limits(draft, sources, "minProperties", "maxProperties");
+    let mut required: Vec<String> = required.iter().map(|name| (*name).to_string()).collect();
+    let mut optional: Vec<String> = optional.iter().map(|name| (*name).to_string()).collect();
+    // Objects with fewer properties are made larger with optional and then arbitrary ones
+    while (required.len() as u64) < min_properties {
+        let name = if optional.is_empty() {
+            format!("property{}", required.len())
+        } else {
+            optional.remove(0)
+        };
+        required.push(name)
+    }
+    // Properties required by the presence of required properties
+    let mut index = 0;
+    while index < required.len() {
+        for source in sources {
+            if let Some(Value::Array(dependencies)) = get(draft, &source.schema, "dependencies")
+                .and_then(|dependencies| dependencies.get(&required[index]))
+            {
+                for dependency in dependencies.iter().filter_map(Value::as_str) {
+                    if !required.iter().any(|name| name == dependency) {
+                        optional.retain(|name| name != dependency);
+                        required.push(dependency.to_string())
+                    }
+                }
+            }
+        }
+        index += 1;
+    }
+    if depth >= MAX_DEPTH {
+        optional.clear()
+    }
+    if let Some(max_properties) = max_properties {
+        optional.truncate((max_properties as usize).saturating_sub(required.len()))
+    }
+    let mut entries: Vec<BoxedStrategy<Option<(String, Value)>>> = vec![];
+    for name in required {
+        let children = subschemas::property(compiled, sources, &name)?;
+        match build(compiled, children, depth + 1)? {
+            Some(strategy) => entries.push(
+                strategy
+                    .prop_map(move |value| Some((name.clone(), value)))
+                    .boxed(),
+            ),
+            None => return Ok(None),
+        }
+    }
+    for name in optional {
+        let children = subschemas::property(compiled, sources, &name)?;
+        if let Some(strategy) = build(compiled, children, depth + 1)? {
+            entries.push(option::of(strategy.prop_map(move |value| (name.clone(), value))).boxed())
+        }
+    }
+    Ok(Some(
+        entries
+            .prop_map(|entries| Value::Object(entries.into_iter().flatten().collect::<Map<_, _>>()))
+            .boxed(),
+    ))
+}
+
+/// Keep only values that are valid against the schema. It also applies to shrunk values.
+fn valid<'s>(
+    compiled: &'s JSONSchema<'s>,
+    strategy: BoxedStrategy<Value>,
+) -> impl Strategy<Value = Value> + 's {
+    strategy.prop_filter("Values should be valid against the schema", move |value| {
+        compiled.is_valid(value)
+    })
+}
+
+impl<'a> JSONSchema<'a> {
+    /// A `proptest` strategy producing instances that are valid against the schema.
+    ///
+    /// Strategies follow the same keywords as `Generator`. Every produced value, including
+    /// shrunk ones, is checked with `JSONSchema::is_valid`, therefore other keywords are
+    /// respected too, at the cost of rejected values. Strategies for unsatisfiable schemas
+    /// reject all values.
+    ///
+    /// ```rust
+    /// use jsonschema::JSONSchema;
+    /// use proptest::test_runner::TestRunner;
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({"type": "array", "items": {"type": "integer", "minimum": 1}});
+    /// let compiled = JSONSchema::compile(&schema).unwrap();
+    /// let strategy = compiled.strategy().unwrap();
+    /// TestRunner::default()
+    ///     .run(&strategy, |value| {
+    ///         assert!(value.as_array().unwrap().iter().all(|item| item.as_i64() >= Some(1)));
+    ///         Ok(())
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn strategy(
+        &self,
+    ) -> std::result::Result<impl Strategy<Value = Value> + '_, ValidationError<'static>> {
+        let strategy =
+            build(self, subschemas::root(self)?, 0)?.unwrap_or_else(|| Just(Value::Null).boxed());
+        Ok(valid(self, strategy))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build;
+    use crate::{subschemas, JSONSchema};
+    use proptest::{
+        prop_assert,
+        test_runner::{TestError, TestRunner},
+    };
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(&json!({"type": "integer", "minimum": 10, "exclusiveMaximum": 13}); "integer range")]
+    #[test_case(&json!({"type": "number", "multipleOf": 0.5, "maximum": 3}); "multiple of")]
+    #[test_case(&json!({"type": "string", "pattern": "^[A-Z]{2}-\\d{3,5}$"}); "pattern")]
+    #[test_case(&json!({"type": "string", "minLength": 2, "maxLength": 4}); "string length")]
+    #[test_case(&json!({"type": "string", "format": "email"}); "format")]
+    #[test_case(&json!({"enum": [1, "a", null]}); "enum values")]
+    #[test_case(&json!({"type": "array", "items": [{"type": "boolean"}], "additionalItems": {"type": "null"}, "contains": {"const": true}}); "tuple")]
+    #[test_case(
+        &json!({
+            "type": "object",
+            "properties": {"id": {"type": "integer"}, "name": {"type": "string"}, "tags": {"type": "array"}},
+            "required": ["id"],
+            "additionalProperties": false,
+            "minProperties": 2
+        });
+        "object"
+    )]
+    #[test_case(
+        &json!({
+            "definitions": {"node": {"type": "object", "properties": {"children": {"type": "array", "items": {"$ref": "#/definitions/node"}}}}},
+            "$ref": "#/definitions/node"
+        });
+        "recursive references"
+    )]
+    #[test_case(&json!({"oneOf": [{"type": "string"}, {"type": "integer", "minimum": 0}]}); "one of")]
+    #[test_case(&json!({"if": {"type": "integer"}, "then": {"minimum": 5}, "else": {"type": "boolean"}}); "conditionals")]
+    fn round_trip(schema: &Value) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        // Values that `JSONSchema::strategy` would reject are failures of the generator itself
+        let strategy = build(&compiled, subschemas::root(&compiled).unwrap(), 0)
+            .unwrap()
+            .unwrap();
+        TestRunner::deterministic()
+            .run(&strategy, |value| {
+                prop_assert!(compiled.is_valid(&value), "{} should be valid", value);
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn shrunk_values_are_valid() {
+        let schema =
+            json!({"type": "array", "items": {"type": "integer", "minimum": 5}, "minItems": 1});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let strategy = compiled.strategy().unwrap();
+        let result = TestRunner::deterministic().run(&strategy, |value| {
+            prop_assert!(value.as_array().unwrap().len() < 3);
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(_, value)) => {
+                assert!(compiled.is_valid(&value));
+                assert!(value.as_array().unwrap().len() >= 3);
+            }
+            _ => panic!("The property should fail"),
+        }
+    }
+}
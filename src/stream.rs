@@ -0,0 +1,431 @@
+//! Validation of large streams of JSON documents.
+//!
+//! Records are read in batches of a bounded size, then every batch is parsed and validated in
+//! parallel. Only one batch is kept in memory at a time.
+use crate::{compilation::JSONSchema, error::ValidationError};
+use rayon::prelude::*;
+use serde_json::Value;
+use std::{
+    collections::VecDeque,
+    error, fmt,
+    io::{self, BufRead, BufReader, Read},
+};
+
+const DEFAULT_BATCH_SIZE: usize = 1024;
+
+/// The outcome of validating a single record from a stream.
+#[derive(Debug)]
+pub struct RecordResult {
+    /// Position of the record in the stream.
+    /// It is the zero-based line number for JSON Lines and the item index for arrays.
+    pub index: usize,
+    /// Validation errors for the record. Empty if the record is valid.
+    pub errors: Vec<ValidationError<'static>>,
+}
+
+impl RecordResult {
+    /// Whether the record is valid.
+    #[must_use]
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// An error that prevents a record from being validated.
+#[derive(Debug)]
+pub enum StreamError {
+    /// The underlying reader failed. The stream ends after this error.
+    Io(io::Error),
+    /// The record is not a valid JSON document. Other records are still validated.
+    Parse {
+        /// Position of the record in the stream.
+        index: usize,
+        /// The underlying parsing error.
+        error: serde_json::Error,
+    },
+}
+
+impl error::Error for StreamError {}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(error) => write!(f, "{}", error),
+            StreamError::Parse { index, error } => write!(f, "Record {}: {}", index, error),
+        }
+    }
+}
+
+/// A source of raw records together with their positions in the stream.
+trait RecordSource {
+    fn next_record(&mut self) -> Option<io::Result<(usize, Vec<u8>)>>;
+}
+
+/// Non-empty lines of newline-delimited JSON.
+struct JSONLines<R> {
+    reader: R,
+    line: usize,
+}
+
+impl<R: BufRead> RecordSource for JSONLines<R> {
+    fn next_record(&mut self) -> Option<io::Result<(usize, Vec<u8>)>> {
+        loop {
+            let mut buffer = Vec::new();
+            match self.reader.read_until(b'\n', &mut buffer) {
+                Ok(0) => return None,
+                Ok(_) => {
+                    let line = self.line;
+                    self.line += 1;
+                    if !buffer.iter().all(u8::is_ascii_whitespace) {
+                        return Some(Ok((line, buffer)));
+                    }
+                }
+                Err(error) => return Some(Err(error)),
+            }
+        }
+    }
+}
+
+enum ArrayState {
+    Start,
+    Items,
+    /// The closing bracket is read, only whitespace may follow it
+    Closed,
+    Finished,
+}
+
+/// Items of a top-level JSON array.
+///
+/// Item boundaries are found by tracking the nesting depth and string literals; the items
+/// themselves are parsed later, together with the rest of their batch.
+struct JSONArray<R> {
+    bytes: io::Bytes<BufReader<R>>,
+    state: ArrayState,
+    index: usize,
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+impl<R: Read> JSONArray<R> {
+    fn next_byte(&mut self) -> io::Result<Option<u8>> {
+        self.bytes.next().transpose()
+    }
+
+    fn next_significant_byte(&mut self) -> io::Result<Option<u8>> {
+        while let Some(byte) = self.next_byte()? {
+            if !byte.is_ascii_whitespace() {
+                return Ok(Some(byte));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Read the next item. Returns `None` when the closing bracket is reached.
+    fn read_item(&mut self) -> io::Result<Option<Vec<u8>>> {
+        if let ArrayState::Start = self.state {
+            match self.next_significant_byte()? {
+                Some(b'[') => {}
+                _ => return Err(invalid_data("Expected a JSON array")),
+            }
+            self.state = ArrayState::Items;
+            // Empty array
+            match self.next_significant_byte()? {
+                Some(b']') => Ok(None),
+                Some(byte) => self.read_item_from(byte).map(Some),
+                None => Err(invalid_data("Unterminated JSON array")),
+            }
+        } else {
+            match self.next_significant_byte()? {
+                Some(byte) => self.read_item_from(byte).map(Some),
+                None => Err(invalid_data("Unterminated JSON array")),
+            }
+        }
+    }
+
+    /// Ensure that nothing but whitespace follows the closing bracket.
+    fn read_end(&mut self) -> io::Result<()> {
+        self.state = ArrayState::Finished;
+        match self.next_significant_byte()? {
+            Some(_) => Err(invalid_data("Unexpected data after the JSON array")),
+            None => Ok(()),
+        }
+    }
+
+    /// Collect bytes of an item that starts with `first` up to the next top-level `,` or `]`.
+    fn read_item_from(&mut self, first: u8) -> io::Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        let mut depth = 0_usize;
+        let mut in_string = false;
+        let mut escaped = false;
+        let mut byte = first;
+        loop {
+            if in_string {
+                if escaped {
+                    escaped = false;
+                } else if byte == b'\\' {
+                    escaped = true;
+                } else if byte == b'"' {
+                    in_string = false;
+                }
+            } else {
+                match byte {
+                    b'"' => in_string = true,
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' if depth > 0 => depth -= 1,
+                    b']' => {
+                        self.state = ArrayState::Closed;
+                        return Ok(buffer);
+                    }
+                    b',' if depth == 0 => return Ok(buffer),
+                    _ => {}
+                }
+            }
+            buffer.push(byte);
+            byte = match self.next_byte()? {
+                Some(byte) => byte,
+                None => return Err(invalid_data("Unterminated JSON array")),
+            };
+        }
+    }
+}
+
+impl<R: Read> RecordSource for JSONArray<R> {
+    fn next_record(&mut self) -> Option<io::Result<(usize, Vec<u8>)>> {
+        match self.state {
+            ArrayState::Finished => return None,
+            ArrayState::Closed => return self.read_end().err().map(Err),
+            _ => {}
+        }
+        match self.read_item() {
+            Ok(Some(item)) => {
+                let index = self.index;
+                self.index += 1;
+                Some(Ok((index, item)))
+            }
+            Ok(None) => self.read_end().err().map(Err),
+            Err(error) => {
+                self.state = ArrayState::Finished;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+/// An iterator over validation results of records read from a stream.
+///
+/// Created by `JSONSchema::validate_lines` and `JSONSchema::validate_array`.
+/// Results are yielded in the same order as records appear in the stream.
+pub struct RecordStream<'s, 'a> {
+    schema: &'s JSONSchema<'a>,
+    source: Box<dyn RecordSource + 's>,
+    batch_size: usize,
+    pending: VecDeque<Result<RecordResult, StreamError>>,
+    finished: bool,
+}
+
+impl fmt::Debug for RecordStream<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecordStream")
+            .field("batch_size", &self.batch_size)
+            .field("pending", &self.pending.len())
+            .field("finished", &self.finished)
+            .finish()
+    }
+}
+
+impl<'s, 'a> RecordStream<'s, 'a> {
+    fn new(schema: &'s JSONSchema<'a>, source: Box<dyn RecordSource + 's>) -> Self {
+        RecordStream {
+            schema,
+            source,
+            batch_size: DEFAULT_BATCH_SIZE,
+            pending: VecDeque::new(),
+            finished: false,
+        }
+    }
+
+    /// Set how many records are read and validated at once (1024 by default).
+    /// It bounds the number of records kept in memory.
+    #[must_use]
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    fn read_batch(&mut self) {
+        let mut batch = Vec::with_capacity(self.batch_size);
+        let mut failure = None;
+        while batch.len() < self.batch_size {
+            match self.source.next_record() {
+                Some(Ok(record)) => batch.push(record),
+                Some(Err(error)) => {
+                    failure = Some(error);
+                    break;
+                }
+                None => {
+                    self.finished = true;
+                    break;
+                }
+            }
+        }
+        let schema = self.schema;
+        let results: Vec<_> = batch
+            .into_par_iter()
+            .map(|(index, record)| validate_record(schema, index, &record))
+            .collect();
+        self.pending.extend(results);
+        if let Some(error) = failure {
+            self.finished = true;
+            self.pending.push_back(Err(StreamError::Io(error)));
+        }
+    }
+}
+
+fn validate_record(
+    schema: &JSONSchema,
+    index: usize,
+    record: &[u8],
+) -> Result<RecordResult, StreamError> {
+    let instance: Value =
+        serde_json::from_slice(record).map_err(|error| StreamError::Parse { index, error })?;
+    let errors = match schema.validate(&instance) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors.map(ValidationError::into_owned).collect(),
+    };
+    Ok(RecordResult { index, errors })
+}
+
+impl Iterator for RecordStream<'_, '_> {
+    type Item = Result<RecordResult, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pending.is_empty() && !self.finished {
+            self.read_batch();
+        }
+        self.pending.pop_front()
+    }
+}
+
+impl<'a> JSONSchema<'a> {
+    /// Validate every line of newline-delimited JSON (JSON Lines) read from `reader`.
+    ///
+    /// Blank lines are skipped. Lines that are not valid JSON are reported as
+    /// `StreamError::Parse` without interrupting the stream.
+    ///
+    /// ```rust
+    /// # use jsonschema::JSONSchema;
+    /// # use serde_json::json;
+    /// let schema = json!({"type": "integer"});
+    /// let compiled = JSONSchema::compile(&schema).unwrap();
+    /// let input = "1\n\"foo\"\n3\n";
+    /// let invalid: Vec<usize> = compiled
+    ///     .validate_lines(input.as_bytes())
+    ///     .filter_map(Result::ok)
+    ///     .filter(|record| !record.is_valid())
+    ///     .map(|record| record.index)
+    ///     .collect();
+    /// assert_eq!(invalid, vec![1]);
+    /// ```
+    pub fn validate_lines<'s, R: BufRead + 's>(&'s self, reader: R) -> RecordStream<'s, 'a> {
+        RecordStream::new(self, Box::new(JSONLines { reader, line: 0 }))
+    }
+
+    /// Validate every item of a top-level JSON array read from `reader`.
+    ///
+    /// Items are read one by one, so the whole array never has to fit in memory.
+    /// The stream ends with `StreamError::Io` if the input is not a well-formed array.
+    pub fn validate_array<'s, R: Read + 's>(&'s self, reader: R) -> RecordStream<'s, 'a> {
+        RecordStream::new(
+            self,
+            Box::new(JSONArray {
+                bytes: BufReader::new(reader).bytes(),
+                state: ArrayState::Start,
+                index: 0,
+            }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StreamError;
+    use crate::JSONSchema;
+    use serde_json::json;
+    use test_case::test_case;
+
+    fn invalid_indices(results: Vec<Result<super::RecordResult, StreamError>>) -> Vec<usize> {
+        results
+            .into_iter()
+            .map(Result::unwrap)
+            .filter(|record| !record.is_valid())
+            .map(|record| record.index)
+            .collect()
+    }
+
+    #[test_case("1\n\"a\"\n\n3\n[]", 1, &[1, 4])]
+    #[test_case("1\n\"a\"\n\n3\n[]", 2, &[1, 4]; "batches")]
+    #[test_case("", 1, &[])]
+    fn json_lines(input: &str, batch_size: usize, expected: &[usize]) {
+        let schema = json!({"type": "integer"});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let results: Vec<_> = compiled
+            .validate_lines(input.as_bytes())
+            .with_batch_size(batch_size)
+            .collect();
+        assert_eq!(invalid_indices(results), expected);
+    }
+
+    #[test]
+    fn json_lines_parse_error() {
+        let schema = json!({"type": "integer"});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let results: Vec<_> = compiled.validate_lines("1\n{\n3".as_bytes()).collect();
+        assert_eq!(results.len(), 3);
+        assert!(matches!(
+            results[1],
+            Err(StreamError::Parse { index: 1, .. })
+        ));
+        assert!(results[2].as_ref().unwrap().is_valid());
+    }
+
+    #[test_case(" [1, \"a\", {\"b\": [\"],\"]}, 4] ", 1, &[1, 2])]
+    #[test_case("[1, \"a\", {\"b\": [\"],\"]}, 4]", 3, &[1, 2]; "batches")]
+    #[test_case("[]", 1, &[])]
+    #[test_case("[\"\\\"]\"]", 1, &[0]; "escaped quote")]
+    fn array(input: &str, batch_size: usize, expected: &[usize]) {
+        let schema = json!({"type": "integer"});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let results: Vec<_> = compiled
+            .validate_array(input.as_bytes())
+            .with_batch_size(batch_size)
+            .collect();
+        assert_eq!(invalid_indices(results), expected);
+    }
+
+    #[test]
+    fn errors_are_owned() {
+        let schema = json!({"items": {"type": "integer"}});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let record = compiled
+            .validate_array("[[1, \"a\"]]".as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.errors.len(), 1);
+        assert_eq!(record.errors[0].instance_path().to_string(), "/1");
+    }
+
+    #[test_case("{}"; "object")]
+    #[test_case("[1, 2"; "unterminated array")]
+    #[test_case("[1, 2] 3"; "trailing data")]
+    #[test_case("[] ]"; "trailing data after empty array")]
+    fn malformed_array(input: &str) {
+        let schema = json!({});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let results: Vec<_> = compiled.validate_array(input.as_bytes()).collect();
+        assert!(matches!(results.last(), Some(Err(StreamError::Io(_)))));
+    }
+}
@@ -0,0 +1,173 @@
+//! Schemas that apply to locations inside an instance.
+//! Used by transformations of instances, e.g. filling defaults.
+use crate::{
+    compilation::JSONSchema,
+    error::ValidationError,
+    keywords::pattern::convert_regex,
+    schemas::{id_of, subschemas},
+};
+use once_cell::sync::OnceCell;
+use regex::Regex;
+use serde_json::Value;
+use std::borrow::Cow;
+use url::Url;
+
+/// A schema that applies to the current location in the instance, with its base URL.
+#[derive(Clone)]
+pub(crate) struct Source<'s> {
+    pub(crate) schema: Cow<'s, Value>,
+    pub(crate) scope: Url,
+    /// Compiled `patternProperties` in the document order, `None` for invalid patterns.
+    patterns: OnceCell<Vec<Option<Regex>>>,
+}
+
+impl Source<'_> {
+    fn patterns(&self) -> &[Option<Regex>] {
+        self.patterns
+            .get_or_init(|| match self.schema.get("patternProperties") {
+                Some(Value::Object(patterns)) => patterns
+                    .keys()
+                    .map(|pattern| convert_regex(pattern).ok())
+                    .collect(),
+                _ => vec![],
+            })
+    }
+}
+
+pub(crate) type Result<T> = std::result::Result<T, ValidationError<'static>>;
+
+/// Collect `schema` and all schemas it includes via `$ref` and `allOf`, in this order.
+/// Every reference is followed at most once, which makes recursive references finite.
+pub(crate) fn collect<'s>(
+    compiled: &'s JSONSchema,
+    schema: Cow<'s, Value>,
+    scope: &Url,
+    sources: &mut Vec<Source<'s>>,
+    references: &mut Vec<Url>,
+) -> Result<()> {
+    let draft = compiled.context.config.draft();
+    let scope = match id_of(draft, &schema) {
+        Some(id) => Url::options().base_url(Some(scope)).parse(id)?,
+        None => scope.clone(),
+    };
+    // `$ref` overrides any sibling keywords
+    if let Some(Value::String(reference)) = schema.get("$ref") {
+        let url = Url::options().base_url(Some(&scope)).parse(reference)?;
+        if !references.contains(&url) {
+            let (scope, resolved) = compiled
+                .resolver
+                .resolve_fragment(draft, &url, compiled.schema)
+                .map_err(ValidationError::into_owned)?;
+            references.push(url);
+            collect(compiled, resolved, &scope, sources, references)?;
+        }
+        return Ok(());
+    }
+    let all_of: Vec<Cow<'s, Value>> = match &schema {
+        Cow::Borrowed(schema) => all_of(schema).map(Cow::Borrowed).collect(),
+        Cow::Owned(schema) => all_of(schema).cloned().map(Cow::Owned).collect(),
+    };
+    sources.push(Source {
+        schema,
+        scope: scope.clone(),
+        patterns: OnceCell::new(),
+    });
+    for subschema in all_of {
+        collect(compiled, subschema, &scope, sources, references)?;
+    }
+    Ok(())
+}
+
+/// Subschemas in `allOf` of `schema`.
+fn all_of(schema: &Value) -> impl Iterator<Item = &Value> {
+    schema
+        .as_object()
+        .into_iter()
+        .flat_map(subschemas)
+        .filter_map(|(keyword, _, subschema)| Some(subschema).filter(|_| keyword == "allOf"))
+}
+
+/// Schemas that apply to the whole instance.
+pub(crate) fn root<'s>(compiled: &'s JSONSchema) -> Result<Vec<Source<'s>>> {
+    let mut sources = vec![];
+    collect(
+        compiled,
+        Cow::Borrowed(compiled.schema),
+        &compiled.context.scope,
+        &mut sources,
+        &mut vec![],
+    )?;
+    Ok(sources)
+}
+
+/// Schemas that apply to a child location of the instance.
+fn children<'s, F, I>(
+    compiled: &'s JSONSchema,
+    sources: &'s [Source<'_>],
+    get: F,
+) -> Result<Vec<Source<'s>>>
+where
+    F: Fn(&'s Source<'_>) -> I,
+    I: IntoIterator<Item = &'s Value>,
+{
+    let mut children = vec![];
+    let mut references = vec![];
+    for source in sources {
+        for subschema in get(source) {
+            collect(
+                compiled,
+                Cow::Borrowed(subschema),
+                &source.scope,
+                &mut children,
+                &mut references,
+            )?;
+        }
+    }
+    Ok(children)
+}
+
+/// Schemas that apply to the `name` property of an object: from `properties`, matching
+/// `patternProperties` and, if none of them match, `additionalProperties`.
+pub(crate) fn property<'s>(
+    compiled: &'s JSONSchema,
+    sources: &'s [Source<'_>],
+    name: &str,
+) -> Result<Vec<Source<'s>>> {
+    children(compiled, sources, |source| {
+        let schema = &*source.schema;
+        let mut matched = vec![];
+        if let Some(subschema) = schema
+            .get("properties")
+            .and_then(|properties| properties.get(name))
+        {
+            matched.push(subschema)
+        }
+        if let Some(Value::Object(patterns)) = schema.get("patternProperties") {
+            for (pattern, subschema) in source.patterns().iter().zip(patterns.values()) {
+                if matches!(pattern, Some(pattern) if pattern.is_match(name)) {
+                    matched.push(subschema)
+                }
+            }
+        }
+        if matched.is_empty() {
+            matched.extend(schema.get("additionalProperties"))
+        }
+        matched
+    })
+}
+
+/// Schemas that apply to the array item at `index`: from `items` and `additionalItems`.
+pub(crate) fn item<'s>(
+    compiled: &'s JSONSchema,
+    sources: &'s [Source<'_>],
+    index: usize,
+) -> Result<Vec<Source<'s>>> {
+    children(compiled, sources, |source| {
+        match source.schema.get("items") {
+            Some(Value::Array(items)) => items
+                .get(index)
+                .or_else(|| source.schema.get("additionalItems")),
+            items => items,
+        }
+    })
+}
@@ -112,7 +112,7 @@ pub(crate) trait Validate: Send + Sync + ToString {
 
     #[inline]
     fn validate_array<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         instance: &'a Value,
         instance_value: &'a [Value],
@@ -125,7 +125,7 @@ pub(crate) trait Validate: Send + Sync + ToString {
     }
     #[inline]
     fn validate_boolean<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         instance: &'a Value,
         instance_value: bool,
@@ -138,7 +138,7 @@ pub(crate) trait Validate: Send + Sync + ToString {
     }
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         instance: &'a Value,
         instance_value: &'a Map<String, Value>,
@@ -151,7 +151,7 @@ pub(crate) trait Validate: Send + Sync + ToString {
     }
     #[inline]
     fn validate_null<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         instance: &'a Value,
         _: (),
@@ -164,7 +164,7 @@ pub(crate) trait Validate: Send + Sync + ToString {
     }
     #[inline]
     fn validate_number<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         instance: &'a Value,
         instance_value: f64,
@@ -177,7 +177,7 @@ pub(crate) trait Validate: Send + Sync + ToString {
     }
     #[inline]
     fn validate_signed_integer<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         instance: &'a Value,
         instance_value: i64,
@@ -190,7 +190,7 @@ pub(crate) trait Validate: Send + Sync + ToString {
     }
     #[inline]
     fn validate_string<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         instance: &'a Value,
         instance_value: &'a str,
@@ -203,7 +203,7 @@ pub(crate) trait Validate: Send + Sync + ToString {
     }
     #[inline]
     fn validate_unsigned_integer<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         instance: &'a Value,
         instance_value: u64,
@@ -215,7 +215,7 @@ pub(crate) trait Validate: Send + Sync + ToString {
         }
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         match instance {
             Value::Array(instance_array) => self.validate_array(schema, instance, instance_array),
             Value::Bool(instance_boolean) => {
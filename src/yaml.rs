@@ -0,0 +1,266 @@
+//! YAML documents support.
+use crate::{
+    paths::{JSONPointer, PathChunk},
+    source::Location,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+use url::Url;
+use yaml_rust::{
+    parser::{Event, MarkedEventReceiver, Parser},
+    scanner::Marker,
+};
+
+impl From<Marker> for Location {
+    #[inline]
+    fn from(marker: Marker) -> Self {
+        Location {
+            line: marker.line(),
+            column: marker.col() + 1,
+        }
+    }
+}
+
+/// A parsed YAML document that remembers where each of its values is located.
+///
+/// ```rust
+/// # #[cfg(feature = "yaml")]
+/// # {
+/// use jsonschema::{JSONSchema, YamlDocument};
+/// use serde_json::json;
+///
+/// let schema = json!({"properties": {"replicas": {"type": "integer"}}});
+/// let document = YamlDocument::parse("kind: Deployment\nreplicas: three\n").unwrap();
+/// let compiled = JSONSchema::compile(&schema).unwrap();
+/// let result = compiled.validate(document.value());
+/// if let Err(errors) = result {
+///     for error in errors {
+///         let location = document.location(&error.instance_path()).unwrap();
+///         assert_eq!((location.line, location.column), (2, 11));
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct YamlDocument {
+    value: Value,
+    locations: HashMap<JSONPointer, Location>,
+}
+
+impl YamlDocument {
+    /// Parse the given YAML text. Only a single document is supported.
+    pub fn parse(text: &str) -> Result<YamlDocument, serde_yaml::Error> {
+        let value = serde_yaml::from_str(text)?;
+        let mut locator = Locator::default();
+        // The text is already accepted by `serde_yaml`, and locations are not essential anyway
+        let _ = Parser::new(text.chars()).load(&mut locator, false);
+        Ok(YamlDocument {
+            value,
+            locations: locator.locations,
+        })
+    }
+    /// The document content.
+    #[must_use]
+    #[inline]
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+    /// The document content.
+    #[must_use]
+    #[inline]
+    pub fn into_value(self) -> Value {
+        self.value
+    }
+    /// Where the value under `path` starts in the source text (e.g. `ValidationError::instance_path`).
+    #[must_use]
+    #[inline]
+    pub fn location(&self, path: &JSONPointer) -> Option<Location> {
+        self.locations.get(path).copied()
+    }
+}
+
+enum Node {
+    Scalar(String),
+    Alias,
+    Sequence,
+    Mapping,
+}
+
+enum Frame {
+    Sequence {
+        path: Vec<PathChunk>,
+        next: usize,
+    },
+    // `key` is `None` when the next node is a key. Complex and aliased keys can't be
+    // expressed in JSON Pointer, and their values are skipped.
+    // Block mappings are reported at the end of their first key, therefore `start` is moved
+    // to the first key if it is before
+    Mapping {
+        path: Vec<PathChunk>,
+        key: Option<Option<String>>,
+        start: Option<Location>,
+    },
+    // Nesting depth of a container that is not addressable
+    Skip(usize),
+}
+
+/// Records locations of all nodes by their JSON Pointers.
+#[derive(Default)]
+struct Locator {
+    stack: Vec<Frame>,
+    locations: HashMap<JSONPointer, Location>,
+}
+
+impl Locator {
+    fn start(&mut self, node: Node, location: Location) {
+        let is_container = matches!(node, Node::Sequence | Node::Mapping);
+        let path = match self.stack.last_mut() {
+            None => Some(Vec::new()),
+            Some(Frame::Skip(depth)) => {
+                if is_container {
+                    *depth += 1;
+                }
+                return;
+            }
+            Some(Frame::Sequence { path, next }) => {
+                let mut path = path.clone();
+                path.push(PathChunk::Index(*next));
+                *next += 1;
+                Some(path)
+            }
+            Some(Frame::Mapping { path, key, start }) => match key.take() {
+                None => {
+                    if let Some(start) = start.take() {
+                        if location < start {
+                            self.locations
+                                .insert(JSONPointer::from(path.clone()), location);
+                        }
+                    }
+                    *key = Some(match node {
+                        Node::Scalar(ref name) => Some(name.clone()),
+                        _ => None,
+                    });
+                    None
+                }
+                Some(Some(name)) => {
+                    let mut path = path.clone();
+                    path.push(PathChunk::Property(name));
+                    Some(path)
+                }
+                Some(None) => None,
+            },
+        };
+        match (path, node) {
+            (Some(path), node) => {
+                self.locations
+                    .insert(JSONPointer::from(path.clone()), location);
+                match node {
+                    Node::Sequence => self.stack.push(Frame::Sequence { path, next: 0 }),
+                    Node::Mapping => self.stack.push(Frame::Mapping {
+                        path,
+                        key: None,
+                        start: Some(location),
+                    }),
+                    Node::Scalar(_) | Node::Alias => {}
+                }
+            }
+            (None, _) if is_container => self.stack.push(Frame::Skip(1)),
+            (None, _) => {}
+        }
+    }
+
+    fn end(&mut self) {
+        if let Some(Frame::Skip(depth)) = self.stack.last_mut() {
+            *depth -= 1;
+            if *depth > 0 {
+                return;
+            }
+        }
+        self.stack.pop();
+    }
+}
+
+impl MarkedEventReceiver for Locator {
+    fn on_event(&mut self, event: Event, marker: Marker) {
+        let node = match event {
+            Event::Scalar(value, ..) => Node::Scalar(value),
+            Event::Alias(_) => Node::Alias,
+            Event::SequenceStart(_) => Node::Sequence,
+            Event::MappingStart(_) => Node::Mapping,
+            Event::SequenceEnd | Event::MappingEnd => return self.end(),
+            _ => return,
+        };
+        self.start(node, Location::from(marker))
+    }
+}
+
+/// Whether a remote document should be parsed as YAML.
+/// The media type takes precedence, otherwise the extension is used.
+pub(crate) fn is_yaml(url: &Url, media_type: Option<&str>) -> bool {
+    let essence = media_type
+        .and_then(|media_type| media_type.split(';').next())
+        .map(str::trim);
+    match essence {
+        Some("application/yaml")
+        | Some("application/x-yaml")
+        | Some("text/yaml")
+        | Some("text/x-yaml") => true,
+        Some("application/json") | Some("application/schema+json") => false,
+        _ => url.path().ends_with(".yaml") || url.path().ends_with(".yml"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_yaml, YamlDocument};
+    use crate::{
+        paths::{JSONPointer, PathChunk},
+        source::Location,
+    };
+    use serde_json::json;
+    use test_case::test_case;
+    use url::Url;
+
+    const DOCUMENT: &str = "\
+name: app
+ports:
+  - 80
+  - port: 443
+    protocol: TCP
+flow: {a: [1, 2]}
+";
+
+    #[test_case(vec![], 1, 1)]
+    #[test_case(vec!["name".into()], 1, 7)]
+    #[test_case(vec!["ports".into(), 0.into()], 3, 5)]
+    #[test_case(vec!["ports".into(), 1.into(), "protocol".into()], 5, 15)]
+    #[test_case(vec!["ports".into(), 1.into()], 4, 5)]
+    #[test_case(vec!["flow".into()], 6, 7)]
+    #[test_case(vec!["flow".into(), "a".into(), 1.into()], 6, 15)]
+    fn locations(path: Vec<PathChunk>, line: usize, column: usize) {
+        let document = YamlDocument::parse(DOCUMENT).unwrap();
+        assert_eq!(
+            document.location(&JSONPointer::from(path)),
+            Some(Location { line, column })
+        );
+    }
+
+    #[test]
+    fn value() {
+        let document = YamlDocument::parse("a: [1, true, null]").unwrap();
+        assert_eq!(document.into_value(), json!({"a": [1, true, null]}));
+    }
+
+    #[test_case("http://example.com/schema.yaml", None, true)]
+    #[test_case("http://example.com/schema.yml", Some("text/plain"), true)]
+    #[test_case(
+        "http://example.com/schema",
+        Some("application/yaml; charset=utf-8"),
+        true
+    )]
+    #[test_case("http://example.com/schema.yaml", Some("application/json"), false)]
+    #[test_case("http://example.com/schema.json", None, false)]
+    fn yaml_detection(url: &str, media_type: Option<&str>, expected: bool) {
+        assert_eq!(is_yaml(&Url::parse(url).unwrap(), media_type), expected)
+    }
+}
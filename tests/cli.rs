@@ -0,0 +1,107 @@
+#![cfg(feature = "cli")]
+use std::{
+    fs,
+    path::PathBuf,
+    process::{Command, Output},
+};
+use tempfile::TempDir;
+
+/// Test files are removed together with `dir`.
+fn write_file(dir: &TempDir, name: &str, content: &str) -> PathBuf {
+    let path = dir.path().join(name);
+    fs::write(&path, content).expect("Failed to write a test file");
+    path
+}
+
+fn temp_dir() -> TempDir {
+    TempDir::new().expect("Failed to create a temporary directory")
+}
+
+fn run(args: &[&PathBuf], extra: &[&str]) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_jsonschema"));
+    command.arg(args[0]);
+    for instance in &args[1..] {
+        command.arg("-i").arg(instance);
+    }
+    command
+        .args(extra)
+        .output()
+        .expect("Failed to run the binary")
+}
+
+const SCHEMA: &str = r#"{"properties": {"name": {"type": "string"}}, "required": ["name"]}"#;
+
+#[test]
+fn valid_instance() {
+    let dir = temp_dir();
+    let schema = write_file(&dir, "valid-schema.json", SCHEMA);
+    let instance = write_file(&dir, "valid-instance.json", r#"{"name": "foo"}"#);
+    let output = run(&[&schema, &instance], &[]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("- VALID"));
+}
+
+#[test]
+fn invalid_instance() {
+    let dir = temp_dir();
+    let schema = write_file(&dir, "invalid-schema.json", SCHEMA);
+    let instance = write_file(&dir, "invalid-instance.json", r#"{"name": 42}"#);
+    let output = run(&[&schema, &instance], &[]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("- INVALID"));
+    assert!(stdout.contains("at '/name' (line 1, column 10): '42' is not of type 'string'"));
+}
+
+#[test]
+fn json_lines() {
+    let dir = temp_dir();
+    let schema = write_file(&dir, "jsonl-schema.json", SCHEMA);
+    let instance = write_file(&dir, "instances.jsonl", "{\"name\": \"foo\"}\n\n{}\n");
+    let output = run(&[&schema, &instance], &[]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("instances.jsonl:1 - VALID"));
+    assert!(stdout.contains("instances.jsonl:3 - INVALID"));
+}
+
+#[test]
+fn draft_option() {
+    let dir = temp_dir();
+    // `exclusiveMinimum` is a boolean modifier of `minimum` in Draft 4
+    let schema = write_file(
+        &dir,
+        "draft-schema.json",
+        r#"{"minimum": 5, "exclusiveMinimum": true}"#,
+    );
+    let instance = write_file(&dir, "draft-instance.json", "5");
+    let output = run(&[&schema, &instance], &["--draft", "4"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("- INVALID"));
+}
+
+#[test]
+fn invalid_schema() {
+    let dir = temp_dir();
+    let schema = write_file(&dir, "broken-schema.json", r#"{"type": 42}"#);
+    let instance = write_file(&dir, "broken-instance.json", "{}");
+    let output = run(&[&schema, &instance], &[]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Schema compilation error"));
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn yaml_documents() {
+    let dir = temp_dir();
+    let schema = write_file(
+        &dir,
+        "schema.yaml",
+        "properties:\n  name:\n    type: string\n",
+    );
+    let instance = write_file(&dir, "instance.yml", "kind: Item\nname: 42\n");
+    let output = run(&[&schema, &instance], &[]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout)
+        .contains("at '/name' (line 2, column 7): '42' is not of type 'string'"));
+}